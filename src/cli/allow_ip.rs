@@ -3,14 +3,20 @@ use crate::cli::util::{
     cluster_from_conn_str, cluster_identifiers_from, find_org_id, find_project_id,
     get_active_cluster,
 };
-use crate::state::State;
+use crate::client::cloud::lookup_public_ip;
+use crate::client::cloud_json::AllowedCidrRequest;
+use crate::state::{RemoteCapellaOrganization, State};
+use chrono::{SecondsFormat, Utc};
 use log::{debug, info};
 use nu_engine::command_prelude::Call;
 use nu_engine::CallExt;
 use nu_protocol::engine::{Command, EngineState, Stack};
 use nu_protocol::shell_error::generic::GenericError;
-use nu_protocol::{Category, PipelineData, ShellError, Signature, SyntaxShape, Value};
+use nu_protocol::{
+    Category, Example, PipelineData, ShellError, Signals, Signature, Span, SyntaxShape, Value,
+};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct AllowIP {
@@ -42,12 +48,44 @@ impl Command for crate::cli::AllowIP {
                 SyntaxShape::String,
                 "ip address to allow access to the cluster",
             )
+            .switch(
+                "current",
+                "allow the public IP address of this machine",
+                None,
+            )
+            .named(
+                "comment",
+                SyntaxShape::String,
+                "a comment describing the address",
+                None,
+            )
+            .named(
+                "expiry",
+                SyntaxShape::Duration,
+                "how long the address is allowed for, leave empty to allow it permanently",
+                None,
+            )
     }
 
     fn description(&self) -> &str {
         "Adds IP address to allowlist on a Capella cluster"
     }
 
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Allow an address range",
+                example: "allow ip 10.0.0.0/16 --comment office",
+                result: None,
+            },
+            Example {
+                description: "Allow the public IP address of this machine for the rest of the day",
+                example: "allow ip --current --expiry 8hr",
+                result: None,
+            },
+        ]
+    }
+
     fn run(
         &self,
         engine_state: &EngineState,
@@ -70,11 +108,31 @@ fn allow_ip(
     let signals = engine_state.signals().clone();
 
     let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+    let comment: Option<String> = call.get_flag(engine_state, stack, "comment")?;
+    let expiry: Option<Duration> = call.get_flag(engine_state, stack, "expiry")?;
+    let current = call.has_flag(engine_state, stack, "current")?;
     let guard = state.lock().unwrap();
 
     debug!("Running allow_ip");
 
+    let expires_at = match expiry {
+        Some(expiry) => {
+            let expiry = chrono::Duration::from_std(expiry).map_err(|e| {
+                ShellError::Generic(GenericError::new_internal(
+                    format!("Invalid expiry: {}", e),
+                    "",
+                ))
+            })?;
+            Some((Utc::now() + expiry).to_rfc3339_opts(SecondsFormat::Millis, true))
+        }
+        None => None,
+    };
+
     let ip_address = match input.into_value(span)? {
+        _ if current => {
+            let org = guard.named_or_active_org(None)?;
+            current_ip_address(org, signals.clone(), span)?
+        }
         Value::String { val, .. } => format_ip_address(val),
         Value::Nothing { .. } => {
             if let Some(address) = call.opt(engine_state, stack, 0)? {
@@ -94,11 +152,15 @@ fn allow_ip(
         }
     };
 
+    let allowed = AllowedCidrRequest::new(ip_address, comment, expires_at);
+
     for identifier in cluster_identifiers {
         let cluster = get_active_cluster(identifier.clone(), &guard, span)?;
 
         let org = guard.named_or_active_org(cluster.capella_org())?;
-        let client = org.client();
+        let client = org
+            .client()
+            .map_err(|e| client_error_to_shell_error(e, span))?;
 
         let org_id = find_org_id(signals.clone(), &client, span)?;
 
@@ -125,7 +187,7 @@ fn allow_ip(
                 org_id,
                 project_id,
                 json_cluster.id(),
-                ip_address.clone(),
+                &allowed,
                 signals.clone(),
             )
             .map_err(|e| client_error_to_shell_error(e, span))?;
@@ -134,7 +196,23 @@ fn allow_ip(
     Ok(PipelineData::empty())
 }
 
-fn format_ip_address(ip_address: String) -> String {
+pub(crate) fn current_ip_address(
+    org: &RemoteCapellaOrganization,
+    signals: Signals,
+    span: Span,
+) -> Result<String, ShellError> {
+    let address = lookup_public_ip(
+        &org.ip_lookup_endpoint(),
+        org.timeout(),
+        org.proxy(),
+        signals,
+    )
+    .map_err(|e| client_error_to_shell_error(e, span))?;
+    info!("Using public IP address {}", address);
+    Ok(format_ip_address(address.to_string()))
+}
+
+pub(crate) fn format_ip_address(ip_address: String) -> String {
     if !ip_address.contains('/') {
         info!("IP address supplied without a subnet mask, defaulting to '/32'");
         format!("{}/32", ip_address)
@@ -0,0 +1,176 @@
+use crate::cli::allow_ip::{current_ip_address, format_ip_address};
+use crate::cli::util::{
+    cluster_from_conn_str, cluster_identifiers_from, find_org_id, find_project_id,
+    get_active_cluster,
+};
+use crate::cli::{client_error_to_shell_error, generic_error};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct AllowIPDrop {
+    state: Arc<Mutex<State>>,
+}
+
+impl AllowIPDrop {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AllowIPDrop {
+    fn name(&self) -> &str {
+        "allow ip drop"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("allow ip drop")
+            .category(Category::Custom("couchbase".to_string()))
+            .optional(
+                "address",
+                SyntaxShape::String,
+                "the ip address or id of the allowlist entry to remove",
+            )
+            .switch(
+                "current",
+                "remove the public IP address of this machine",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+    }
+
+    fn description(&self) -> &str {
+        "Removes an IP address from the allowlist of a Capella cluster"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Remove an address range",
+                example: "allow ip drop 10.0.0.0/16",
+                result: None,
+            },
+            Example {
+                description: "Remove the public IP address of this machine",
+                example: "allow ip drop --current",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        allow_ip_drop(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn allow_ip_drop(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+    let address: Option<String> = call.opt(engine_state, stack, 0)?;
+    let current = call.has_flag(engine_state, stack, "current")?;
+    let guard = state.lock().unwrap();
+
+    let address = match address {
+        _ if current => {
+            let org = guard.named_or_active_org(None)?;
+            current_ip_address(org, signals.clone(), span)?
+        }
+        Some(address) => address,
+        None => {
+            return Err(generic_error(
+                "No IP address provided",
+                "Provide the address or id of the entry to remove, or use --current".to_string(),
+                span,
+            ));
+        }
+    };
+
+    debug!("Running allow ip drop for {}", &address);
+
+    for identifier in cluster_identifiers {
+        let cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+
+        let org = guard.named_or_active_org(cluster.capella_org())?;
+        let client = org
+            .client()
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+
+        let org_id = find_org_id(signals.clone(), &client, span)?;
+
+        let project_id = find_project_id(
+            signals.clone(),
+            guard.named_or_active_project(cluster.project())?,
+            &client,
+            span,
+            org_id.clone(),
+        )?;
+
+        let json_cluster = cluster_from_conn_str(
+            identifier.clone(),
+            signals.clone(),
+            cluster.hostnames().clone(),
+            &client,
+            span,
+            org_id.clone(),
+            project_id.clone(),
+        )?;
+
+        let allowed = client
+            .list_allowed_ip_addresses(
+                org_id.clone(),
+                project_id.clone(),
+                json_cluster.id(),
+                signals.clone(),
+            )
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+
+        let cidr = format_ip_address(address.clone());
+        let entry = allowed
+            .data()
+            .iter()
+            .find(|a| a.id() == address || a.cidr() == cidr)
+            .ok_or_else(|| {
+                generic_error(
+                    format!("{} is not on the allowlist of {}", address, identifier),
+                    "Use `allow ip list` to see the allowed addresses".to_string(),
+                    span,
+                )
+            })?;
+
+        client
+            .drop_allowed_ip_address(
+                org_id,
+                project_id,
+                json_cluster.id(),
+                entry.id(),
+                signals.clone(),
+            )
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+    }
+
+    Ok(PipelineData::empty())
+}
@@ -0,0 +1,122 @@
+use crate::cli::client_error_to_shell_error;
+use crate::cli::util::{
+    cluster_from_conn_str, cluster_identifiers_from, find_org_id, find_project_id,
+    get_active_cluster, NuValueMap,
+};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct AllowIPList {
+    state: Arc<Mutex<State>>,
+}
+
+impl AllowIPList {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AllowIPList {
+    fn name(&self) -> &str {
+        "allow ip list"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("allow ip list")
+            .category(Category::Custom("couchbase".to_string()))
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+    }
+
+    fn description(&self) -> &str {
+        "Lists the IP addresses on the allowlist of a Capella cluster"
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        allow_ip_list(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn allow_ip_list(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+    let guard = state.lock().unwrap();
+
+    debug!("Running allow ip list");
+
+    let mut results = vec![];
+    for identifier in cluster_identifiers {
+        let cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+
+        let org = guard.named_or_active_org(cluster.capella_org())?;
+        let client = org
+            .client()
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+
+        let org_id = find_org_id(signals.clone(), &client, span)?;
+
+        let project_id = find_project_id(
+            signals.clone(),
+            guard.named_or_active_project(cluster.project())?,
+            &client,
+            span,
+            org_id.clone(),
+        )?;
+
+        let json_cluster = cluster_from_conn_str(
+            identifier.clone(),
+            signals.clone(),
+            cluster.hostnames().clone(),
+            &client,
+            span,
+            org_id.clone(),
+            project_id.clone(),
+        )?;
+
+        let allowed = client
+            .list_allowed_ip_addresses(org_id, project_id, json_cluster.id(), signals.clone())
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+
+        for address in allowed.data() {
+            let mut collected = NuValueMap::default();
+            collected.add_string("cluster", identifier.clone(), span);
+            collected.add_string("id", address.id(), span);
+            collected.add_string("address", address.cidr(), span);
+            collected.add_string("comment", address.comment(), span);
+            collected.add_string("type", address.cidr_type(), span);
+            collected.add_string("status", address.status(), span);
+            match address.expires_at() {
+                Some(expires_at) => collected.add_string("expires at", expires_at, span),
+                None => collected.add("expires at", Value::nothing(span)),
+            }
+            results.push(collected.into_value(span));
+        }
+    }
+
+    Ok(Value::list(results, span).into_pipeline_data())
+}
@@ -1,7 +1,8 @@
-use crate::cli::analytics_common::send_analytics_query;
+use crate::cli::analytics_common::{analytics_options_from_args, send_analytics_query};
 use crate::cli::util::{
     cluster_identifiers_from, convert_json_value_to_nu_value, get_active_cluster,
 };
+use crate::client::http_handler::HTTP_RUNTIME;
 use crate::state::State;
 use futures::StreamExt;
 use log::debug;
@@ -10,8 +11,8 @@ use nu_engine::CallExt;
 use nu_protocol::engine::{Command, EngineState, Stack};
 use nu_protocol::shell_error::generic::GenericError;
 use nu_protocol::{
-    Category, IntoPipelineData, ListStream, PipelineData, ShellError, Signature, Span, SyntaxShape,
-    Value,
+    Category, Example, IntoPipelineData, ListStream, PipelineData, ShellError, Signature, Span,
+    SyntaxShape, Value,
 };
 use std::str::from_utf8;
 use std::sync::{Arc, Mutex};
@@ -51,6 +52,30 @@ impl Command for Analytics {
                 "the scope to query against",
                 None,
             )
+            .named(
+                "params",
+                SyntaxShape::Any,
+                "named or positional parameters for the query",
+                None,
+            )
+            .named(
+                "scan-consistency",
+                SyntaxShape::String,
+                "the scan consistency of the query: not_bounded or request_plus",
+                None,
+            )
+            .switch("readonly", "reject the query if it would modify data", None)
+            .switch(
+                "priority",
+                "run the query ahead of other requests on the service",
+                None,
+            )
+            .named(
+                "client-context-id",
+                SyntaxShape::String,
+                "the id used to identify the query in logs and active requests",
+                None,
+            )
             .switch("with-meta", "Includes related metadata in the result", None)
             .named(
                 "clusters",
@@ -65,6 +90,21 @@ impl Command for Analytics {
         "Performs an analytics query"
     }
 
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Pass query parameters as an object",
+                example: "analytics \"SELECT airportname FROM `travel-sample`.inventory.airport WHERE country = $country\" --params {country: France}",
+                result: None,
+            },
+            Example {
+                description: "Run a query that sees all prior mutations, ahead of other requests",
+                example: "analytics \"SELECT COUNT(*) FROM `travel-sample`.inventory.airport\" --scan-consistency request_plus --priority",
+                result: None,
+            },
+        ]
+    }
+
     fn run(
         &self,
         engine_state: &EngineState,
@@ -79,8 +119,8 @@ impl Command for Analytics {
 pub struct AnalyticsStream {
     span: Span,
     streams: StreamMap<String, RawJsonRowStreamer>,
-    // This allows us to extend the lifetime of the runtime used to create the streams longer than
-    // run(), else we panic when reading the streams
+    // The streams are read on the runtime which sent the requests, as their connections are
+    // driven by it.
     rt: Arc<Runtime>,
 }
 
@@ -128,12 +168,13 @@ fn run(
 
     let scope: Option<String> = call.get_flag(engine_state, stack, "scope")?;
     let with_meta = call.has_flag(engine_state, stack, "with-meta")?;
+    let options = analytics_options_from_args(engine_state, stack, call)?;
 
     debug!("Running analytics query {}", &statement);
 
     let mut results: Vec<Value> = vec![];
     let mut streams = StreamMap::new();
-    let rt = Arc::new(Runtime::new().unwrap());
+    let rt = HTTP_RUNTIME.clone();
     for identifier in cluster_identifiers.clone() {
         let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
         let bucket = call
@@ -145,9 +186,9 @@ fn run(
             active_cluster,
             maybe_scope,
             statement.clone(),
+            options.clone(),
             signals.clone(),
             span,
-            rt.clone(),
         )?;
 
         let json_stream = JsonRowStream::new(resp.stream());
@@ -12,7 +12,6 @@ use nu_protocol::{
 };
 
 use std::sync::{Arc, Mutex};
-use tokio::runtime::Runtime;
 
 #[derive(Clone)]
 pub struct AnalyticsBuckets {
@@ -78,14 +77,8 @@ fn dataverses(
     let mut results: Vec<Value> = vec![];
     for identifier in cluster_identifiers {
         let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
-        let resp = send_analytics_query(
-            active_cluster,
-            None,
-            statement,
-            signals.clone(),
-            span,
-            Arc::new(Runtime::new().unwrap()),
-        )?;
+        let resp =
+            send_analytics_query(active_cluster, None, statement, None, signals.clone(), span)?;
 
         results.extend(read_analytics_response(
             identifier.clone(),
@@ -1,38 +1,94 @@
-use crate::cli::util::{convert_row_to_nu_value, duration_to_golang_string};
+use crate::cli::util::{
+    convert_nu_value_to_json_value, convert_row_to_nu_value, duration_to_golang_string,
+};
 use crate::cli::{
-    analytics_error, client_error_to_shell_error, deserialize_error,
-    insufficient_columnar_permissions_error, malformed_response_error,
+    analytics_error, client_error_to_shell_error, deserialize_error, generic_error,
+    insufficient_columnar_permissions_error, malformed_response_error, serialize_error,
     unexpected_status_code_error, AnalyticsErrorReason,
 };
 use crate::client::http_handler::HttpStreamResponse;
-use crate::client::AnalyticsQueryRequest;
+use crate::client::{AnalyticsQueryOptions, AnalyticsQueryRequest};
 use crate::remote_cluster::RemoteCluster;
-use nu_protocol::{ShellError, Signals, Span, Value};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{EngineState, Stack};
+use nu_protocol::{Record, ShellError, Signals, Span, Value};
 use std::ops::Add;
-use std::sync::Arc;
-use tokio::runtime::Runtime;
 use tokio::time::Instant;
 
+// The scan consistency levels accepted by the analytics service.
+const SCAN_CONSISTENCIES: [&str; 2] = ["not_bounded", "request_plus"];
+
+// Reads the query option flags shared by analytics and columnar query.
+pub fn analytics_options_from_args(
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+) -> Result<AnalyticsQueryOptions, ShellError> {
+    let span = call.head;
+
+    let params: Option<serde_json::Value> =
+        match call.get_flag::<Value>(engine_state, stack, "params")? {
+            Some(p) => match p {
+                Value::Record { .. } | Value::List { .. } => {
+                    Some(convert_nu_value_to_json_value(&p, span)?)
+                }
+                _ => {
+                    return Err(generic_error(
+                        "Parameters must be a list or JSON object",
+                        "Run the command with --help to see examples".to_string(),
+                        span,
+                    ));
+                }
+            },
+            None => None,
+        };
+
+    let scan_consistency: Option<String> =
+        call.get_flag(engine_state, stack, "scan-consistency")?;
+    if let Some(consistency) = &scan_consistency {
+        if !SCAN_CONSISTENCIES.contains(&consistency.as_str()) {
+            return Err(generic_error(
+                format!("Unknown scan consistency {}", consistency),
+                format!(
+                    "The scan consistency must be one of {}",
+                    SCAN_CONSISTENCIES.join(", ")
+                ),
+                span,
+            ));
+        }
+    }
+
+    Ok(AnalyticsQueryOptions::new(
+        params,
+        scan_consistency,
+        call.has_flag(engine_state, stack, "readonly")?,
+        call.has_flag(engine_state, stack, "priority")?,
+        call.get_flag::<String>(engine_state, stack, "client-context-id")?,
+    ))
+}
+
 pub fn send_columnar_query(
     active_cluster: &RemoteCluster,
     scope: impl Into<Option<(String, String)>>,
     statement: impl Into<String>,
+    options: impl Into<Option<AnalyticsQueryOptions>>,
     signals: Signals,
     span: Span,
-    rt: Arc<Runtime>,
 ) -> Result<HttpStreamResponse, ShellError> {
     let response = active_cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .analytics_query_request(
             AnalyticsQueryRequest::Execute {
                 statement: statement.into(),
                 scope: scope.into(),
                 timeout: duration_to_golang_string(active_cluster.timeouts().analytics_timeout()),
+                options: options.into().unwrap_or_default(),
             },
             Instant::now().add(active_cluster.timeouts().analytics_timeout()),
             signals.clone(),
-            rt.clone(),
         )
         .map_err(|e| client_error_to_shell_error(e, span))?;
 
@@ -51,22 +107,23 @@ pub fn send_analytics_query(
     active_cluster: &RemoteCluster,
     scope: impl Into<Option<(String, String)>>,
     statement: impl Into<String>,
+    options: impl Into<Option<AnalyticsQueryOptions>>,
     signals: Signals,
     span: Span,
-    rt: Arc<Runtime>,
 ) -> Result<HttpStreamResponse, ShellError> {
     let response = active_cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .analytics_query_request(
             AnalyticsQueryRequest::Execute {
                 statement: statement.into(),
                 scope: scope.into(),
                 timeout: duration_to_golang_string(active_cluster.timeouts().analytics_timeout()),
+                options: options.into().unwrap_or_default(),
             },
             Instant::now().add(active_cluster.timeouts().analytics_timeout()),
             signals.clone(),
-            rt.clone(),
         )
         .map_err(|e| client_error_to_shell_error(e, span))?;
 
@@ -170,3 +227,78 @@ pub fn read_analytics_response(
 
     Ok(results)
 }
+
+// The link types accepted by the analytics link REST API.
+const LINK_TYPES: [&str; 4] = ["couchbase", "s3", "azureblob", "gcs"];
+
+// Builds the form encoded body for creating or altering a link, the settings are passed through
+// as is so they must use the names expected by the REST API, e.g. accessKeyId for S3 links.
+pub fn link_payload(
+    link_type: String,
+    settings: &Record,
+    span: Span,
+) -> Result<String, ShellError> {
+    let link_type = link_type.to_lowercase();
+    if !LINK_TYPES.contains(&link_type.as_str()) {
+        return Err(generic_error(
+            format!("Unknown link type {}", link_type),
+            format!("The link type must be one of {}", LINK_TYPES.join(", ")),
+            span,
+        ));
+    }
+
+    let mut form = vec![("type".to_string(), link_type)];
+    for (k, v) in settings.iter() {
+        let val = match v {
+            Value::String { val, .. } => val.clone(),
+            Value::Int { val, .. } => val.to_string(),
+            Value::Float { val, .. } => val.to_string(),
+            Value::Bool { val, .. } => val.to_string(),
+            _ => {
+                return Err(generic_error(
+                    format!("Unsupported value for link setting {}", k),
+                    "Link settings must be strings, numbers or booleans".to_string(),
+                    span,
+                ));
+            }
+        };
+        form.push((k.clone(), val));
+    }
+
+    serde_urlencoded::to_string(&form).map_err(|e| serialize_error(e.to_string(), span))
+}
+
+pub fn send_analytics_link_request(
+    active_cluster: &RemoteCluster,
+    request: AnalyticsQueryRequest,
+    signals: Signals,
+    span: Span,
+) -> Result<(), ShellError> {
+    let response = active_cluster
+        .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
+        .http_client()
+        .analytics_query_request(
+            request,
+            Instant::now().add(active_cluster.timeouts().management_timeout()),
+            signals,
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    match response.status() {
+        200 => Ok(()),
+        _ => Err(unexpected_status_code_error(
+            response.status(),
+            response.content()?,
+            span,
+        )),
+    }
+}
+
+// Multi part dataverse names are given dot separated, e.g. travel.inventory, but links take them
+// slash separated, which the request encodes as a single segment of the path.
+pub fn link_scope(dataverse: Option<String>) -> String {
+    dataverse
+        .map(|d| d.split('.').collect::<Vec<&str>>().join("/"))
+        .unwrap_or_else(|| "Default".to_string())
+}
@@ -9,7 +9,6 @@ use nu_protocol::{
     Category, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
 };
 use std::sync::{Arc, Mutex};
-use tokio::runtime::Runtime;
 
 #[derive(Clone)]
 pub struct AnalyticsDatasets {
@@ -75,14 +74,8 @@ fn datasets(
     let mut results: Vec<Value> = vec![];
     for identifier in cluster_identifiers {
         let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
-        let resp = send_analytics_query(
-            active_cluster,
-            None,
-            statement,
-            signals.clone(),
-            span,
-            Arc::new(Runtime::new().unwrap()),
-        )?;
+        let resp =
+            send_analytics_query(active_cluster, None, statement, None, signals.clone(), span)?;
 
         results.extend(read_analytics_response(
             identifier.clone(),
@@ -0,0 +1,155 @@
+use crate::cli::analytics_common::{read_analytics_response, send_analytics_query};
+use crate::cli::util::{cluster_identifiers_from, get_active_cluster, string_literal};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct AnalyticsDatasetsCreateExternal {
+    state: Arc<Mutex<State>>,
+}
+
+impl AnalyticsDatasetsCreateExternal {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AnalyticsDatasetsCreateExternal {
+    fn name(&self) -> &str {
+        "analytics datasets create-external"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("analytics datasets create-external")
+            .required("name", SyntaxShape::String, "the name of the dataset")
+            .required(
+                "link",
+                SyntaxShape::String,
+                "the name of the external link to read from",
+            )
+            .required(
+                "container",
+                SyntaxShape::String,
+                "the bucket or container holding the data",
+            )
+            .named(
+                "path",
+                SyntaxShape::String,
+                "the path of the data within the container",
+                None,
+            )
+            .named(
+                "format",
+                SyntaxShape::String,
+                "the format of the data: json, csv, tsv or parquet (default = json)",
+                None,
+            )
+            .named(
+                "dataverse",
+                SyntaxShape::String,
+                "the dataverse to create the dataset in (default = Default)",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Creates an analytics dataset over data held behind an external link"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description:
+                "Create a dataset over the json files under the reviews path of an S3 bucket",
+            example: "analytics datasets create-external reviews myS3Link my-bucket --path reviews",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        datasets_create_external(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn datasets_create_external(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let signals = engine_state.signals().clone();
+    let span = call.head;
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let link: String = call.req(engine_state, stack, 1)?;
+    let container: String = call.req(engine_state, stack, 2)?;
+    let path: Option<String> = call.get_flag(engine_state, stack, "path")?;
+    let format = call
+        .get_flag(engine_state, stack, "format")?
+        .unwrap_or_else(|| "json".to_string());
+    let dataverse: Option<String> = call.get_flag(engine_state, stack, "dataverse")?;
+
+    let dataset = match dataverse {
+        Some(dv) => format!(
+            "{}.`{}`",
+            dv.split('.')
+                .map(|part| format!("`{}`", part))
+                .collect::<Vec<String>>()
+                .join("."),
+            name
+        ),
+        None => format!("`{}`", name),
+    };
+
+    let mut statement = format!(
+        "CREATE EXTERNAL DATASET {} ON `{}` AT `{}`",
+        dataset, container, link
+    );
+    if let Some(p) = path {
+        statement = format!("{} USING {}", statement, string_literal(&p));
+    }
+    statement = format!(
+        "{} WITH {{\"format\": {}}}",
+        statement,
+        string_literal(&format)
+    );
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+
+    let guard = state.lock().unwrap();
+    debug!("Running analytics query {}", &statement);
+
+    for identifier in cluster_identifiers {
+        let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+        let resp = send_analytics_query(
+            active_cluster,
+            None,
+            statement.clone(),
+            None,
+            signals.clone(),
+            span,
+        )?;
+
+        read_analytics_response(identifier.clone(), resp, span, false, true)?;
+    }
+
+    Ok(PipelineData::empty())
+}
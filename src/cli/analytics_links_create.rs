@@ -0,0 +1,117 @@
+use crate::cli::analytics_common::{link_payload, link_scope, send_analytics_link_request};
+use crate::cli::util::{cluster_identifiers_from, get_active_cluster};
+use crate::client::AnalyticsQueryRequest;
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, Record, ShellError, Signature, SyntaxShape};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct AnalyticsLinksCreate {
+    state: Arc<Mutex<State>>,
+}
+
+impl AnalyticsLinksCreate {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AnalyticsLinksCreate {
+    fn name(&self) -> &str {
+        "analytics links create"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("analytics links create")
+            .required("name", SyntaxShape::String, "the name of the link")
+            .required(
+                "type",
+                SyntaxShape::String,
+                "the type of link: couchbase, s3, azureblob or gcs",
+            )
+            .required(
+                "settings",
+                SyntaxShape::Record(vec![]),
+                "the link settings, using the names from the analytics links REST API",
+            )
+            .named(
+                "dataverse",
+                SyntaxShape::String,
+                "the dataverse of the link (default = Default)",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Creates an analytics link"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Create an S3 link in the Default dataverse",
+            example: "analytics links create myS3Link s3 {accessKeyId: ACCESSKEY secretAccessKey: SECRETKEY region: us-east-1}",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        links_create(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn links_create(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let link_type: String = call.req(engine_state, stack, 1)?;
+    let settings: Record = call.req(engine_state, stack, 2)?;
+    let scope = link_scope(call.get_flag(engine_state, stack, "dataverse")?);
+
+    let payload = link_payload(link_type, &settings, span)?;
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+    let guard = state.lock().unwrap();
+
+    for identifier in cluster_identifiers {
+        let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+
+        debug!("Running analytics links create for {} in {}", &name, &scope);
+
+        send_analytics_link_request(
+            active_cluster,
+            AnalyticsQueryRequest::CreateLink {
+                scope: scope.clone(),
+                name: name.clone(),
+                payload: payload.clone(),
+            },
+            signals.clone(),
+            span,
+        )?;
+    }
+
+    Ok(PipelineData::empty())
+}
@@ -0,0 +1,102 @@
+use crate::cli::analytics_common::{link_scope, send_analytics_link_request};
+use crate::cli::util::{cluster_identifiers_from, get_active_cluster};
+use crate::client::AnalyticsQueryRequest;
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct AnalyticsLinksDrop {
+    state: Arc<Mutex<State>>,
+}
+
+impl AnalyticsLinksDrop {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AnalyticsLinksDrop {
+    fn name(&self) -> &str {
+        "analytics links drop"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("analytics links drop")
+            .required("name", SyntaxShape::String, "the name of the link")
+            .named(
+                "dataverse",
+                SyntaxShape::String,
+                "the dataverse of the link (default = Default)",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Drops an analytics link"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Drop a link from the travel.inventory dataverse",
+            example: "analytics links drop myS3Link --dataverse travel.inventory",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        links_drop(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn links_drop(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let scope = link_scope(call.get_flag(engine_state, stack, "dataverse")?);
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+    let guard = state.lock().unwrap();
+
+    for identifier in cluster_identifiers {
+        let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+
+        debug!("Running analytics links drop for {} in {}", &name, &scope);
+
+        send_analytics_link_request(
+            active_cluster,
+            AnalyticsQueryRequest::DropLink {
+                scope: scope.clone(),
+                name: name.clone(),
+            },
+            signals.clone(),
+            span,
+        )?;
+    }
+
+    Ok(PipelineData::empty())
+}
@@ -11,7 +11,6 @@ use nu_protocol::{
 };
 use std::ops::Add;
 use std::sync::{Arc, Mutex};
-use tokio::runtime::Runtime;
 use tokio::time::Instant;
 
 #[derive(Clone)]
@@ -74,12 +73,12 @@ fn pending_mutations(
         let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
         let response = active_cluster
             .cluster()
+            .map_err(|e| client_error_to_shell_error(e, span))?
             .http_client()
             .analytics_query_request(
                 AnalyticsQueryRequest::PendingMutations,
                 Instant::now().add(active_cluster.timeouts().analytics_timeout()),
                 signals.clone(),
-                Arc::new(Runtime::new().unwrap()),
             )
             .map_err(|e| client_error_to_shell_error(e, span))?;
 
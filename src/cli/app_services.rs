@@ -0,0 +1,128 @@
+use crate::cli::client_error_to_shell_error;
+use crate::cli::util::{convert_json_value_to_nu_value, find_org_id, find_project_id, NuValueMap};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct AppServices {
+    state: Arc<Mutex<State>>,
+}
+
+impl AppServices {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AppServices {
+    fn name(&self) -> &str {
+        "app services"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("app services")
+            .named(
+                "capella",
+                SyntaxShape::String,
+                "the Capella organization to use",
+                None,
+            )
+            .named(
+                "project",
+                SyntaxShape::String,
+                "the Capella project to use",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Lists all app services in the active Capella project"
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        app_services(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn app_services(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+    let capella = call.get_flag(engine_state, stack, "capella")?;
+
+    debug!("Running app services");
+
+    let guard = state.lock().unwrap();
+    let control = guard.named_or_active_org(capella)?;
+
+    let project =
+        guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
+
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+    let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
+
+    let cluster_names: HashMap<String, String> = client
+        .list_clusters(org_id.clone(), project_id.clone(), signals.clone())
+        .map_err(|e| client_error_to_shell_error(e, span))?
+        .items()
+        .into_iter()
+        .map(|c| (c.id(), c.name()))
+        .collect();
+
+    let app_services = client
+        .list_app_services(org_id, signals)
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let mut results = vec![];
+    for app_service in app_services.items() {
+        let cluster = match cluster_names.get(&app_service.cluster_id()) {
+            Some(name) => name.clone(),
+            None => continue,
+        };
+
+        let mut collected = NuValueMap::default();
+        collected.add_string("name", app_service.name(), span);
+        collected.add_string("id", app_service.id(), span);
+        collected.add_string("cluster", cluster, span);
+        collected.add_string("state", app_service.state(), span);
+        collected.add_i64("nodes", app_service.nodes(), span);
+        collected.add(
+            "compute",
+            convert_json_value_to_nu_value(
+                &serde_json::to_value(app_service.compute()).unwrap(),
+                span,
+            )
+            .unwrap(),
+        );
+        collected.add_string("cloud provider", app_service.cloud_provider(), span);
+        collected.add_string("version", app_service.version(), span);
+        collected.add_string("description", app_service.description(), span);
+        results.push(collected.into_value(span))
+    }
+
+    Ok(Value::list(results, span).into_pipeline_data())
+}
@@ -0,0 +1,186 @@
+use crate::cli::error::client_error_to_shell_error;
+use crate::cli::generic_error;
+use crate::cli::util::{find_org_id, find_project_id, wait_timeout_from};
+use crate::client::cloud_json::AppServiceCreateRequest;
+use crate::state::State;
+use log::{debug, info};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct AppServicesCreate {
+    state: Arc<Mutex<State>>,
+}
+
+impl AppServicesCreate {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AppServicesCreate {
+    fn name(&self) -> &str {
+        "app services create"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("app services create")
+            .required("name", SyntaxShape::String, "the name of the app service")
+            .required_named(
+                "cluster",
+                SyntaxShape::String,
+                "the cluster to link the app service to",
+                None,
+            )
+            .named(
+                "nodes",
+                SyntaxShape::Int,
+                "the number of app service nodes",
+                None,
+            )
+            .named("cpu", SyntaxShape::Int, "the number of cpus per node", None)
+            .named("ram", SyntaxShape::Int, "the GiB of ram per node", None)
+            .named(
+                "description",
+                SyntaxShape::String,
+                "a description of the app service",
+                None,
+            )
+            .named(
+                "wait",
+                SyntaxShape::Duration,
+                "wait up to this long for the app service to be deployed",
+                None,
+            )
+            .named(
+                "capella",
+                SyntaxShape::String,
+                "the Capella organization to use",
+                None,
+            )
+            .named(
+                "project",
+                SyntaxShape::String,
+                "the Capella project to use",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Creates an app service linked to a cluster in the active Capella project"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Create an app service with the default size",
+                example: "app services create mobile --cluster dev-cluster",
+                result: None,
+            },
+            Example {
+                description: "Create a two node app service and wait until it is deployed",
+                example: "app services create mobile --cluster dev-cluster --nodes 2 --cpu 4 --ram 8 --wait 30min",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        app_services_create(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn app_services_create(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let cluster_name: String = call
+        .get_flag::<String>(engine_state, stack, "cluster")?
+        .unwrap();
+    let nodes = call.get_flag(engine_state, stack, "nodes")?;
+    let cpu: Option<i32> = call.get_flag(engine_state, stack, "cpu")?;
+    let ram: Option<i32> = call.get_flag(engine_state, stack, "ram")?;
+    let description = call.get_flag(engine_state, stack, "description")?;
+    let capella = call.get_flag(engine_state, stack, "capella")?;
+    let wait_timeout = wait_timeout_from(engine_state, stack, call)?;
+
+    let compute = match (cpu, ram) {
+        (Some(cpu), Some(ram)) => Some((cpu, ram)),
+        (None, None) => None,
+        _ => {
+            return Err(generic_error(
+                "Both --cpu and --ram are required to set the node size",
+                "Pass both flags, or neither to use the default size".to_string(),
+                span,
+            ));
+        }
+    };
+
+    debug!("Running app services create for {}", &name);
+
+    let guard = state.lock().unwrap();
+    let control = guard.named_or_active_org(capella)?;
+
+    let project =
+        guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
+
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+    let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
+
+    let cluster = client
+        .get_cluster(
+            cluster_name,
+            org_id.clone(),
+            project_id.clone(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let request = AppServiceCreateRequest::new(name.clone(), description, nodes, compute);
+    client
+        .create_app_service(
+            org_id.clone(),
+            project_id.clone(),
+            cluster.id(),
+            &request,
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    if let Some(timeout) = wait_timeout {
+        client
+            .wait_for_app_service_state(
+                name.clone(),
+                org_id,
+                project_id,
+                &["healthy"],
+                timeout,
+                signals,
+            )
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+        info!("app service {} is healthy", name);
+    }
+
+    Ok(PipelineData::empty())
+}
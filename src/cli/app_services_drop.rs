@@ -0,0 +1,122 @@
+use crate::cli::error::client_error_to_shell_error;
+use crate::cli::util::{find_org_id, find_project_id, wait_timeout_from};
+use crate::state::State;
+use log::{debug, info};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, PipelineData, ShellError, Signature, SyntaxShape};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct AppServicesDrop {
+    state: Arc<Mutex<State>>,
+}
+
+impl AppServicesDrop {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AppServicesDrop {
+    fn name(&self) -> &str {
+        "app services drop"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("app services drop")
+            .required("name", SyntaxShape::String, "the name of the app service")
+            .named(
+                "wait",
+                SyntaxShape::Duration,
+                "wait up to this long for the app service to be deleted",
+                None,
+            )
+            .named(
+                "capella",
+                SyntaxShape::String,
+                "the Capella organization to use",
+                None,
+            )
+            .named(
+                "project",
+                SyntaxShape::String,
+                "the Capella project to use",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Deletes an app service from the active Capella project"
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        app_services_drop(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn app_services_drop(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let capella = call.get_flag(engine_state, stack, "capella")?;
+    let wait_timeout = wait_timeout_from(engine_state, stack, call)?;
+
+    debug!("Running app services drop for {}", &name);
+
+    let guard = state.lock().unwrap();
+    let control = guard.named_or_active_org(capella)?;
+
+    let project =
+        guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
+
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+    let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
+
+    let app_service = client
+        .get_app_service(
+            name.clone(),
+            org_id.clone(),
+            project_id.clone(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    client
+        .delete_app_service(
+            org_id.clone(),
+            project_id.clone(),
+            app_service.cluster_id(),
+            app_service.id(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    if let Some(timeout) = wait_timeout {
+        client
+            .wait_for_app_service_deleted(name.clone(), org_id, project_id, timeout, signals)
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+        info!("app service {} has been deleted", name);
+    }
+
+    Ok(PipelineData::empty())
+}
@@ -0,0 +1,126 @@
+use crate::cli::error::client_error_to_shell_error;
+use crate::cli::util::{find_org_id, find_project_id, wait_timeout_from};
+use crate::state::State;
+use log::{debug, info};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, PipelineData, ShellError, Signature, SyntaxShape};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct AppServicesOff {
+    state: Arc<Mutex<State>>,
+}
+
+impl AppServicesOff {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AppServicesOff {
+    fn name(&self) -> &str {
+        "app services off"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("app services off")
+            .required("name", SyntaxShape::String, "the name of the app service")
+            .named(
+                "wait",
+                SyntaxShape::Duration,
+                "wait up to this long for the app service to finish turning off",
+                None,
+            )
+            .named(
+                "capella",
+                SyntaxShape::String,
+                "the Capella organization to use",
+                None,
+            )
+            .named(
+                "project",
+                SyntaxShape::String,
+                "the Capella project to use",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Turns off an app service in the active Capella project"
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        app_services_off(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn app_services_off(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let capella = call.get_flag(engine_state, stack, "capella")?;
+    let wait_timeout = wait_timeout_from(engine_state, stack, call)?;
+
+    debug!("Running app services off for {}", &name);
+
+    let guard = state.lock().unwrap();
+    let control = guard.named_or_active_org(capella)?;
+
+    let project =
+        guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
+
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+    let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
+
+    let app_service = client
+        .get_app_service(
+            name.clone(),
+            org_id.clone(),
+            project_id.clone(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    client
+        .turn_app_service_off(
+            org_id.clone(),
+            project_id.clone(),
+            app_service.cluster_id(),
+            app_service.id(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    if let Some(timeout) = wait_timeout {
+        let app_service = client
+            .wait_for_app_service_state(name, org_id, project_id, &["turnedOff"], timeout, signals)
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+        info!(
+            "app service {} is {}",
+            app_service.name(),
+            app_service.state()
+        );
+    }
+
+    Ok(PipelineData::empty())
+}
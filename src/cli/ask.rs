@@ -1,17 +1,32 @@
-use crate::client::{ClientError, LLMClients};
+use crate::cli::ask_session::record_chat_exchange;
+use crate::cli::doc_common::get_active_cluster_client_cid;
+use crate::cli::util::{get_active_cluster, namespace_from_args, NuValueMap};
+use crate::cli::vector_search::{index_name_from_namespace, SearchResultData};
+use crate::client::{
+    ChatMessage, ClientError, KeyValueRequest, LLMClients, VectorSearchQueryRequest,
+};
 use crate::state::State;
+use futures::future::join_all;
+use log::debug;
 use nu_protocol::{Example, IntoValue};
-use std::sync::{Arc, Mutex};
+use serde_json::json;
+use std::ops::Add;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
 use tokio::runtime::Runtime;
 use tokio::select;
+use tokio::time::Instant;
 
-use crate::cli::{client_error_to_shell_error, generic_error, no_llm_configured};
+use crate::cli::{
+    client_error_to_shell_error, generic_error, no_llm_configured, unexpected_status_code_error,
+};
 use crate::CtrlcFuture;
 use nu_engine::command_prelude::Call;
 use nu_engine::CallExt;
 use nu_protocol::engine::{Command, EngineState, Stack};
 use nu_protocol::{
-    Category, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
+    ByteStream, ByteStreamType, Category, IntoPipelineData, PipelineData, ShellError, Signals,
+    Signature, Span, SyntaxShape, Value,
 };
 
 #[derive(Clone)]
@@ -44,6 +59,56 @@ impl Command for Ask {
                 "the chat model to ask the question",
                 None,
             )
+            .switch("stream", "render the answer as it is generated", Some('s'))
+            .named(
+                "index",
+                SyntaxShape::String,
+                "vector index used to fetch context for the question",
+                None,
+            )
+            .named(
+                "field",
+                SyntaxShape::String,
+                "the field of the matching docs to use as context (default = text)",
+                None,
+            )
+            .named(
+                "vector-field",
+                SyntaxShape::String,
+                "name of the vector field the index was built on (default = vector)",
+                None,
+            )
+            .named(
+                "neighbors",
+                SyntaxShape::Int,
+                "number of docs fetched from the vector index as context (default = 3)",
+                None,
+            )
+            .named(
+                "embed-model",
+                SyntaxShape::String,
+                "the model used to embed the question when using --index",
+                None,
+            )
+            .named(
+                "dimension",
+                SyntaxShape::Int,
+                "dimension of the question embedding when using --index",
+                None,
+            )
+            .named(
+                "bucket",
+                SyntaxShape::String,
+                "the name of the bucket",
+                None,
+            )
+            .named("scope", SyntaxShape::String, "the name of the scope", None)
+            .named(
+                "collection",
+                SyntaxShape::String,
+                "the name of the collection",
+                None,
+            )
             .category(Category::Custom("couchbase".to_string()))
     }
 
@@ -63,6 +128,16 @@ impl Command for Ask {
                 example: "[landmark_10019 landmark_10020] | subdoc get content | select content | ask \"summarize this for me\"",
                 result: None,
             },
+            Example {
+                description: "Render a long answer as it is generated",
+                example: "ask \"write me a poem about databases\" --stream",
+                result: None,
+            },
+            Example {
+                description: "Use the text field of the 3 closest docs from a vector index as context",
+                example: "ask \"how do I register a cluster?\" --index rag-index --field text --vector-field vector",
+                result: None,
+            },
         ]
     }
 
@@ -87,7 +162,7 @@ pub fn ask(
     let span = call.head;
 
     let question: String = call.req(engine_state, stack, 0)?;
-    let context: Vec<String> = match call.opt(engine_state, stack, 1)? {
+    let mut context: Vec<String> = match call.opt(engine_state, stack, 1)? {
         Some(ctx) => ctx,
         None => {
             match input.into_value(span)? {
@@ -146,6 +221,88 @@ pub fn ask(
         }
     };
 
+    let model = chat_model_from_args(&state, engine_state, stack, call)?;
+    let stream = call.has_flag(engine_state, stack, "stream")?;
+    let index = call.get_flag::<String>(engine_state, stack, "index")?;
+
+    if stream && index.is_some() {
+        return Err(generic_error(
+            "The --stream and --index flags cannot be used together",
+            "Remove --stream to see the ids of the docs used as context".to_string(),
+            span,
+        ));
+    }
+
+    let client = LLMClients::new(state.clone(), None)?;
+    let rt = Runtime::new().unwrap();
+
+    let sources = match index {
+        Some(index) => {
+            let (ctx, ids) = context_from_vector_index(
+                state.clone(),
+                &client,
+                &rt,
+                index,
+                question.clone(),
+                engine_state,
+                stack,
+                call,
+            )?;
+            context.extend(ctx);
+            Some(ids)
+        }
+        None => None,
+    };
+
+    let history = state
+        .lock()
+        .unwrap()
+        .chat_session()
+        .map(|s| s.messages().clone())
+        .unwrap_or_default();
+
+    let signals = engine_state.signals().clone();
+    if stream {
+        return Ok(ask_llm_streamed(
+            state, client, question, context, history, model, signals, span,
+        ));
+    }
+
+    let answer = ask_llm(
+        &client,
+        &rt,
+        question.clone(),
+        context,
+        &history,
+        model,
+        signals.clone(),
+        span,
+    )?;
+
+    record_chat_exchange(&state, &rt, question, answer.clone(), signals, span)?;
+
+    match sources {
+        Some(ids) => {
+            let mut collected = NuValueMap::default();
+            collected.add_string("answer", answer, span);
+            collected.add_vec(
+                "sources",
+                ids.into_iter().map(|id| Value::string(id, span)).collect(),
+                span,
+            );
+            Ok(collected.into_pipeline_data(span))
+        }
+        None => Ok(Value::string(answer, span).into_pipeline_data()),
+    }
+}
+
+pub(crate) fn chat_model_from_args(
+    state: &Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+) -> Result<String, ShellError> {
+    let span = call.head;
     let model = match call.get_flag::<String>(engine_state, stack, "model")? {
         Some(m) => m,
         None => {
@@ -170,28 +327,213 @@ pub fn ask(
         }
     };
 
-    let client = LLMClients::new(state, None)?;
+    Ok(model)
+}
 
-    let signals = engine_state.signals().clone();
+pub(crate) fn ask_llm(
+    client: &LLMClients,
+    rt: &Runtime,
+    question: String,
+    context: Vec<String>,
+    history: &[ChatMessage],
+    model: String,
+    signals: Signals,
+    span: Span,
+) -> Result<String, ShellError> {
     let signals_fut = CtrlcFuture::new(signals);
-    let rt = Runtime::new().unwrap();
-    let answer = match rt.block_on(async {
+    rt.block_on(async {
         select! {
-            answer = client.ask(question.clone(), context.clone(), model) => {
-                match answer {
-                    Ok(a) => Ok(a),
-                    Err(e) => Err(e),
-                }
-            },
+            answer = client.ask(question, context, history, model) => answer,
+            () = signals_fut =>
+                Err(client_error_to_shell_error(ClientError::Cancelled{key: None}, span)),
+        }
+    })
+}
+
+// The answer is generated on a separate thread which feeds each chunk into the returned stream,
+// so that it can be rendered as it arrives. The exchange is recorded in the active chat session
+// once the answer is complete.
+fn ask_llm_streamed(
+    state: Arc<Mutex<State>>,
+    client: LLMClients,
+    question: String,
+    context: Vec<String>,
+    history: Vec<ChatMessage>,
+    model: String,
+    signals: Signals,
+    span: Span,
+) -> PipelineData {
+    let (tx, rx) = mpsc::channel::<Result<String, ShellError>>();
+    let thread_signals = signals.clone();
+
+    thread::spawn(move || {
+        let rt = Runtime::new().unwrap();
+        let signals_fut = CtrlcFuture::new(thread_signals.clone());
+        let chunk_tx = tx.clone();
+        let answer = rt.block_on(async {
+            select! {
+                answer = client.ask_stream(question.clone(), context, &history, model, |chunk| {
+                    let _ = chunk_tx.send(Ok(chunk));
+                }) => answer,
+                () = signals_fut =>
+                    Err(client_error_to_shell_error(ClientError::Cancelled{key: None}, span)),
+            }
+        });
+
+        let result = answer.and_then(|answer| {
+            record_chat_exchange(&state, &rt, question, answer, thread_signals, span)
+        });
+        if let Err(e) = result {
+            let _ = tx.send(Err(e));
+        }
+    });
+
+    PipelineData::ByteStream(
+        ByteStream::from_result_iter(rx, span, signals, ByteStreamType::String),
+        None,
+    )
+}
+
+// Embeds the question, runs it against the vector index and fetches the matching docs over KV,
+// returning the contents of the requested field from each doc along with the doc ids used.
+fn context_from_vector_index(
+    state: Arc<Mutex<State>>,
+    client: &LLMClients,
+    rt: &Runtime,
+    index: String,
+    question: String,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+) -> Result<(Vec<String>, Vec<String>), ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let field = call
+        .get_flag::<String>(engine_state, stack, "field")?
+        .unwrap_or_else(|| "text".to_string());
+    let vector_field = call
+        .get_flag::<String>(engine_state, stack, "vector-field")?
+        .unwrap_or_else(|| "vector".to_string());
+    let neighbors = call
+        .get_flag::<i64>(engine_state, stack, "neighbors")?
+        .unwrap_or(3);
+    let dim = call.get_flag::<usize>(engine_state, stack, "dimension")?;
+    let bucket_flag: Option<String> = call.get_flag(engine_state, stack, "bucket")?;
+    let scope_flag: Option<String> = call.get_flag(engine_state, stack, "scope")?;
+    let collection_flag: Option<String> = call.get_flag(engine_state, stack, "collection")?;
+
+    let embed_model = match call.get_flag::<String>(engine_state, stack, "embed-model")? {
+        Some(m) => m,
+        None => state.lock().unwrap().active_embed_model()?,
+    };
+
+    let batch = vec![question];
+    let signals_fut = CtrlcFuture::new(signals.clone());
+    let embeddings = rt.block_on(async {
+        select! {
+            result = client.embed(&batch, dim, embed_model) => result,
             () = signals_fut =>
                 Err(client_error_to_shell_error(ClientError::Cancelled{key: None}, span)),
         }
-    }) {
-        Ok(a) => a,
-        Err(e) => {
-            return Err(e);
+    })?;
+
+    let vector = match embeddings.into_iter().next() {
+        Some(v) => v,
+        None => {
+            return Err(generic_error(
+                "No embedding was returned for the question",
+                None,
+                span,
+            ));
         }
     };
 
-    Ok(Value::string(answer, span).into_pipeline_data())
+    let guard = state.lock().unwrap();
+    let identifier = guard.active();
+    let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+
+    let namespace = namespace_from_args(
+        bucket_flag.clone(),
+        scope_flag.clone(),
+        None,
+        active_cluster,
+        span,
+    )?;
+    let qualified_index = index_name_from_namespace(index, namespace);
+
+    debug!(
+        "Fetching {} neighbors from {} as context",
+        neighbors, &qualified_index
+    );
+
+    let response = active_cluster
+        .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
+        .http_client()
+        .search_query_request(
+            VectorSearchQueryRequest::Execute {
+                query: json!({"match_none": {}}),
+                index: qualified_index,
+                vector,
+                field: vector_field,
+                neighbors,
+                timeout: active_cluster.timeouts().search_timeout().as_millis(),
+            },
+            Instant::now().add(active_cluster.timeouts().search_timeout()),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let status = response.status();
+    let content = response.content()?;
+    let results: SearchResultData = match status {
+        200 => serde_json::from_str(&content)
+            .map_err(|_e| unexpected_status_code_error(status, content, span))?,
+        _ => {
+            return Err(unexpected_status_code_error(status, content, span));
+        }
+    };
+
+    let (active_cluster, kv_client, cid) = get_active_cluster_client_cid(
+        rt,
+        identifier,
+        &guard,
+        bucket_flag,
+        scope_flag,
+        collection_flag,
+        signals.clone(),
+        span,
+    )?;
+
+    let responses = rt.block_on(join_all(results.hits.into_iter().map(|hit| {
+        let deadline = Instant::now().add(active_cluster.timeouts().data_timeout());
+        kv_client.request(
+            KeyValueRequest::Get { key: hit.id },
+            cid,
+            deadline,
+            signals.clone(),
+        )
+    })));
+
+    let mut context = vec![];
+    let mut sources = vec![];
+    for response in responses {
+        let mut res = response.map_err(|e| client_error_to_shell_error(e, span))?;
+        let doc = res.content().unwrap_or_default();
+
+        let ctx = match field.split('.').try_fold(&doc, |v, f| v.get(f)) {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(v) => v.to_string(),
+            None => {
+                debug!("Skipping doc {} as it has no field {}", res.key(), &field);
+                continue;
+            }
+        };
+
+        context.push(ctx);
+        sources.push(res.key());
+    }
+
+    Ok((context, sources))
 }
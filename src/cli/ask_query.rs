@@ -0,0 +1,335 @@
+//! The `ask query` command generates a SQL++ statement from a natural language question.
+
+use crate::cli::ask::{ask_llm, chat_model_from_args};
+use crate::cli::generic_error;
+use crate::cli::query::{handle_query_response, send_query, send_readonly_query};
+use crate::cli::util::{get_active_cluster, namespace_from_args};
+use crate::client::LLMClients;
+use crate::read_input;
+use crate::state::State;
+use crate::RemoteCluster;
+use log::{debug, info, warn};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signals, Signature, Span,
+    SyntaxShape, Value,
+};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Runtime;
+
+// The number of docs sampled by INFER, or fetched directly if INFER fails.
+const DEFAULT_SAMPLE_SIZE: i64 = 10;
+
+#[derive(Clone)]
+pub struct AskQuery {
+    state: Arc<Mutex<State>>,
+}
+
+impl AskQuery {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AskQuery {
+    fn name(&self) -> &str {
+        "ask query"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("ask query")
+            .required(
+                "question",
+                SyntaxShape::String,
+                "the question to turn into a SQL++ statement",
+            )
+            .named(
+                "model",
+                SyntaxShape::String,
+                "the chat model used to generate the statement",
+                None,
+            )
+            .named(
+                "bucket",
+                SyntaxShape::String,
+                "the name of the bucket",
+                None,
+            )
+            .named("scope", SyntaxShape::String, "the name of the scope", None)
+            .named(
+                "collection",
+                SyntaxShape::String,
+                "the name of the collection",
+                None,
+            )
+            .named(
+                "sample-size",
+                SyntaxShape::Int,
+                "the number of docs sampled to work out the schema (default = 10)",
+                None,
+            )
+            .switch(
+                "execute",
+                "execute the generated statement as a read-only query",
+                Some('x'),
+            )
+            .switch("yes", "answer yes to confirmation prompt", Some('y'))
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Asks a connected LLM to write a SQL++ statement for the active collection"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Generate a statement for a question",
+                example: "ask query \"which airlines fly out of LAX?\" --scope inventory --collection route",
+                result: None,
+            },
+            Example {
+                description: "Generate and execute a statement without confirmation",
+                example: "ask query \"how many hotels are in France?\" --execute --yes",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        ask_query(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn ask_query(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let question: String = call.req(engine_state, stack, 0)?;
+    let bucket_flag: Option<String> = call.get_flag(engine_state, stack, "bucket")?;
+    let scope_flag: Option<String> = call.get_flag(engine_state, stack, "scope")?;
+    let collection_flag: Option<String> = call.get_flag(engine_state, stack, "collection")?;
+    let sample_size = call
+        .get_flag::<i64>(engine_state, stack, "sample-size")?
+        .unwrap_or(DEFAULT_SAMPLE_SIZE);
+    let execute = call.has_flag(engine_state, stack, "execute")?;
+
+    let model = chat_model_from_args(&state, engine_state, stack, call)?;
+
+    let (identifier, keyspace, query_context, schema) = {
+        let guard = state.lock().unwrap();
+        let identifier = guard.active();
+        let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+
+        let (bucket, scope, collection) = namespace_from_args(
+            bucket_flag,
+            scope_flag,
+            collection_flag,
+            active_cluster,
+            span,
+        )?;
+        let scope = if scope.is_empty() {
+            "_default".to_string()
+        } else {
+            scope
+        };
+        let collection = if collection.is_empty() {
+            "_default".to_string()
+        } else {
+            collection
+        };
+
+        let keyspace = format!("`{}`.`{}`.`{}`", bucket, scope, collection);
+        let schema = keyspace_schema(
+            active_cluster,
+            &keyspace,
+            sample_size,
+            signals.clone(),
+            span,
+        )?;
+
+        (identifier, keyspace, (bucket, scope), schema)
+    };
+
+    let context = vec![
+        "You are an expert in Couchbase SQL++. Respond with a single SQL++ statement that answers the question. Do not include any explanation or markdown formatting.".to_string(),
+        format!(
+            "The statement must query the keyspace {}, always use the fully qualified keyspace name.",
+            keyspace
+        ),
+        format!(
+            "The schema of the keyspace, as inferred from a sample of its documents, is: {}",
+            schema
+        ),
+    ];
+
+    let client = LLMClients::new(state.clone(), None)?;
+    let rt = Runtime::new().unwrap();
+    let answer = ask_llm(
+        &client,
+        &rt,
+        question,
+        context,
+        &[],
+        model,
+        signals.clone(),
+        span,
+    )?;
+    let statement = statement_from_answer(answer);
+
+    if !execute {
+        return Ok(Value::string(statement, span).into_pipeline_data());
+    }
+
+    if !call.has_flag(engine_state, stack, "yes")? {
+        println!("{}", statement);
+        warn!("execute this statement against {}? (y/n)", identifier);
+
+        let confirmation = read_input().unwrap_or("n".to_string());
+        if !confirmation.starts_with('y') {
+            info!("statement execution canceled");
+            return Ok(PipelineData::empty());
+        }
+    }
+
+    debug!("Running generated n1ql query {}", &statement);
+
+    let guard = state.lock().unwrap();
+    let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+    // The statement is written by the model from a prompt including sampled document values, so
+    // it is only allowed to read data.
+    let response = send_readonly_query(
+        active_cluster,
+        statement,
+        Some(query_context),
+        signals,
+        span,
+    )?;
+    drop(guard);
+
+    let status = response.status();
+    let results = handle_query_response(false, identifier, status, response.content()?, span)?;
+
+    Ok(Value::list(results, span).into_pipeline_data())
+}
+
+// Uses INFER to describe the documents in the keyspace, falling back to a raw sample of docs if
+// INFER is unavailable, for example when the user lacks the permissions to run it.
+fn keyspace_schema(
+    cluster: &RemoteCluster,
+    keyspace: &str,
+    sample_size: i64,
+    signals: Signals,
+    span: Span,
+) -> Result<String, ShellError> {
+    let infer = format!(
+        "INFER {} WITH {{\"sample_size\": {}, \"num_sample_values\": 3}}",
+        keyspace, sample_size
+    );
+
+    debug!("Inferring schema with {}", &infer);
+
+    let response = send_query(
+        cluster,
+        infer,
+        None,
+        None,
+        signals.clone(),
+        None,
+        span,
+        None,
+    )?;
+    let status = response.status();
+    match schema_from_response(status, response.content()?) {
+        Some(s) => return Ok(s),
+        None => {
+            debug!("INFER failed for {}, sampling docs instead", keyspace);
+        }
+    }
+
+    let sample = format!("SELECT RAW d FROM {} d LIMIT {}", keyspace, sample_size);
+    let response = send_query(cluster, sample, None, None, signals, None, span, None)?;
+    let status = response.status();
+    let content = response.content()?;
+
+    schema_from_response(status, content.clone()).ok_or_else(|| {
+        generic_error(
+            format!("Could not determine the schema of {}", keyspace),
+            content,
+            span,
+        )
+    })
+}
+
+fn schema_from_response(status: u16, content: String) -> Option<String> {
+    if status != 200 {
+        return None;
+    }
+
+    let content: serde_json::Value = serde_json::from_str(&content).ok()?;
+    content.get("results").map(|r| r.to_string())
+}
+
+// Models will often wrap the statement in a markdown code block regardless of being told not to.
+fn statement_from_answer(answer: String) -> String {
+    let trimmed = answer.trim();
+    let unfenced = match trimmed.strip_prefix("```") {
+        Some(s) => {
+            let s = s.strip_suffix("```").unwrap_or(s);
+            match s.split_once('\n') {
+                Some((lang, rest)) if !lang.contains(' ') => rest,
+                _ => s,
+            }
+        }
+        None => trimmed,
+    };
+
+    unfenced.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::ask_query::statement_from_answer;
+
+    #[test]
+    fn statement_from_plain_answer() {
+        assert_eq!(
+            "SELECT * FROM `travel-sample`.`inventory`.`hotel`".to_string(),
+            statement_from_answer(
+                " SELECT * FROM `travel-sample`.`inventory`.`hotel`\n".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn statement_from_fenced_answer() {
+        assert_eq!(
+            "SELECT COUNT(*) FROM `travel-sample`.`inventory`.`hotel`".to_string(),
+            statement_from_answer(
+                "```sql\nSELECT COUNT(*) FROM `travel-sample`.`inventory`.`hotel`\n```".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn statement_from_fenced_answer_without_language() {
+        assert_eq!(
+            "SELECT 1".to_string(),
+            statement_from_answer("```\nSELECT 1\n```".to_string())
+        );
+    }
+}
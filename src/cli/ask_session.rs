@@ -0,0 +1,217 @@
+use crate::cli::doc_common::get_active_cluster_client_cid;
+use crate::cli::util::NuValueMap;
+use crate::cli::{client_error_to_shell_error, deserialize_error, generic_error, serialize_error};
+use crate::client::{ChatMessage, ChatRole, ClientError, KeyValueRequest};
+use crate::state::{ChatSession, ChatSessionKeyspace, State};
+use nu_engine::command_prelude::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signals, Signature, Span, Value,
+};
+use serde_derive::{Deserialize, Serialize};
+use std::ops::Add;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::runtime::Runtime;
+use tokio::time::Instant;
+
+#[derive(Clone)]
+pub struct AskSession {
+    state: Arc<Mutex<State>>,
+}
+
+impl AskSession {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AskSession {
+    fn name(&self) -> &str {
+        "ask session"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("ask session").category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Shows the message history of the active chat session"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Show the questions and answers so far",
+            example: "ask session",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _engine_state: &EngineState,
+        _stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+
+        let session = match self.state.lock().unwrap().chat_session() {
+            Some(s) => s,
+            None => {
+                return Err(no_chat_session_error(span));
+            }
+        };
+
+        let messages = session
+            .messages()
+            .iter()
+            .map(|m| {
+                let mut collected = NuValueMap::default();
+                collected.add_string("session", session.id(), span);
+                collected.add_string(
+                    "role",
+                    match m.role() {
+                        ChatRole::User => "user",
+                        ChatRole::Assistant => "assistant",
+                    },
+                    span,
+                );
+                collected.add_string("content", m.content(), span);
+                collected.into_value(span)
+            })
+            .collect();
+
+        Ok(Value::list(messages, span).into_pipeline_data())
+    }
+}
+
+pub(crate) fn session_to_value(session: &ChatSession, span: Span) -> Value {
+    let mut collected = NuValueMap::default();
+    collected.add_string("id", session.id(), span);
+    collected.add_i64("messages", session.messages().len() as i64, span);
+    match session.keyspace() {
+        Some(k) => {
+            collected.add_string("cluster", k.cluster(), span);
+            collected.add_string(
+                "keyspace",
+                format!("{}.{}.{}", k.bucket(), k.scope(), k.collection()),
+                span,
+            );
+        }
+        None => {
+            collected.add_string("cluster", "", span);
+            collected.add_string("keyspace", "", span);
+        }
+    }
+    collected.into_value(span)
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PersistedChatSession {
+    messages: Vec<ChatMessage>,
+}
+
+// Records a question and its answer in the active chat session, if there is one, and persists
+// the updated history when the session was started with a keyspace.
+pub(crate) fn record_chat_exchange(
+    state: &Arc<Mutex<State>>,
+    rt: &Runtime,
+    question: String,
+    answer: String,
+    signals: Signals,
+    span: Span,
+) -> Result<(), ShellError> {
+    let guard = state.lock().unwrap();
+    let session = match guard.add_chat_exchange(question, answer) {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+
+    if let Some(keyspace) = session.keyspace() {
+        persist_chat_session(&guard, rt, &session, keyspace, signals, span)?;
+    }
+
+    Ok(())
+}
+
+fn persist_chat_session(
+    guard: &MutexGuard<State>,
+    rt: &Runtime,
+    session: &ChatSession,
+    keyspace: ChatSessionKeyspace,
+    signals: Signals,
+    span: Span,
+) -> Result<(), ShellError> {
+    let (active_cluster, client, cid) = get_active_cluster_client_cid(
+        rt,
+        keyspace.cluster(),
+        guard,
+        Some(keyspace.bucket()),
+        Some(keyspace.scope()),
+        Some(keyspace.collection()),
+        signals.clone(),
+        span,
+    )?;
+
+    let value = serde_json::to_vec(&PersistedChatSession {
+        messages: session.messages().clone(),
+    })
+    .map_err(|e| serialize_error(e.to_string(), span))?;
+
+    let deadline = Instant::now().add(active_cluster.timeouts().data_timeout());
+    rt.block_on(client.request(
+        KeyValueRequest::Set {
+            key: session.id(),
+            value,
+            expiry: 0,
+        },
+        cid,
+        deadline,
+        signals,
+    ))
+    .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    Ok(())
+}
+
+// Fetches the history of a persisted session, a session which has not been persisted yet has
+// no history.
+pub(crate) fn load_chat_history(
+    guard: &MutexGuard<State>,
+    rt: &Runtime,
+    id: String,
+    keyspace: &ChatSessionKeyspace,
+    signals: Signals,
+    span: Span,
+) -> Result<Vec<ChatMessage>, ShellError> {
+    let (active_cluster, client, cid) = get_active_cluster_client_cid(
+        rt,
+        keyspace.cluster(),
+        guard,
+        Some(keyspace.bucket()),
+        Some(keyspace.scope()),
+        Some(keyspace.collection()),
+        signals.clone(),
+        span,
+    )?;
+
+    let deadline = Instant::now().add(active_cluster.timeouts().data_timeout());
+    match rt.block_on(client.request(KeyValueRequest::Get { key: id }, cid, deadline, signals)) {
+        Ok(mut res) => {
+            let content = res.content().unwrap_or_default();
+            let persisted: PersistedChatSession = serde_json::from_value(content)
+                .map_err(|e| deserialize_error(e.to_string(), span))?;
+            Ok(persisted.messages)
+        }
+        Err(ClientError::KeyNotFound { .. }) => Ok(vec![]),
+        Err(e) => Err(client_error_to_shell_error(e, span)),
+    }
+}
+
+pub(crate) fn no_chat_session_error(span: Span) -> ShellError {
+    generic_error(
+        "No active chat session",
+        "Use 'ask session start' to start one".to_string(),
+        span,
+    )
+}
@@ -0,0 +1,54 @@
+use crate::cli::ask_session::{no_chat_session_error, session_to_value};
+use crate::state::State;
+use nu_engine::command_prelude::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, IntoPipelineData, PipelineData, ShellError, Signature};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct AskSessionEnd {
+    state: Arc<Mutex<State>>,
+}
+
+impl AskSessionEnd {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AskSessionEnd {
+    fn name(&self) -> &str {
+        "ask session end"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("ask session end").category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Ends the active chat session, persisted history is left in place"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "End the active session",
+            example: "ask session end",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _engine_state: &EngineState,
+        _stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+
+        match self.state.lock().unwrap().end_chat_session() {
+            Some(session) => Ok(session_to_value(&session, span).into_pipeline_data()),
+            None => Err(no_chat_session_error(span)),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::cli::ask_session::{load_chat_history, session_to_value};
+use crate::cli::util::{get_active_cluster, namespace_from_args};
+use crate::state::{ChatSession, ChatSessionKeyspace, State};
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape,
+};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct AskSessionStart {
+    state: Arc<Mutex<State>>,
+}
+
+impl AskSessionStart {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for AskSessionStart {
+    fn name(&self) -> &str {
+        "ask session start"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("ask session start")
+            .named(
+                "id",
+                SyntaxShape::String,
+                "the id of the session, used to resume a persisted session",
+                None,
+            )
+            .switch(
+                "persist",
+                "persist the session history to a collection",
+                Some('p'),
+            )
+            .named(
+                "bucket",
+                SyntaxShape::String,
+                "the name of the bucket",
+                None,
+            )
+            .named("scope", SyntaxShape::String, "the name of the scope", None)
+            .named(
+                "collection",
+                SyntaxShape::String,
+                "the name of the collection",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Starts a chat session so that ask remembers previous questions and answers"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Start a new in memory session",
+                example: "ask session start",
+                result: None,
+            },
+            Example {
+                description: "Resume a session persisted to the active collection",
+                example: "ask session start --persist --id my-session",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+        let signals = engine_state.signals().clone();
+
+        let id = call
+            .get_flag::<String>(engine_state, stack, "id")?
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let persist = call.has_flag(engine_state, stack, "persist")?;
+
+        let guard = self.state.lock().unwrap();
+
+        let session = if persist {
+            let identifier = guard.active();
+            let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+            let (bucket, scope, collection) = namespace_from_args(
+                call.get_flag(engine_state, stack, "bucket")?,
+                call.get_flag(engine_state, stack, "scope")?,
+                call.get_flag(engine_state, stack, "collection")?,
+                active_cluster,
+                span,
+            )?;
+            let keyspace = ChatSessionKeyspace::new(identifier, bucket, scope, collection);
+
+            let rt = Runtime::new().unwrap();
+            let history = load_chat_history(&guard, &rt, id.clone(), &keyspace, signals, span)?;
+
+            debug!(
+                "Resuming chat session {} with {} messages",
+                &id,
+                history.len()
+            );
+
+            ChatSession::new(id, history, keyspace)
+        } else {
+            ChatSession::new(id, vec![], None)
+        };
+
+        let value = session_to_value(&session, span);
+        guard.start_chat_session(session);
+
+        Ok(value.into_pipeline_data())
+    }
+}
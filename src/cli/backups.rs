@@ -0,0 +1,137 @@
+use crate::cli::error::client_error_to_shell_error;
+use crate::cli::util::{
+    cluster_identifiers_from, find_org_project_cluster_ids, get_active_cluster, validate_is_cloud,
+    NuValueMap,
+};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct Backups {
+    state: Arc<Mutex<State>>,
+}
+
+impl Backups {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for Backups {
+    fn name(&self) -> &str {
+        "backups"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("backups")
+            .named(
+                "bucket",
+                SyntaxShape::String,
+                "only list the backups of this bucket",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Lists the backups of a Capella cluster"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "List the backups of the travel-sample bucket",
+            example: "backups --bucket travel-sample",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        backups(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn backups(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+    let bucket: Option<String> = call.get_flag(engine_state, stack, "bucket")?;
+    let guard = state.lock().unwrap();
+
+    debug!("Running backups");
+
+    let mut results = vec![];
+    for identifier in cluster_identifiers {
+        let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+        validate_is_cloud(active_cluster, "backups", span)?;
+
+        let client = guard
+            .named_or_active_org(active_cluster.capella_org())?
+            .client()
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+
+        let (org_id, project_id, cluster_id) = find_org_project_cluster_ids(
+            &client,
+            signals.clone(),
+            span,
+            identifier.clone(),
+            guard.named_or_active_project(active_cluster.project())?,
+            active_cluster,
+        )?;
+
+        let backups = client
+            .list_backups(org_id, project_id, cluster_id, signals.clone())
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+
+        for backup in backups.data() {
+            if let Some(b) = &bucket {
+                if &backup.bucket() != b {
+                    continue;
+                }
+            }
+
+            let mut collected = NuValueMap::default();
+            collected.add_string("cluster", identifier.clone(), span);
+            collected.add_string("id", backup.id(), span);
+            collected.add_string("bucket", backup.bucket(), span);
+            collected.add_string("status", backup.status(), span);
+            collected.add_string("method", backup.method(), span);
+            collected.add_string("source", backup.source(), span);
+            collected.add_string("date", backup.date(), span);
+            collected.add_string("restore before", backup.restore_before(), span);
+            collected.add_i64("items", backup.items(), span);
+            collected.add(
+                "size",
+                Value::filesize(backup.size_in_mib() * 1024 * 1024, span),
+            );
+            results.push(collected.into_value(span));
+        }
+    }
+
+    Ok(Value::list(results, span).into_pipeline_data())
+}
@@ -0,0 +1,117 @@
+use crate::cli::error::{client_error_to_shell_error, no_active_bucket_error};
+use crate::cli::util::{
+    cluster_identifiers_from, find_org_project_cluster_ids, get_active_cluster, validate_is_cloud,
+};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct BackupsCreate {
+    state: Arc<Mutex<State>>,
+}
+
+impl BackupsCreate {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for BackupsCreate {
+    fn name(&self) -> &str {
+        "backups create"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("backups create")
+            .named(
+                "bucket",
+                SyntaxShape::String,
+                "the name of the bucket to back up",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Starts a backup of a bucket on a Capella cluster"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Back up the travel-sample bucket",
+            example: "backups create --bucket travel-sample",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        backups_create(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn backups_create(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+    let bucket_flag: Option<String> = call.get_flag(engine_state, stack, "bucket")?;
+    let guard = state.lock().unwrap();
+
+    for identifier in cluster_identifiers {
+        let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+        validate_is_cloud(active_cluster, "backups create", span)?;
+
+        let bucket = match bucket_flag
+            .clone()
+            .or_else(|| active_cluster.active_bucket())
+        {
+            Some(b) => b,
+            None => return Err(no_active_bucket_error(span)),
+        };
+
+        debug!("Running backups create for bucket {}", &bucket);
+
+        let client = guard
+            .named_or_active_org(active_cluster.capella_org())?
+            .client()
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+
+        let (org_id, project_id, cluster_id) = find_org_project_cluster_ids(
+            &client,
+            signals.clone(),
+            span,
+            identifier.clone(),
+            guard.named_or_active_project(active_cluster.project())?,
+            active_cluster,
+        )?;
+
+        client
+            .create_backup(org_id, project_id, cluster_id, bucket, signals.clone())
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+    }
+
+    Ok(PipelineData::empty())
+}
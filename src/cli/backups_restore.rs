@@ -0,0 +1,178 @@
+use crate::cli::error::client_error_to_shell_error;
+use crate::cli::util::{find_org_project_cluster_ids, get_active_cluster, validate_is_cloud};
+use crate::client::cloud_json::BackupRestoreRequest;
+use crate::read_input;
+use crate::state::State;
+use log::{debug, info, warn};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct BackupsRestore {
+    state: Arc<Mutex<State>>,
+}
+
+impl BackupsRestore {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for BackupsRestore {
+    fn name(&self) -> &str {
+        "backups restore"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("backups restore")
+            .required("id", SyntaxShape::String, "the id of the backup to restore")
+            .named(
+                "cluster",
+                SyntaxShape::String,
+                "the cluster the backup was taken from, defaults to the active cluster",
+                None,
+            )
+            .named(
+                "target",
+                SyntaxShape::String,
+                "the cluster to restore the backup to, defaults to the cluster it was taken from",
+                None,
+            )
+            .named(
+                "services",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "the services to restore, defaults to data and query",
+                None,
+            )
+            .switch(
+                "force-updates",
+                "overwrite documents which have changed since the backup",
+                None,
+            )
+            .switch(
+                "auto-remove-collections",
+                "remove collections which have been dropped since the backup",
+                None,
+            )
+            .switch("yes", "answer yes to confirmation prompt", Some('y'))
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Restores a backup to a Capella cluster"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Restore the latest backup of the travel-sample bucket",
+                example: "backups restore (backups --bucket travel-sample | sort-by date | last | get id)",
+                result: None,
+            },
+            Example {
+                description: "Restore a backup to another cluster, overwriting any changed documents",
+                example: "backups restore 8a8e7a5c-c1a2-4d11-9f5e-1ed0c4b7d932 --target staging --force-updates",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        backups_restore(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn backups_restore(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let backup_id: String = call.req(engine_state, stack, 0)?;
+    let services: Vec<String> = call
+        .get_flag(engine_state, stack, "services")?
+        .unwrap_or_else(|| vec!["data".to_string(), "query".to_string()]);
+    let force_updates = call.has_flag(engine_state, stack, "force-updates")?;
+    let auto_remove_collections = call.has_flag(engine_state, stack, "auto-remove-collections")?;
+
+    let guard = state.lock().unwrap();
+    let source: String = match call.get_flag(engine_state, stack, "cluster")? {
+        Some(c) => c,
+        None => guard.active(),
+    };
+    let target: String = call
+        .get_flag(engine_state, stack, "target")?
+        .unwrap_or_else(|| source.clone());
+
+    let source_cluster = get_active_cluster(source.clone(), &guard, span)?;
+    validate_is_cloud(source_cluster, "backups restore", span)?;
+    let target_cluster = get_active_cluster(target.clone(), &guard, span)?;
+    validate_is_cloud(target_cluster, "backups restore", span)?;
+
+    if !call.has_flag(engine_state, stack, "yes")? {
+        warn!(
+            "restoring backup {} from {} will overwrite data on {}, is this okay? (y/n)",
+            &backup_id, &source, &target
+        );
+
+        let confirmation = read_input().unwrap_or("n".to_string());
+        if !confirmation.starts_with('y') {
+            info!("backup restore canceled");
+            return Ok(PipelineData::empty());
+        }
+    }
+
+    debug!(
+        "Running backups restore for {} from {} to {}",
+        &backup_id, &source, &target
+    );
+
+    let client = guard
+        .named_or_active_org(source_cluster.capella_org())?
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let (org_id, project_id, source_id) = find_org_project_cluster_ids(
+        &client,
+        signals.clone(),
+        span,
+        source.clone(),
+        guard.named_or_active_project(source_cluster.project())?,
+        source_cluster,
+    )?;
+    let (_, _, target_id) = find_org_project_cluster_ids(
+        &client,
+        signals.clone(),
+        span,
+        target,
+        guard.named_or_active_project(target_cluster.project())?,
+        target_cluster,
+    )?;
+
+    let restore = BackupRestoreRequest::new(
+        source_id.clone(),
+        target_id,
+        services,
+        force_updates,
+        auto_remove_collections,
+    );
+
+    client
+        .restore_backup(org_id, project_id, source_id, backup_id, &restore, signals)
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    Ok(PipelineData::empty())
+}
@@ -0,0 +1,148 @@
+use crate::cli::error::{client_error_to_shell_error, no_active_bucket_error};
+use crate::cli::util::{
+    cluster_identifiers_from, find_org_project_cluster_ids, get_active_cluster, validate_is_cloud,
+    NuValueMap,
+};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct BackupsSchedule {
+    state: Arc<Mutex<State>>,
+}
+
+impl BackupsSchedule {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for BackupsSchedule {
+    fn name(&self) -> &str {
+        "backups schedule"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("backups schedule")
+            .named(
+                "bucket",
+                SyntaxShape::String,
+                "the name of the bucket",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Gets the backup schedule of a bucket on a Capella cluster"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Get the backup schedule of the travel-sample bucket",
+            example: "backups schedule --bucket travel-sample",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        backups_schedule(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn backups_schedule(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+    let bucket_flag: Option<String> = call.get_flag(engine_state, stack, "bucket")?;
+    let guard = state.lock().unwrap();
+
+    let mut results = vec![];
+    for identifier in cluster_identifiers {
+        let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+        validate_is_cloud(active_cluster, "backups schedule", span)?;
+
+        let bucket = match bucket_flag
+            .clone()
+            .or_else(|| active_cluster.active_bucket())
+        {
+            Some(b) => b,
+            None => return Err(no_active_bucket_error(span)),
+        };
+
+        debug!("Running backups schedule for bucket {}", &bucket);
+
+        let client = guard
+            .named_or_active_org(active_cluster.capella_org())?
+            .client()
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+
+        let (org_id, project_id, cluster_id) = find_org_project_cluster_ids(
+            &client,
+            signals.clone(),
+            span,
+            identifier.clone(),
+            guard.named_or_active_project(active_cluster.project())?,
+            active_cluster,
+        )?;
+
+        let schedule = client
+            .get_backup_schedule(
+                org_id,
+                project_id,
+                cluster_id,
+                bucket.clone(),
+                signals.clone(),
+            )
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+
+        let mut collected = NuValueMap::default();
+        collected.add_string("cluster", identifier.clone(), span);
+        collected.add_string("bucket", bucket, span);
+        match schedule {
+            Some(schedule) => {
+                collected.add_string("day", schedule.day_of_week(), span);
+                collected.add_i64("start at", schedule.start_at(), span);
+                collected.add_i64("incremental every", schedule.incremental_every(), span);
+                collected.add_string("retention", schedule.retention_time(), span);
+                collected.add_bool("cost optimized", schedule.cost_optimized_retention(), span);
+            }
+            None => {
+                collected.add("day", Value::nothing(span));
+                collected.add("start at", Value::nothing(span));
+                collected.add("incremental every", Value::nothing(span));
+                collected.add("retention", Value::nothing(span));
+                collected.add("cost optimized", Value::nothing(span));
+            }
+        }
+        results.push(collected.into_value(span));
+    }
+
+    Ok(Value::list(results, span).into_pipeline_data())
+}
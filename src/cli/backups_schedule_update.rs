@@ -0,0 +1,195 @@
+use crate::cli::error::{client_error_to_shell_error, no_active_bucket_error};
+use crate::cli::generic_error;
+use crate::cli::util::{
+    cluster_identifiers_from, find_org_project_cluster_ids, get_active_cluster, validate_is_cloud,
+};
+use crate::client::cloud_json::BackupSchedule;
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape};
+use std::sync::{Arc, Mutex};
+
+const DAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+#[derive(Clone)]
+pub struct BackupsScheduleUpdate {
+    state: Arc<Mutex<State>>,
+}
+
+impl BackupsScheduleUpdate {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for BackupsScheduleUpdate {
+    fn name(&self) -> &str {
+        "backups schedule update"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("backups schedule update")
+            .required_named(
+                "day",
+                SyntaxShape::String,
+                "the day of the week to take a full backup on",
+                None,
+            )
+            .named(
+                "start-at",
+                SyntaxShape::Int,
+                "the hour of the day to start backups at, defaults to 0",
+                None,
+            )
+            .named(
+                "incremental-every",
+                SyntaxShape::Int,
+                "the number of hours between incremental backups, defaults to 24",
+                None,
+            )
+            .named(
+                "retention",
+                SyntaxShape::String,
+                "how long backups are kept for, e.g. 30days or 1year, defaults to 30days",
+                None,
+            )
+            .switch(
+                "cost-optimized",
+                "keep backups in cheaper storage, which makes them slower to restore",
+                None,
+            )
+            .named(
+                "bucket",
+                SyntaxShape::String,
+                "the name of the bucket",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Sets the backup schedule of a bucket on a Capella cluster"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Take a full backup every Sunday at 2am and incremental backups every 6 hours",
+            example: "backups schedule update --bucket travel-sample --day sunday --start-at 2 --incremental-every 6 --retention 90days",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        backups_schedule_update(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn backups_schedule_update(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let day: String = call
+        .get_flag::<String>(engine_state, stack, "day")?
+        .unwrap()
+        .to_lowercase();
+    if !DAYS.contains(&day.as_str()) {
+        return Err(generic_error(
+            format!("Invalid day {}", day),
+            format!("The day must be one of {}", DAYS.join(", ")),
+            span,
+        ));
+    }
+    let start_at: i64 = call.get_flag(engine_state, stack, "start-at")?.unwrap_or(0);
+    if !(0..24).contains(&start_at) {
+        return Err(generic_error(
+            format!("Invalid start hour {}", start_at),
+            "The start hour must be between 0 and 23".to_string(),
+            span,
+        ));
+    }
+    let incremental_every: i64 = call
+        .get_flag(engine_state, stack, "incremental-every")?
+        .unwrap_or(24);
+    let retention: String = call
+        .get_flag(engine_state, stack, "retention")?
+        .unwrap_or_else(|| "30days".to_string());
+    let cost_optimized = call.has_flag(engine_state, stack, "cost-optimized")?;
+
+    let schedule =
+        BackupSchedule::weekly(day, start_at, incremental_every, retention, cost_optimized);
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+    let bucket_flag: Option<String> = call.get_flag(engine_state, stack, "bucket")?;
+    let guard = state.lock().unwrap();
+
+    for identifier in cluster_identifiers {
+        let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+        validate_is_cloud(active_cluster, "backups schedule update", span)?;
+
+        let bucket = match bucket_flag
+            .clone()
+            .or_else(|| active_cluster.active_bucket())
+        {
+            Some(b) => b,
+            None => return Err(no_active_bucket_error(span)),
+        };
+
+        debug!("Running backups schedule update for bucket {}", &bucket);
+
+        let client = guard
+            .named_or_active_org(active_cluster.capella_org())?
+            .client()
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+
+        let (org_id, project_id, cluster_id) = find_org_project_cluster_ids(
+            &client,
+            signals.clone(),
+            span,
+            identifier.clone(),
+            guard.named_or_active_project(active_cluster.project())?,
+            active_cluster,
+        )?;
+
+        client
+            .set_backup_schedule(
+                org_id,
+                project_id,
+                cluster_id,
+                bucket,
+                &schedule,
+                signals.clone(),
+            )
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+    }
+
+    Ok(PipelineData::empty())
+}
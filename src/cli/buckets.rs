@@ -102,6 +102,7 @@ pub fn get_buckets(
 ) -> Result<Vec<BucketSettings>, ShellError> {
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::GetBuckets,
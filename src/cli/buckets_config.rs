@@ -70,29 +70,45 @@ fn buckets(
         }
     };
 
-    let response = active_cluster
-        .cluster()
-        .http_client()
-        .management_request(
-            ManagementRequest::GetBucket { name },
-            Instant::now().add(active_cluster.timeouts().management_timeout()),
-            signals,
-        )
-        .map_err(|e| client_error_to_shell_error(e, span))?;
-
-    match response.status() {
-        200 => {}
-        _ => {
-            return Err(unexpected_status_code_error(
-                response.status(),
-                response.content()?,
-                span,
-            ));
+    let fetch = |request: ManagementRequest| -> Result<serde_json::Value, ShellError> {
+        let response = active_cluster
+            .cluster()
+            .map_err(|e| client_error_to_shell_error(e, span))?
+            .http_client()
+            .management_request(
+                request,
+                Instant::now().add(active_cluster.timeouts().management_timeout()),
+                signals.clone(),
+            )
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+
+        match response.status() {
+            200 => {}
+            _ => {
+                return Err(unexpected_status_code_error(
+                    response.status(),
+                    response.content()?,
+                    span,
+                ));
+            }
+        }
+
+        serde_json::from_str(&response.content()?)
+            .map_err(|e| deserialize_error(e.to_string(), span))
+    };
+
+    let mut content = fetch(ManagementRequest::GetBucket { name: name.clone() })?;
+
+    // Only the terse config carries the revision of the topology that the data service works from.
+    let terse = fetch(ManagementRequest::GetBucketTerse { name })?;
+    if let Some(config) = content.as_object_mut() {
+        for field in ["rev", "revEpoch"] {
+            if let Some(value) = terse.get(field) {
+                config.insert(field.to_string(), value.clone());
+            }
         }
     }
 
-    let content = serde_json::from_str(&response.content()?)
-        .map_err(|e| deserialize_error(e.to_string(), span))?;
     let converted = convert_json_value_to_nu_value(&content, span)?;
 
     Ok(converted.into_pipeline_data())
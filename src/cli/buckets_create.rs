@@ -193,6 +193,7 @@ pub fn create_server_bucket(
 ) -> Result<(), ShellError> {
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::CreateBucket { payload },
@@ -221,7 +222,9 @@ pub fn create_capella_bucket(
     signals: Signals,
     span: Span,
 ) -> Result<(), ShellError> {
-    let client = org.client();
+    let client = org
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
 
     let org_id = find_org_id(signals.clone(), &client, span)?;
     let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
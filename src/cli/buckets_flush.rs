@@ -85,6 +85,7 @@ fn buckets_flush(
 
         let result = cluster
             .cluster()
+            .map_err(|e| client_error_to_shell_error(e, span))?
             .http_client()
             .management_request(
                 ManagementRequest::FlushBucket { name: name.clone() },
@@ -107,6 +107,7 @@ pub fn get_server_bucket(
 ) -> Result<BucketSettings, ShellError> {
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::GetBucket {
@@ -1,19 +1,23 @@
 use crate::cli::error::client_error_to_shell_error;
 use crate::cli::util::{
-    cluster_identifiers_from, find_org_project_cluster_ids, get_active_cluster, NuValueMap,
+    cluster_identifiers_from, find_org_project_cluster_ids, get_active_cluster, wait_timeout_from,
+    NuValueMap,
 };
-use crate::client::{ClientError, ManagementRequest};
+use crate::client::cloud::{poll_until, PollStatus};
+use crate::client::{CapellaClient, ClientError, ManagementRequest};
 use crate::remote_cluster::RemoteCluster;
 use crate::state::State;
 use nu_engine::command_prelude::Call;
 use nu_engine::CallExt;
 use nu_protocol::engine::{Command, EngineState, Stack};
 use nu_protocol::{
-    Category, IntoPipelineData, PipelineData, ShellError, Signals, Signature, Span, SyntaxShape,
-    Value,
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signals, Signature, Span,
+    SyntaxShape, Value,
 };
+use serde_json::json;
 use std::ops::Add;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::time::Instant;
 
 #[derive(Clone)]
@@ -45,6 +49,12 @@ impl Command for BucketsSample {
                 "the clusters which should be contacted",
                 None,
             )
+            .named(
+                "wait",
+                SyntaxShape::Duration,
+                "wait up to this long for the sample to finish loading",
+                None,
+            )
             .category(Category::Custom("couchbase".to_string()))
     }
 
@@ -52,6 +62,14 @@ impl Command for BucketsSample {
         "Load a sample bucket"
     }
 
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Load the travel sample and wait until all of its documents are loaded",
+            example: "buckets load-sample travel-sample --wait 10min",
+            result: None,
+        }]
+    }
+
     fn run(
         &self,
         engine_state: &EngineState,
@@ -76,6 +94,7 @@ fn load_sample_bucket(
     let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
     let guard = state.lock().unwrap();
     let bucket_name: String = call.req(engine_state, stack, 0)?;
+    let wait_timeout = wait_timeout_from(engine_state, stack, call)?;
 
     let mut results: Vec<Value> = vec![];
     for identifier in cluster_identifiers {
@@ -84,7 +103,8 @@ fn load_sample_bucket(
         let result = if active_cluster.is_capella() {
             let client = guard
                 .named_or_active_org(active_cluster.capella_org())?
-                .client();
+                .client()
+                .map_err(|e| client_error_to_shell_error(e, span))?;
 
             let (org_id, project_id, cluster_id) = find_org_project_cluster_ids(
                 &client,
@@ -97,15 +117,38 @@ fn load_sample_bucket(
 
             client
                 .load_sample_bucket(
-                    org_id,
-                    project_id,
-                    cluster_id,
+                    org_id.clone(),
+                    project_id.clone(),
+                    cluster_id.clone(),
                     bucket_name.clone(),
                     signals.clone(),
                 )
+                .and_then(|_| match wait_timeout {
+                    Some(timeout) => wait_for_capella_sample(
+                        &client,
+                        org_id,
+                        project_id,
+                        cluster_id,
+                        bucket_name.clone(),
+                        timeout,
+                        signals.clone(),
+                    ),
+                    None => Ok(()),
+                })
                 .map_err(|e| client_error_to_shell_error(e, span))
         } else {
-            load_sever_sample(active_cluster, bucket_name.clone(), signals.clone(), span)
+            load_sever_sample(active_cluster, bucket_name.clone(), signals.clone(), span).and_then(
+                |_| match wait_timeout {
+                    Some(timeout) => wait_for_sample(
+                        active_cluster,
+                        bucket_name.clone(),
+                        timeout,
+                        signals.clone(),
+                        span,
+                    ),
+                    None => Ok(()),
+                },
+            )
         };
 
         let mut collected = NuValueMap::default();
@@ -135,6 +178,7 @@ fn load_sever_sample(
 ) -> Result<(), ShellError> {
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::LoadSampleBucket {
@@ -168,3 +212,174 @@ fn load_sever_sample(
     }
     .map_err(|e| client_error_to_shell_error(e, span))
 }
+
+// Sample loading runs as a cluster task, which is listed until the load has either completed or
+// failed. The task is only registered shortly after the load is accepted, so its absence only
+// means the load has finished once it has been seen or the bucket it creates exists, as a small
+// sample can be loaded before the first poll.
+fn wait_for_sample(
+    cluster: &RemoteCluster,
+    sample: String,
+    timeout: Duration,
+    signals: Signals,
+    span: Span,
+) -> Result<(), ShellError> {
+    let mut task_seen = false;
+    poll_until(
+        &format!("sample {}", sample),
+        timeout,
+        signals.clone(),
+        || {
+            let response = cluster.cluster()?.http_client().management_request(
+                ManagementRequest::GetTasks,
+                Instant::now().add(cluster.timeouts().management_timeout()),
+                signals.clone(),
+            )?;
+
+            let status = response.status();
+            let content = response.content().map_err(|e| ClientError::RequestFailed {
+                reason: Some(e.to_string()),
+                key: None,
+            })?;
+            if status != 200 {
+                return Err(ClientError::RequestFailed {
+                    reason: Some(content),
+                    key: None,
+                });
+            }
+
+            let tasks: Vec<serde_json::Value> = serde_json::from_str(&content)?;
+            let bucket_exists = || -> Result<bool, ClientError> {
+                let response = cluster.cluster()?.http_client().management_request(
+                    ManagementRequest::GetBucket {
+                        name: sample.clone(),
+                    },
+                    Instant::now().add(cluster.timeouts().management_timeout()),
+                    signals.clone(),
+                )?;
+                Ok(response.status() == 200)
+            };
+            sample_task_status(&tasks, &sample, &mut task_seen, bucket_exists)
+        },
+    )
+    .map_err(|e| client_error_to_shell_error(e, span))
+}
+
+// Capella does not expose the cluster tasks, so the load is taken to be done once the bucket is
+// listed.
+fn wait_for_capella_sample(
+    client: &CapellaClient,
+    org_id: String,
+    project_id: String,
+    cluster_id: String,
+    sample: String,
+    timeout: Duration,
+    signals: Signals,
+) -> Result<(), ClientError> {
+    poll_until(
+        &format!("sample {}", sample),
+        timeout,
+        signals.clone(),
+        || {
+            let exists = client.bucket_exists(
+                org_id.clone(),
+                project_id.clone(),
+                cluster_id.clone(),
+                sample.clone(),
+                signals.clone(),
+            )?;
+            Ok(match exists {
+                true => PollStatus::Done(()),
+                false => PollStatus::Pending("loading".to_string()),
+            })
+        },
+    )
+}
+
+fn sample_task_status(
+    tasks: &[serde_json::Value],
+    sample: &str,
+    task_seen: &mut bool,
+    bucket_exists: impl FnOnce() -> Result<bool, ClientError>,
+) -> Result<PollStatus<()>, ClientError> {
+    let task = tasks.iter().find(|t| {
+        t.get("type") == Some(&json!("loadingSampleBucket"))
+            && t.get("bucket") == Some(&json!(sample))
+    });
+    if task.is_some() {
+        *task_seen = true;
+    }
+
+    Ok(
+        match task.and_then(|t| t.get("status")).and_then(|s| s.as_str()) {
+            Some("failed") => PollStatus::Failed("failed to load".to_string()),
+            Some("completed") => PollStatus::Done(()),
+            None if *task_seen || bucket_exists()? => PollStatus::Done(()),
+            None => PollStatus::Pending("waiting to start loading".to_string()),
+            Some(_) => PollStatus::Pending("loading".to_string()),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::buckets_sample::sample_task_status;
+    use crate::client::cloud::PollStatus;
+    use serde_json::json;
+
+    #[test]
+    fn sample_task_states() {
+        let tasks = vec![
+            json!({"type": "rebalance", "status": "notRunning"}),
+            json!({"type": "loadingSampleBucket", "status": "running", "bucket": "travel-sample"}),
+            json!({"type": "loadingSampleBucket", "status": "failed", "bucket": "beer-sample"}),
+            json!({"type": "loadingSampleBucket", "status": "completed", "bucket": "gamesim-sample"}),
+        ];
+
+        let mut seen = false;
+        assert!(matches!(
+            sample_task_status(&tasks, "travel-sample", &mut seen, || Ok(false)).unwrap(),
+            PollStatus::Pending(_)
+        ));
+        assert!(seen);
+        assert!(matches!(
+            sample_task_status(&tasks, "beer-sample", &mut false, || Ok(false)).unwrap(),
+            PollStatus::Failed(_)
+        ));
+        assert!(matches!(
+            sample_task_status(&tasks, "gamesim-sample", &mut false, || Ok(false)).unwrap(),
+            PollStatus::Done(())
+        ));
+    }
+
+    #[test]
+    fn sample_task_not_yet_seen() {
+        let mut seen = false;
+        assert!(matches!(
+            sample_task_status(&[], "travel-sample", &mut seen, || Ok(false)).unwrap(),
+            PollStatus::Pending(_)
+        ));
+
+        let running = vec![
+            json!({"type": "loadingSampleBucket", "status": "running", "bucket": "travel-sample"}),
+        ];
+        assert!(matches!(
+            sample_task_status(&running, "travel-sample", &mut seen, || Ok(false)).unwrap(),
+            PollStatus::Pending(_)
+        ));
+
+        // Once reaped the task is no longer listed.
+        assert!(matches!(
+            sample_task_status(&[], "travel-sample", &mut seen, || Ok(false)).unwrap(),
+            PollStatus::Done(())
+        ));
+    }
+
+    #[test]
+    fn sample_loaded_before_first_poll() {
+        assert!(matches!(
+            sample_task_status(&[], "travel-sample", &mut false, || Ok(true)).unwrap(),
+            PollStatus::Done(())
+        ));
+    }
+}
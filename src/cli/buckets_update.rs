@@ -131,7 +131,8 @@ fn buckets_update(
         if active_cluster.is_capella() {
             let client = guard
                 .named_or_active_org(active_cluster.capella_org())?
-                .client();
+                .client()
+                .map_err(|e| client_error_to_shell_error(e, span))?;
 
             let (org_id, project_id, cluster_id) = find_org_project_cluster_ids(
                 &client,
@@ -174,6 +175,7 @@ fn update_server_bucket(
 
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::UpdateBucket {
@@ -1,4 +1,5 @@
 use crate::state::State;
+use log::warn;
 use std::sync::{Arc, Mutex};
 
 use nu_engine::command_prelude::Call;
@@ -44,7 +45,27 @@ impl Command for CbEnvCluster {
         _input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
         let guard = self.state.lock().unwrap();
-        guard.set_active(call.req(engine_state, stack, 0)?)?;
+        let identifier: String = call.req(engine_state, stack, 0)?;
+
+        if let Some(txn) = guard.active_transaction() {
+            if txn.cluster() != identifier {
+                warn!(
+                    "Query transaction {} is still open against {}, statements in it continue to run there",
+                    txn.id(),
+                    txn.cluster()
+                );
+            }
+        }
+        if let Some(txn) = guard.kv_transaction() {
+            if txn.cluster != identifier {
+                warn!(
+                    "Transaction {} is still open against {}, doc commands continue to run there until it ends",
+                    &txn.id, &txn.cluster
+                );
+            }
+        }
+
+        guard.set_active(identifier)?;
 
         Ok(PipelineData::Value(Value::nothing(call.head), None))
     }
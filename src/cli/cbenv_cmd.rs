@@ -166,6 +166,12 @@ fn use_cmd(
         }
     }
 
+    results.add_string(
+        "transaction",
+        guard.open_transaction_id().unwrap_or_default(),
+        span,
+    );
+
     if let Some(llm_id) = guard.active_llm_id() {
         results.add_string("llm", llm_id, span);
     }
@@ -1,10 +1,9 @@
 use crate::cli::error::generic_error;
 use crate::cli::util::{get_username_and_password, read_config_file, update_config_file};
 use crate::config::{ClusterConfig, DEFAULT_KV_BATCH_SIZE};
+use crate::connection_string::ConnectionString;
 use crate::state::State;
-use crate::{
-    ClusterTimeouts, RemoteCluster, RemoteClusterResources, RemoteClusterType, RustTlsConfig,
-};
+use crate::{RemoteCluster, RemoteClusterResources, RustTlsConfig};
 use nu_engine::command_prelude::Call;
 use nu_engine::CallExt;
 use nu_protocol::engine::{Command, EngineState, Stack};
@@ -93,6 +92,18 @@ impl Command for CbEnvRegister {
                 "whether or not to accept all certs with tls, defaults to true",
                 None,
             )
+            .named(
+                "tls-client-cert-path",
+                SyntaxShape::String,
+                "the path to the client certificate to authenticate with instead of a password",
+                None,
+            )
+            .named(
+                "tls-client-key-path",
+                SyntaxShape::String,
+                "the path to the private key of the client certificate",
+                None,
+            )
             .switch(
                 "save",
                 "whether or not to add the cluster to the .cbsh config file, defaults to false",
@@ -141,26 +152,33 @@ fn clusters_register(
     let bucket = call.get_flag(engine_state, stack, "default-bucket")?;
     let scope = call.get_flag(engine_state, stack, "default-scope")?;
     let collection = call.get_flag(engine_state, stack, "default-collection")?;
-    let tls_enabled = call
-        .get_flag(engine_state, stack, "tls-enabled")?
-        .unwrap_or(true);
-    let tls_accept_all_certs = call
-        .get_flag(engine_state, stack, "tls-accept-all-certs")?
-        .unwrap_or(true);
+    let tls_enabled: Option<bool> = call.get_flag(engine_state, stack, "tls-enabled")?;
+    let tls_accept_all_certs: Option<bool> =
+        call.get_flag(engine_state, stack, "tls-accept-all-certs")?;
     let cert_path = call.get_flag(engine_state, stack, "tls-cert-path")?;
+    let client_cert_path: Option<String> =
+        call.get_flag(engine_state, stack, "tls-client-cert-path")?;
+    let client_key_path = call.get_flag(engine_state, stack, "tls-client-key-path")?;
     let save = call.has_flag(engine_state, stack, "save")?;
     let capella = call.get_flag(engine_state, stack, "capella-organization")?;
     let project = call.get_flag(engine_state, stack, "project")?;
     let display_name = call.get_flag(engine_state, stack, "display-name")?;
 
-    let hostnames = conn_string
-        .split(',')
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>();
+    let conn_string =
+        ConnectionString::parse(&conn_string).map_err(|e| generic_error(e, None, call.head))?;
+    // The flags take precedence over the connection string.
+    let tls_enabled = tls_enabled.or(conn_string.tls_enabled()).unwrap_or(true);
     let tls_config = if tls_enabled {
         Some(
-            RustTlsConfig::new(tls_accept_all_certs, cert_path)
-                .map_err(|e| generic_error(e.message(), e.expanded_message(), None))?,
+            RustTlsConfig::new(
+                tls_accept_all_certs
+                    .or(conn_string.accept_all_certs())
+                    .unwrap_or(true),
+                cert_path,
+                client_cert_path.clone(),
+                client_key_path,
+            )
+            .map_err(|e| generic_error(e.message(), e.expanded_message(), None))?,
         )
     } else {
         None
@@ -169,24 +187,36 @@ fn clusters_register(
     let username_flag = call.get_flag(engine_state, stack, "username")?;
     let password_flag = call.get_flag(engine_state, stack, "password")?;
 
-    let (username, password) = get_username_and_password(username_flag, password_flag)?;
+    // The client certificate identifies the user, so there is nothing to prompt for.
+    let (username, password) = if tls_enabled && client_cert_path.is_some() {
+        (
+            username_flag.unwrap_or_default(),
+            password_flag.unwrap_or_default(),
+        )
+    } else {
+        get_username_and_password(username_flag, password_flag)?
+    };
 
+    let timeouts = conn_string.timeouts();
+    let cluster_type = conn_string.cluster_type();
     let cluster = RemoteCluster::new(
         RemoteClusterResources {
-            hostnames: hostnames.clone(),
+            conn_string,
             username,
-            password,
+            password: password.into(),
+            sasl_mechanism: None,
+            proxy: None,
             active_bucket: bucket,
             active_scope: scope,
             active_collection: collection,
             display_name,
         },
         tls_config,
-        ClusterTimeouts::default(),
+        timeouts,
         capella,
         project,
         DEFAULT_KV_BATCH_SIZE,
-        RemoteClusterType::from(hostnames),
+        cluster_type,
     );
 
     let mut guard = state.lock().unwrap();
@@ -199,7 +229,7 @@ fn clusters_register(
     Ok(PipelineData::Value(Value::nothing(call.head), None))
 }
 
-fn save_new_cluster_config(
+pub(crate) fn save_new_cluster_config(
     guard: &mut MutexGuard<State>,
     span: Span,
     identifier: String,
@@ -0,0 +1,95 @@
+use crate::cli::generic_error;
+use crate::cli::util::NuValueMap;
+use crate::state::State;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct UseTrace {
+    state: Arc<Mutex<State>>,
+}
+
+impl UseTrace {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for UseTrace {
+    fn name(&self) -> &str {
+        "cb-env trace"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("cb-env trace")
+            .named(
+                "threshold",
+                SyntaxShape::Int,
+                "log operations which take at least this long (in ms)",
+                None,
+            )
+            .switch("disable", "stop logging slow operations", None)
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Logs data, query, search, analytics and management operations slower than a threshold"
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let threshold: Option<i64> = call.get_flag(engine_state, stack, "threshold")?;
+
+        let guard = self.state.lock().unwrap();
+        if call.has_flag(engine_state, stack, "disable")? {
+            guard.set_slow_operation_threshold(None);
+        } else if let Some(t) = threshold {
+            if t < 0 {
+                return Err(generic_error(
+                    format!("Invalid threshold {}", t),
+                    "The threshold must be zero or more milliseconds".to_string(),
+                    call.head,
+                ));
+            }
+            guard.set_slow_operation_threshold(Some(Duration::from_millis(t as u64)));
+        }
+
+        let mut using_now = NuValueMap::default();
+        match guard.slow_operation_threshold() {
+            Some(t) => {
+                using_now.add_bool("enabled", true, call.head);
+                using_now.add_i64("threshold (ms)", t.as_millis() as i64, call.head);
+            }
+            None => {
+                using_now.add_bool("enabled", false, call.head);
+                using_now.add("threshold (ms)", Value::nothing(call.head));
+            }
+        }
+
+        Ok(using_now.into_pipeline_data(call.head))
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Log every operation taking 500ms or longer",
+                example: "cb-env trace --threshold 500",
+                result: None,
+            },
+            Example {
+                description: "Stop logging slow operations",
+                example: "cb-env trace --disable",
+                result: None,
+            },
+        ]
+    }
+}
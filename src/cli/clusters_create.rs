@@ -6,7 +6,7 @@ use std::sync::{Arc, Mutex};
 
 use crate::cli::error::{client_error_to_shell_error, serialize_error};
 use crate::cli::generic_error;
-use crate::cli::util::{find_org_id, find_project_id};
+use crate::cli::util::{find_org_id, find_project_id, wait_timeout_from};
 use crate::read_input;
 use nu_engine::command_prelude::Call;
 use nu_engine::CallExt;
@@ -77,6 +77,12 @@ impl Command for ClustersCreate {
                 None,
             )
             .switch("yes", "answer yes to confirmation prompt", Some('y'))
+            .named(
+                "wait",
+                SyntaxShape::Duration,
+                "wait up to this long for the cluster to be deployed",
+                None,
+            )
             .category(Category::Custom("couchbase".to_string()))
     }
 
@@ -208,7 +214,9 @@ fn clusters_create(
     let capella: Option<String> = call.get_flag(engine_state, stack, "capella")?;
     let guard = state.lock().unwrap();
     let control = guard.named_or_active_org(capella)?;
-    let client = control.client();
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
 
     let project =
         guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
@@ -216,19 +224,34 @@ fn clusters_create(
     let org_id = find_org_id(signals.clone(), &client, span)?;
     let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
 
+    let name = definition.name();
     if free_tier {
         let free_tier_def = FreeTierClusterCreateRequest::from(definition);
         let payload = serde_json::to_string(&free_tier_def)
             .map_err(|e| serialize_error(e.to_string(), span))?;
         client
-            .create_free_tier_cluster(org_id, project_id, payload, signals)
+            .create_free_tier_cluster(org_id.clone(), project_id.clone(), payload, signals.clone())
             .map_err(|e| client_error_to_shell_error(e, span))?;
     } else {
         let payload =
             serde_json::to_string(&definition).map_err(|e| serialize_error(e.to_string(), span))?;
         client
-            .create_cluster(org_id, project_id, payload, signals)
+            .create_cluster(org_id.clone(), project_id.clone(), payload, signals.clone())
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+    }
+
+    if let Some(timeout) = wait_timeout_from(engine_state, stack, call)? {
+        client
+            .wait_for_cluster_state(
+                name.clone(),
+                org_id,
+                project_id,
+                &["healthy"],
+                timeout,
+                signals,
+            )
             .map_err(|e| client_error_to_shell_error(e, span))?;
+        info!("cluster {} is healthy", name);
     }
 
     Ok(PipelineData::empty())
@@ -1,9 +1,9 @@
 use crate::state::State;
-use log::debug;
+use log::{debug, info};
 use std::sync::{Arc, Mutex};
 
 use crate::cli::error::client_error_to_shell_error;
-use crate::cli::util::{find_org_id, find_project_id};
+use crate::cli::util::{find_org_id, find_project_id, wait_timeout_from};
 use nu_engine::command_prelude::Call;
 use nu_engine::CallExt;
 use nu_protocol::engine::{Command, EngineState, Stack};
@@ -40,6 +40,12 @@ impl Command for ClustersDrop {
                 "the Capella project to use",
                 None,
             )
+            .named(
+                "wait",
+                SyntaxShape::Duration,
+                "wait up to this long for the cluster to be deleted",
+                None,
+            )
             .category(Category::Custom("couchbase".to_string()))
     }
 
@@ -79,19 +85,38 @@ fn clusters_drop(
     let project =
         guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
 
-    let client = control.client();
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
 
     let org_id = find_org_id(signals.clone(), &client, span)?;
     let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
 
     let cluster = client
-        .get_cluster(name, org_id.clone(), project_id.clone(), signals.clone())
+        .get_cluster(
+            name.clone(),
+            org_id.clone(),
+            project_id.clone(),
+            signals.clone(),
+        )
         .map_err(|e| client_error_to_shell_error(e, span))?;
 
     client
-        .delete_cluster(org_id, project_id, cluster.id(), signals)
+        .delete_cluster(
+            org_id.clone(),
+            project_id.clone(),
+            cluster.id(),
+            signals.clone(),
+        )
         .map_err(|e| client_error_to_shell_error(e, span))?;
 
+    if let Some(timeout) = wait_timeout_from(engine_state, stack, call)? {
+        client
+            .wait_for_cluster_deleted(name.clone(), org_id, project_id, timeout, signals)
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+        info!("cluster {} has been deleted", name);
+    }
+
     let identifier = {
         guard
             .clusters()
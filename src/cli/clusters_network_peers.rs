@@ -0,0 +1,115 @@
+use crate::state::State;
+use log::debug;
+use std::sync::{Arc, Mutex};
+
+use crate::cli::error::client_error_to_shell_error;
+use crate::cli::util::{convert_json_value_to_nu_value, find_org_id, find_project_id, NuValueMap};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
+};
+
+#[derive(Clone)]
+pub struct ClustersNetworkPeers {
+    state: Arc<Mutex<State>>,
+}
+
+impl ClustersNetworkPeers {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for ClustersNetworkPeers {
+    fn name(&self) -> &str {
+        "clusters network-peers"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("clusters network-peers")
+            .required("name", SyntaxShape::String, "the name of the cluster")
+            .named(
+                "capella",
+                SyntaxShape::String,
+                "the Capella organization to use",
+                None,
+            )
+            .named(
+                "project",
+                SyntaxShape::String,
+                "the Capella project to use",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Lists the network peers of a cluster in the active Capella project"
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        clusters_network_peers(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn clusters_network_peers(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let capella = call.get_flag(engine_state, stack, "capella")?;
+
+    debug!("Running clusters network-peers for {}", &name);
+
+    let guard = state.lock().unwrap();
+    let control = guard.named_or_active_org(capella)?;
+
+    let project =
+        guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
+
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+    let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
+
+    let cluster = client
+        .get_cluster(name, org_id.clone(), project_id.clone(), signals.clone())
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let peers = client
+        .list_network_peers(org_id, project_id, cluster.id(), signals)
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let mut results = vec![];
+    for peer in peers.data() {
+        let mut collected = NuValueMap::default();
+        collected.add_string("name", peer.name(), span);
+        collected.add_string("id", peer.id(), span);
+        collected.add_string("provider", peer.provider(), span);
+        collected.add_string("state", peer.state(), span);
+        collected.add_string("reasoning", peer.reasoning(), span);
+        collected.add(
+            "provider config",
+            convert_json_value_to_nu_value(peer.provider_config(), span)?,
+        );
+        results.push(collected.into_value(span));
+    }
+
+    Ok(Value::list(results, span).into_pipeline_data())
+}
@@ -0,0 +1,137 @@
+use crate::state::State;
+use log::{debug, info};
+use std::sync::{Arc, Mutex};
+
+use crate::cli::error::client_error_to_shell_error;
+use crate::cli::util::{find_org_id, find_project_id, wait_timeout_from};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape};
+
+#[derive(Clone)]
+pub struct ClustersOff {
+    state: Arc<Mutex<State>>,
+}
+
+impl ClustersOff {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for ClustersOff {
+    fn name(&self) -> &str {
+        "clusters off"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("clusters off")
+            .required("name", SyntaxShape::String, "the name of the cluster")
+            .named(
+                "wait",
+                SyntaxShape::Duration,
+                "wait up to this long for the cluster to finish turning off",
+                None,
+            )
+            .named(
+                "capella",
+                SyntaxShape::String,
+                "the Capella organization to use",
+                None,
+            )
+            .named(
+                "project",
+                SyntaxShape::String,
+                "the Capella project to use",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Turns off a cluster in the active Capella project"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Turn off a cluster",
+                example: "clusters off dev-cluster",
+                result: None,
+            },
+            Example {
+                description: "Turn off a cluster and wait until it is off",
+                example: "clusters off dev-cluster --wait 30min",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        clusters_off(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn clusters_off(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let capella = call.get_flag(engine_state, stack, "capella")?;
+    let wait_timeout = wait_timeout_from(engine_state, stack, call)?;
+
+    debug!("Running clusters off for {}", &name);
+
+    let guard = state.lock().unwrap();
+    let control = guard.named_or_active_org(capella)?;
+
+    let project =
+        guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
+
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+    let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
+
+    let cluster = client
+        .get_cluster(
+            name.clone(),
+            org_id.clone(),
+            project_id.clone(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    client
+        .turn_cluster_off(
+            org_id.clone(),
+            project_id.clone(),
+            cluster.id(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    if let Some(timeout) = wait_timeout {
+        let cluster = client
+            .wait_for_cluster_state(name, org_id, project_id, &["turnedOff"], timeout, signals)
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+        info!("cluster {} is {}", cluster.name(), cluster.state());
+    }
+
+    Ok(PipelineData::empty())
+}
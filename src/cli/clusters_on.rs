@@ -0,0 +1,144 @@
+use crate::state::State;
+use log::{debug, info};
+use std::sync::{Arc, Mutex};
+
+use crate::cli::error::client_error_to_shell_error;
+use crate::cli::util::{find_org_id, find_project_id, wait_timeout_from};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape};
+
+#[derive(Clone)]
+pub struct ClustersOn {
+    state: Arc<Mutex<State>>,
+}
+
+impl ClustersOn {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for ClustersOn {
+    fn name(&self) -> &str {
+        "clusters on"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("clusters on")
+            .required("name", SyntaxShape::String, "the name of the cluster")
+            .switch(
+                "linked-app-service",
+                "also turn on the app service linked to the cluster",
+                None,
+            )
+            .named(
+                "wait",
+                SyntaxShape::Duration,
+                "wait up to this long for the cluster to finish turning on",
+                None,
+            )
+            .named(
+                "capella",
+                SyntaxShape::String,
+                "the Capella organization to use",
+                None,
+            )
+            .named(
+                "project",
+                SyntaxShape::String,
+                "the Capella project to use",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Turns on a cluster in the active Capella project"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Turn on a cluster and its linked app service",
+                example: "clusters on dev-cluster --linked-app-service",
+                result: None,
+            },
+            Example {
+                description: "Turn on a cluster and wait until it is healthy",
+                example: "clusters on dev-cluster --wait 30min",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        clusters_on(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn clusters_on(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let capella = call.get_flag(engine_state, stack, "capella")?;
+    let linked_app_service = call.has_flag(engine_state, stack, "linked-app-service")?;
+    let wait_timeout = wait_timeout_from(engine_state, stack, call)?;
+
+    debug!("Running clusters on for {}", &name);
+
+    let guard = state.lock().unwrap();
+    let control = guard.named_or_active_org(capella)?;
+
+    let project =
+        guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
+
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+    let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
+
+    let cluster = client
+        .get_cluster(
+            name.clone(),
+            org_id.clone(),
+            project_id.clone(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    client
+        .turn_cluster_on(
+            org_id.clone(),
+            project_id.clone(),
+            cluster.id(),
+            linked_app_service,
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    if let Some(timeout) = wait_timeout {
+        let cluster = client
+            .wait_for_cluster_state(name, org_id, project_id, &["healthy"], timeout, signals)
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+        info!("cluster {} is {}", cluster.name(), cluster.state());
+    }
+
+    Ok(PipelineData::empty())
+}
@@ -0,0 +1,108 @@
+use crate::state::State;
+use log::debug;
+use std::sync::{Arc, Mutex};
+
+use crate::cli::error::client_error_to_shell_error;
+use crate::cli::util::{find_org_id, find_project_id, NuValueMap};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
+};
+
+#[derive(Clone)]
+pub struct ClustersPrivateEndpoints {
+    state: Arc<Mutex<State>>,
+}
+
+impl ClustersPrivateEndpoints {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for ClustersPrivateEndpoints {
+    fn name(&self) -> &str {
+        "clusters private-endpoints"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("clusters private-endpoints")
+            .required("name", SyntaxShape::String, "the name of the cluster")
+            .named(
+                "capella",
+                SyntaxShape::String,
+                "the Capella organization to use",
+                None,
+            )
+            .named(
+                "project",
+                SyntaxShape::String,
+                "the Capella project to use",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Lists the private endpoints of a cluster in the active Capella project"
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        clusters_private_endpoints(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn clusters_private_endpoints(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let capella = call.get_flag(engine_state, stack, "capella")?;
+
+    debug!("Running clusters private-endpoints for {}", &name);
+
+    let guard = state.lock().unwrap();
+    let control = guard.named_or_active_org(capella)?;
+
+    let project =
+        guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
+
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+    let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
+
+    let cluster = client
+        .get_cluster(name, org_id.clone(), project_id.clone(), signals.clone())
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let endpoints = client
+        .list_private_endpoints(org_id, project_id, cluster.id(), signals)
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let mut results = vec![];
+    for endpoint in endpoints.endpoints() {
+        let mut collected = NuValueMap::default();
+        collected.add_string("id", endpoint.id(), span);
+        collected.add_string("status", endpoint.status(), span);
+        results.push(collected.into_value(span));
+    }
+
+    Ok(Value::list(results, span).into_pipeline_data())
+}
@@ -0,0 +1,176 @@
+use crate::state::State;
+use log::{debug, info};
+use std::sync::{Arc, Mutex};
+
+use crate::cli::error::{client_error_to_shell_error, generic_error, serialize_error};
+use crate::cli::util::{
+    convert_json_value_to_nu_value, convert_nu_value_to_json_value, find_org_id, find_project_id,
+};
+use crate::client::cloud_json::OnOffSchedule;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
+};
+
+#[derive(Clone)]
+pub struct ClustersSchedule {
+    state: Arc<Mutex<State>>,
+}
+
+impl ClustersSchedule {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for ClustersSchedule {
+    fn name(&self) -> &str {
+        "clusters schedule"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("clusters schedule")
+            .required("name", SyntaxShape::String, "the name of the cluster")
+            .optional(
+                "schedule",
+                SyntaxShape::Record(vec![]),
+                "the on/off schedule to set for the cluster",
+            )
+            .switch("remove", "remove the on/off schedule", None)
+            .named(
+                "capella",
+                SyntaxShape::String,
+                "the Capella organization to use",
+                None,
+            )
+            .named(
+                "project",
+                SyntaxShape::String,
+                "the Capella project to use",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Gets, sets or removes the on/off schedule of a cluster in the active Capella project"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Get the on/off schedule of a cluster",
+                example: "clusters schedule dev-cluster",
+                result: None,
+            },
+            Example {
+                description: "Only run a cluster during working hours on weekdays",
+                example: "clusters schedule dev-cluster {timezone: \"US/Pacific\", days: ([monday tuesday wednesday thursday friday] | each {|d| {day: $d, state: custom, from: {hour: 8}, to: {hour: 18}}} | append [{day: saturday, state: off} {day: sunday, state: off}])}",
+                result: None,
+            },
+            Example {
+                description: "Remove the on/off schedule of a cluster",
+                example: "clusters schedule dev-cluster --remove",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        clusters_schedule(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn clusters_schedule(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let schedule = match call.opt::<Value>(engine_state, stack, 1)? {
+        Some(v) => Some(v),
+        None => match input.into_value(span)? {
+            Value::Nothing { .. } => None,
+            v => Some(v),
+        },
+    };
+    let remove = call.has_flag(engine_state, stack, "remove")?;
+    let capella = call.get_flag(engine_state, stack, "capella")?;
+
+    debug!("Running clusters schedule for {}", &name);
+
+    let guard = state.lock().unwrap();
+    let control = guard.named_or_active_org(capella)?;
+
+    let project =
+        guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
+
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+    let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
+
+    let cluster = client
+        .get_cluster(
+            name.clone(),
+            org_id.clone(),
+            project_id.clone(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    if remove {
+        client
+            .delete_on_off_schedule(org_id, project_id, cluster.id(), signals)
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+        return Ok(PipelineData::empty());
+    }
+
+    if let Some(schedule) = schedule {
+        let json = convert_nu_value_to_json_value(&schedule, span)?;
+        let schedule: OnOffSchedule =
+            serde_json::from_value(json).map_err(|e| {
+            generic_error(
+                format!("Could not parse on/off schedule: {}", e),
+                "The schedule must contain a timezone and the state of every day of the week. Run 'clusters schedule --help' for an example".to_string(),
+                span,
+            )
+        })?;
+
+        client
+            .set_on_off_schedule(org_id, project_id, cluster.id(), &schedule, signals)
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+        return Ok(PipelineData::empty());
+    }
+
+    let schedule = client
+        .get_on_off_schedule(org_id, project_id, cluster.id(), signals)
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    match schedule {
+        Some(schedule) => {
+            let json =
+                serde_json::to_value(schedule).map_err(|e| serialize_error(e.to_string(), span))?;
+            Ok(convert_json_value_to_nu_value(&json, span)?.into_pipeline_data())
+        }
+        None => {
+            info!("cluster {} has no on/off schedule", &name);
+            Ok(PipelineData::empty())
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use crate::cli::error::{client_error_to_shell_error, serialize_error};
+use crate::cli::generic_error;
+use crate::cli::util::{
+    convert_json_value_to_nu_value, convert_nu_value_to_json_value, find_org_id, find_project_id,
+    NuValueMap,
+};
+use crate::client::cloud_json::ClusterUpdateRequest;
+use crate::read_input;
+use crate::state::State;
+use log::{debug, info, warn};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Span, SyntaxShape,
+    Value,
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct ClustersUpdate {
+    state: Arc<Mutex<State>>,
+}
+
+impl ClustersUpdate {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for ClustersUpdate {
+    fn name(&self) -> &str {
+        "clusters update"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("clusters update")
+            .required("name", SyntaxShape::String, "the name of the cluster")
+            .optional(
+                "spec",
+                SyntaxShape::Any,
+                "the parts of the cluster spec to change, as a record or json string",
+            )
+            .named(
+                "capella",
+                SyntaxShape::String,
+                "the Capella organization to use",
+                None,
+            )
+            .named(
+                "project",
+                SyntaxShape::String,
+                "the Capella project to use",
+                None,
+            )
+            .switch("yes", "answer yes to confirmation prompt", Some('y'))
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Updates the spec of a cluster in the active Capella Project"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Change the support plan of a cluster",
+                example: "clusters update my-cluster {support: {plan: enterprise, timezone: GMT}}",
+                result: None,
+            },
+            Example {
+                description: "Scale a cluster to 5 nodes without confirmation",
+                example: "clusters update my-cluster {serviceGroups: [{node: {compute: {cpu: 4, ram: 16}, disk: {type: gp3, storage: 50, iops: 3000}}, numOfNodes: 5, services: [data, index, query]}]} --yes",
+                result: None,
+            },
+            Example {
+                description: "Update a cluster from a saved spec",
+                example: "open spec.json | clusters update my-cluster",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        clusters_update(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn clusters_update(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let spec = match call.opt::<Value>(engine_state, stack, 1)? {
+        Some(v) => v,
+        None => input.into_value(span)?,
+    };
+    let spec = match spec {
+        Value::String { val, .. } => serde_json::from_str(&val)
+            .map_err(|e| generic_error("Could not parse the cluster spec", e.to_string(), span))?,
+        Value::Record { .. } => convert_nu_value_to_json_value(&spec, span)?,
+        _ => {
+            return Err(generic_error(
+                "No cluster spec provided",
+                "Provide the parts of the spec to change as a record or json string".to_string(),
+                span,
+            ));
+        }
+    };
+
+    let capella = call.get_flag(engine_state, stack, "capella")?;
+    let guard = state.lock().unwrap();
+    let control = guard.named_or_active_org(capella)?;
+    let project =
+        guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+    let project_id = find_project_id(signals.clone(), project, &client, span, org_id.clone())?;
+
+    let cluster = client
+        .get_cluster(
+            name.clone(),
+            org_id.clone(),
+            project_id.clone(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let current = serde_json::to_value(ClusterUpdateRequest::from(&cluster))
+        .map_err(|e| serialize_error(e.to_string(), span))?;
+    let mut updated = current.clone();
+    merge_json(&mut updated, spec);
+
+    let changes = json_diff(&current, &updated);
+    if changes.is_empty() {
+        info!("cluster {} already matches the spec", &name);
+        return Ok(PipelineData::empty());
+    }
+
+    if !call.has_flag(engine_state, stack, "yes")? {
+        for (field, from, to) in &changes {
+            println!("{}: {} -> {}", field, from, to);
+        }
+        warn!("apply these changes to {}? (y/n)", &name);
+
+        let confirmation = read_input().unwrap_or("n".to_string());
+        if !confirmation.starts_with('y') {
+            info!("cluster update canceled");
+            return Ok(PipelineData::empty());
+        }
+    }
+
+    debug!("Running clusters update for {} with {}", &name, &updated);
+
+    let payload =
+        serde_json::to_string(&updated).map_err(|e| serialize_error(e.to_string(), span))?;
+    client
+        .update_cluster(org_id, project_id, cluster.id(), payload, signals)
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let mut results = vec![];
+    for (field, from, to) in changes {
+        results.push(change_to_value(field, &from, &to, span)?);
+    }
+
+    Ok(Value::list(results, span).into_pipeline_data())
+}
+
+fn change_to_value(
+    field: String,
+    from: &serde_json::Value,
+    to: &serde_json::Value,
+    span: Span,
+) -> Result<Value, ShellError> {
+    let mut collected = NuValueMap::default();
+    collected.add_string("field", field, span);
+    collected.add("current", convert_json_value_to_nu_value(from, span)?);
+    collected.add("updated", convert_json_value_to_nu_value(to, span)?);
+    Ok(collected.into_value(span))
+}
+
+// Merges a partial spec into the current one. Objects are merged field by field while anything
+// else, including arrays, is replaced outright.
+fn merge_json(base: &mut serde_json::Value, patch: serde_json::Value) {
+    match (base, patch) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(patch)) => {
+            for (k, v) in patch {
+                match base.get_mut(&k) {
+                    Some(existing) => merge_json(existing, v),
+                    None => {
+                        base.insert(k, v);
+                    }
+                }
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+// Lists the fields which differ between two specs, along with their before and after values.
+fn json_diff(
+    before: &serde_json::Value,
+    after: &serde_json::Value,
+) -> Vec<(String, serde_json::Value, serde_json::Value)> {
+    let mut changes = vec![];
+    diff_at("", before, after, &mut changes);
+    changes
+}
+
+fn diff_at(
+    path: &str,
+    before: &serde_json::Value,
+    after: &serde_json::Value,
+    changes: &mut Vec<(String, serde_json::Value, serde_json::Value)>,
+) {
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+
+    match (before, after) {
+        (serde_json::Value::Object(b), serde_json::Value::Object(a)) => {
+            let mut keys: Vec<&String> = b.keys().chain(a.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for k in keys {
+                let null = serde_json::Value::Null;
+                diff_at(
+                    &child(k),
+                    b.get(k).unwrap_or(&null),
+                    a.get(k).unwrap_or(&null),
+                    changes,
+                );
+            }
+        }
+        (serde_json::Value::Array(b), serde_json::Value::Array(a)) if b.len() == a.len() => {
+            for (i, (b, a)) in b.iter().zip(a.iter()).enumerate() {
+                diff_at(&format!("{}[{}]", path, i), b, a, changes);
+            }
+        }
+        (b, a) => {
+            if b != a {
+                changes.push((path.to_string(), b.clone(), a.clone()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::clusters_update::{json_diff, merge_json};
+    use serde_json::json;
+
+    #[test]
+    fn merge_partial_spec() {
+        let mut spec = json!({
+            "name": "dev",
+            "support": {"plan": "basic", "timezone": "GMT"},
+            "serviceGroups": [{"numOfNodes": 3}],
+        });
+        merge_json(
+            &mut spec,
+            json!({"support": {"plan": "enterprise"}, "serviceGroups": [{"numOfNodes": 5}]}),
+        );
+
+        assert_eq!(
+            json!({
+                "name": "dev",
+                "support": {"plan": "enterprise", "timezone": "GMT"},
+                "serviceGroups": [{"numOfNodes": 5}],
+            }),
+            spec
+        );
+    }
+
+    #[test]
+    fn diff_specs() {
+        let before = json!({
+            "name": "dev",
+            "support": {"plan": "basic"},
+            "serviceGroups": [{"numOfNodes": 3, "node": {"disk": {"autoExpansion": false}}}],
+        });
+        let after = json!({
+            "name": "dev",
+            "support": {"plan": "enterprise"},
+            "serviceGroups": [{"numOfNodes": 5, "node": {"disk": {"autoExpansion": false}}}],
+        });
+
+        assert_eq!(
+            vec![
+                (
+                    "serviceGroups[0].numOfNodes".to_string(),
+                    json!(3),
+                    json!(5)
+                ),
+                (
+                    "support.plan".to_string(),
+                    json!("basic"),
+                    json!("enterprise")
+                ),
+            ],
+            json_diff(&before, &after)
+        );
+    }
+}
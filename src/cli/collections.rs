@@ -103,7 +103,8 @@ fn collections_get(
         let collections = if active_cluster.is_capella() {
             let client = guard
                 .named_or_active_org(active_cluster.capella_org())?
-                .client();
+                .client()
+                .map_err(|e| client_error_to_shell_error(e, span))?;
 
             let (org_id, project_id, cluster_id) = find_org_project_cluster_ids(
                 &client,
@@ -212,6 +213,7 @@ fn get_server_collections(
 ) -> Result<Vec<Collection>, ShellError> {
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::GetCollections { bucket },
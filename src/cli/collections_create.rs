@@ -108,7 +108,8 @@ fn collections_create(
         if active_cluster.is_capella() {
             let client = guard
                 .named_or_active_org(active_cluster.capella_org())?
-                .client();
+                .client()
+                .map_err(|e| client_error_to_shell_error(e, span))?;
 
             let (org_id, project_id, cluster_id) = find_org_project_cluster_ids(
                 &client,
@@ -159,6 +160,7 @@ fn create_server_collection(
 
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             CreateCollection {
@@ -74,7 +74,9 @@ fn columnar_clusters(
 
     let project =
         guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
-    let client = control.client();
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
 
     let org_id = find_org_id(signals.clone(), &client, span)?;
     let project_id = find_project_id(
@@ -1,4 +1,4 @@
-use crate::cli::util::{find_org_id, find_project_id};
+use crate::cli::util::{find_org_id, find_project_id, wait_timeout_from};
 use crate::cli::{client_error_to_shell_error, generic_error, serialize_error};
 use crate::client::cloud_json::ColumnarClusterCreateRequest;
 use crate::state::State;
@@ -47,6 +47,12 @@ impl Command for ColumnarClustersCreate {
                 "the Capella project to use",
                 None,
             )
+            .named(
+                "wait",
+                SyntaxShape::Duration,
+                "wait up to this long for the cluster to be deployed",
+                None,
+            )
             .category(Category::Custom("couchbase".to_string()))
     }
 
@@ -119,7 +125,9 @@ fn columnar_clusters_create(
 
     let guard = state.lock().unwrap();
     let control = guard.named_or_active_org(capella)?;
-    let client = control.client();
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
 
     let project =
         guard.named_or_active_project(call.get_flag(engine_state, stack, "project")?)?;
@@ -130,9 +138,24 @@ fn columnar_clusters_create(
     let payload =
         serde_json::to_string(&definition).map_err(|e| serialize_error(e.to_string(), span))?;
     client
-        .create_columnar_cluster(org_id, project_id, payload, signals)
+        .create_columnar_cluster(org_id.clone(), project_id.clone(), payload, signals.clone())
         .map_err(|e| client_error_to_shell_error(e, span))?;
 
+    if let Some(timeout) = wait_timeout_from(engine_state, stack, call)? {
+        let name = definition.name();
+        client
+            .wait_for_columnar_cluster_state(
+                name.clone(),
+                org_id,
+                project_id,
+                &["healthy"],
+                timeout,
+                signals,
+            )
+            .map_err(|e| client_error_to_shell_error(e, span))?;
+        info!("columnar cluster {} is healthy", name);
+    }
+
     Ok(PipelineData::empty())
 }
 
@@ -0,0 +1,162 @@
+use crate::cli::analytics_common::{read_analytics_response, send_columnar_query};
+use crate::cli::generic_error;
+use crate::cli::util::{cluster_identifiers_from, get_active_cluster, string_literal};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct ColumnarCollectionsCreateExternal {
+    state: Arc<Mutex<State>>,
+}
+
+impl ColumnarCollectionsCreateExternal {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for ColumnarCollectionsCreateExternal {
+    fn name(&self) -> &str {
+        "columnar collections create-external"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("columnar collections create-external")
+            .required("name", SyntaxShape::String, "the name of the collection")
+            .required(
+                "link",
+                SyntaxShape::String,
+                "the name of the external link to read from",
+            )
+            .required(
+                "container",
+                SyntaxShape::String,
+                "the bucket or container holding the data",
+            )
+            .named(
+                "path",
+                SyntaxShape::String,
+                "the path of the data within the container",
+                None,
+            )
+            .named(
+                "format",
+                SyntaxShape::String,
+                "the format of the data: json, csv, tsv or parquet (default = json)",
+                None,
+            )
+            .named(
+                "database",
+                SyntaxShape::String,
+                "the database to create the collection in (default = active bucket)",
+                None,
+            )
+            .named(
+                "scope",
+                SyntaxShape::String,
+                "the scope to create the collection in (default = active scope)",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Creates a Columnar collection over data held behind an external link"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Create a collection over the parquet files under the sales path of a GCS bucket",
+            example: "columnar collections create-external sales myGcsLink my-bucket --path sales --format parquet --database retail --scope raw",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        collections_create_external(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn collections_create_external(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let signals = engine_state.signals().clone();
+    let span = call.head;
+
+    let name: String = call.req(engine_state, stack, 0)?;
+    let link: String = call.req(engine_state, stack, 1)?;
+    let container: String = call.req(engine_state, stack, 2)?;
+    let path: Option<String> = call.get_flag(engine_state, stack, "path")?;
+    let format = call
+        .get_flag(engine_state, stack, "format")?
+        .unwrap_or_else(|| "json".to_string());
+    let database_flag: Option<String> = call.get_flag(engine_state, stack, "database")?;
+    let scope_flag: Option<String> = call.get_flag(engine_state, stack, "scope")?;
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+
+    let guard = state.lock().unwrap();
+
+    for identifier in cluster_identifiers {
+        let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+        let database = database_flag
+            .clone()
+            .or_else(|| active_cluster.active_bucket());
+        let scope = scope_flag.clone().or_else(|| active_cluster.active_scope());
+
+        let collection =
+            match (&database, &scope) {
+                (Some(d), Some(s)) => format!("`{}`.`{}`.`{}`", d, s, name),
+                (None, None) => format!("`{}`", name),
+                _ => return Err(generic_error(
+                    "Both a database and a scope are needed to create the collection in",
+                    "Use --database and --scope, or set the active bucket and scope with cb-env"
+                        .to_string(),
+                    span,
+                )),
+            };
+
+        let mut statement = format!(
+            "CREATE EXTERNAL COLLECTION {} ON `{}` AT `{}`",
+            collection, container, link
+        );
+        if let Some(p) = &path {
+            statement = format!("{} PATH {}", statement, string_literal(p));
+        }
+        statement = format!(
+            "{} WITH {{\"format\": {}}}",
+            statement,
+            string_literal(&format)
+        );
+
+        debug!("Running Columnar analytics query {}", &statement);
+
+        let resp =
+            send_columnar_query(active_cluster, None, statement, None, signals.clone(), span)?;
+
+        read_analytics_response(identifier.clone(), resp, span, false, true)?;
+    }
+
+    Ok(PipelineData::empty())
+}
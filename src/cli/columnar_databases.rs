@@ -9,7 +9,6 @@ use nu_protocol::{
     Category, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
 };
 use std::sync::{Arc, Mutex};
-use tokio::runtime::Runtime;
 
 #[derive(Clone)]
 pub struct ColumnarDatabases {
@@ -72,23 +71,17 @@ fn columnar_databases(
     let mut results: Vec<Value> = vec![];
     for identifier in cluster_identifiers {
         let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
-        let resp = send_columnar_query(
-            active_cluster,
-            None,
-            statement,
-            signals.clone(),
-            span,
-            Arc::new(Runtime::new().unwrap()),
-        )
-        .map_err(|e| {
-            if e.to_string().contains("No nodes found for service")
-                || format!("{:?}", e).contains("Cannot find analytics collection Database")
-            {
-                cluster_not_columnar(identifier.clone())
-            } else {
-                e
-            }
-        })?;
+        let resp =
+            send_columnar_query(active_cluster, None, statement, None, signals.clone(), span)
+                .map_err(|e| {
+                    if e.to_string().contains("No nodes found for service")
+                        || format!("{:?}", e).contains("Cannot find analytics collection Database")
+                    {
+                        cluster_not_columnar(identifier.clone())
+                    } else {
+                        e
+                    }
+                })?;
 
         results.extend(read_analytics_response(
             identifier.clone(),
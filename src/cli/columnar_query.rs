@@ -1,4 +1,6 @@
-use crate::cli::analytics_common::{read_analytics_response, send_columnar_query};
+use crate::cli::analytics_common::{
+    analytics_options_from_args, read_analytics_response, send_columnar_query,
+};
 use crate::cli::util::{cluster_identifiers_from, get_active_cluster};
 use crate::state::State;
 use log::debug;
@@ -6,10 +8,9 @@ use nu_engine::command_prelude::Call;
 use nu_engine::CallExt;
 use nu_protocol::engine::{Command, EngineState, Stack};
 use nu_protocol::{
-    Category, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
 };
 use std::sync::{Arc, Mutex};
-use tokio::runtime::Runtime;
 
 #[derive(Clone)]
 pub struct ColumnarQuery {
@@ -42,6 +43,30 @@ impl Command for ColumnarQuery {
                 "the scope to query against",
                 None,
             )
+            .named(
+                "params",
+                SyntaxShape::Any,
+                "named or positional parameters for the query",
+                None,
+            )
+            .named(
+                "scan-consistency",
+                SyntaxShape::String,
+                "the scan consistency of the query: not_bounded or request_plus",
+                None,
+            )
+            .switch("readonly", "reject the query if it would modify data", None)
+            .switch(
+                "priority",
+                "run the query ahead of other requests on the service",
+                None,
+            )
+            .named(
+                "client-context-id",
+                SyntaxShape::String,
+                "the id used to identify the query in logs and active requests",
+                None,
+            )
             .switch("with-meta", "Includes related metadata in the result", None)
             .named(
                 "clusters",
@@ -56,6 +81,21 @@ impl Command for ColumnarQuery {
         "Performs a query against a Columnar analytics cluster"
     }
 
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Pass query parameters as an object",
+                example: "columnar query \"SELECT airportname FROM `travel-sample`.inventory.airport WHERE country = $country\" --params {country: France}",
+                result: None,
+            },
+            Example {
+                description: "Run a query that sees all prior mutations, ahead of other requests",
+                example: "columnar query \"SELECT COUNT(*) FROM `travel-sample`.inventory.airport\" --scan-consistency request_plus --priority",
+                result: None,
+            },
+        ]
+    }
+
     fn run(
         &self,
         engine_state: &EngineState,
@@ -84,6 +124,7 @@ fn columnar_query(
 
     let scope: Option<String> = call.get_flag(engine_state, stack, "scope")?;
     let with_meta = call.has_flag(engine_state, stack, "with-meta")?;
+    let options = analytics_options_from_args(engine_state, stack, call)?;
 
     debug!("Running Columnar analytics query {}", &statement);
 
@@ -98,9 +139,9 @@ fn columnar_query(
             active_cluster,
             maybe_scope,
             statement.clone(),
+            options.clone(),
             signals.clone(),
             span,
-            Arc::new(Runtime::new().unwrap()),
         )?;
 
         results.extend(read_analytics_response(
@@ -98,33 +98,8 @@ fn credentials_create(
     let bucket = call.get_flag(engine_state, stack, "bucket")?;
     let scopes_flag: Option<Value> = call.get_flag(engine_state, stack, "scopes")?;
 
-    let scopes = match scopes_flag {
-        Some(_) if bucket.is_none() => Err(ShellError::Generic(
-            GenericError::new_internal("--scopes cannot be used without specifying a bucket", "")
-                .with_help(
-                "Use the --bucket flag to specify a bucket the credentials are allowed to access",
-            ),
-        )),
-        Some(val) => match val {
-            Value::String { val, .. } => Ok(vec![val]),
-            Value::List { vals, .. } => vals
-                .iter()
-                .map(|v| v.as_str().map(|s| s.to_string()))
-                .collect(),
-            _ => Err(ShellError::Generic(
-                GenericError::new_internal("failed to parse scopes", "")
-                    .with_help("--scopes must be a string or a list of strings"),
-            )),
-        },
-        None => Ok(vec!["*".to_string()]),
-    }?;
-
-    if !read && !write {
-        return Err(ShellError::Generic(
-            GenericError::new_internal("Credentials must have at least read or write access", "")
-                .with_help("Use the --read and --write flags to add permissions."),
-        ));
-    }
+    let scopes = scopes_from_flag(scopes_flag, &bucket)?;
+    validate_access(read, write)?;
 
     let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
     let guard = state.lock().unwrap();
@@ -134,7 +109,9 @@ fn credentials_create(
 
         let org = guard.named_or_active_org(cluster.capella_org())?;
 
-        let client = org.client();
+        let client = org
+            .client()
+            .map_err(|e| client_error_to_shell_error(e, span))?;
 
         let org_id = find_org_id(signals.clone(), &client, span)?;
 
@@ -167,7 +144,9 @@ fn credentials_create(
         let (name, password) = if use_registered {
             (
                 cluster.username().to_string(),
-                cluster.password().to_string(),
+                cluster
+                    .password()
+                    .map_err(|e| client_error_to_shell_error(e, span))?,
             )
         } else {
             let username_flag = call.get_flag(engine_state, stack, "username")?;
@@ -197,3 +176,39 @@ fn credentials_create(
 
     Ok(PipelineData::empty())
 }
+
+pub(crate) fn scopes_from_flag(
+    scopes_flag: Option<Value>,
+    bucket: &Option<String>,
+) -> Result<Vec<String>, ShellError> {
+    match scopes_flag {
+        Some(_) if bucket.is_none() => Err(ShellError::Generic(
+            GenericError::new_internal("--scopes cannot be used without specifying a bucket", "")
+                .with_help(
+                "Use the --bucket flag to specify a bucket the credentials are allowed to access",
+            ),
+        )),
+        Some(val) => match val {
+            Value::String { val, .. } => Ok(vec![val]),
+            Value::List { vals, .. } => vals
+                .iter()
+                .map(|v| v.as_str().map(|s| s.to_string()))
+                .collect(),
+            _ => Err(ShellError::Generic(
+                GenericError::new_internal("failed to parse scopes", "")
+                    .with_help("--scopes must be a string or a list of strings"),
+            )),
+        },
+        None => Ok(vec!["*".to_string()]),
+    }
+}
+
+pub(crate) fn validate_access(read: bool, write: bool) -> Result<(), ShellError> {
+    if !read && !write {
+        return Err(ShellError::Generic(
+            GenericError::new_internal("Credentials must have at least read or write access", "")
+                .with_help("Use the --read and --write flags to add permissions."),
+        ));
+    }
+    Ok(())
+}
@@ -89,7 +89,9 @@ fn credentials_drop(
 
     let org = guard.named_or_active_org(cluster.capella_org())?;
 
-    let client = org.client();
+    let client = org
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
 
     let org_id = find_org_id(signals.clone(), &client, span)?;
 
@@ -0,0 +1,274 @@
+use crate::cli::cbenv_register::save_new_cluster_config;
+use crate::cli::util::{
+    cluster_from_conn_str, cluster_identifiers_from, find_org_id, find_project_id,
+    get_active_cluster, NuValueMap,
+};
+use crate::cli::{client_error_to_shell_error, generic_error};
+use crate::client::cloud_json::CredentialsUpdateRequest;
+use crate::state::State;
+use crate::{RemoteCluster, RemoteClusterResources};
+use log::{debug, info};
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape,
+};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::sync::{Arc, Mutex};
+
+const GENERATED_PASSWORD_LENGTH: usize = 24;
+
+#[derive(Clone)]
+pub struct CredentialsRotatePassword {
+    state: Arc<Mutex<State>>,
+}
+
+impl CredentialsRotatePassword {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for CredentialsRotatePassword {
+    fn name(&self) -> &str {
+        "credentials rotate-password"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("credentials rotate-password")
+            .category(Category::Custom("couchbase".to_string()))
+            .required(
+                "credentials ID",
+                SyntaxShape::String,
+                "the id of the credentials to change the password of",
+            )
+            .named(
+                "password",
+                SyntaxShape::String,
+                "the new password, a random one is generated if not given",
+                None,
+            )
+            .named(
+                "register",
+                SyntaxShape::String,
+                "register the cluster with the new credentials under this identifier",
+                None,
+            )
+            .switch(
+                "save",
+                "save the registered cluster to the config file",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+    }
+
+    fn description(&self) -> &str {
+        "Changes the password of credentials on a Capella cluster"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Rotate the password of credentials to a randomly generated one",
+                example: "credentials rotate-password 4f1f6d5c-8a2b-4b6e-9f0e-2f1c3e9a7d10",
+                result: None,
+            },
+            Example {
+                description: "Rotate the password and save the cluster with the new credentials",
+                example: "credentials rotate-password 4f1f6d5c-8a2b-4b6e-9f0e-2f1c3e9a7d10 --register dev-rotated --save",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        credentials_rotate_password(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn credentials_rotate_password(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let credential_id: String = call.req(engine_state, stack, 0)?;
+    let password: String = call
+        .get_flag(engine_state, stack, "password")?
+        .unwrap_or_else(generate_password);
+    let register: Option<String> = call.get_flag(engine_state, stack, "register")?;
+    let save = call.has_flag(engine_state, stack, "save")?;
+
+    if save && register.is_none() {
+        return Err(generic_error(
+            "--save cannot be used without --register",
+            "Use the --register flag to give the identifier to save the cluster under".to_string(),
+            span,
+        ));
+    }
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+    if cluster_identifiers.len() != 1 {
+        return Err(generic_error(
+            "multiple clusters specified",
+            "Credentials rotate-password can only be used against one cluster at a time"
+                .to_string(),
+            span,
+        ));
+    }
+
+    debug!("Running credentials rotate-password for {}", &credential_id);
+
+    let mut guard = state.lock().unwrap();
+    let cluster = get_active_cluster(cluster_identifiers[0].clone(), &guard, span)?;
+
+    let org = guard.named_or_active_org(cluster.capella_org())?;
+
+    let client = org
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+
+    let project_id = find_project_id(
+        signals.clone(),
+        guard.active_project().unwrap(),
+        &client,
+        span,
+        org_id.clone(),
+    )?;
+
+    let json_cluster = cluster_from_conn_str(
+        cluster_identifiers[0].clone(),
+        signals.clone(),
+        cluster.hostnames().clone(),
+        &client,
+        span,
+        org_id.clone(),
+        project_id.clone(),
+    )?;
+
+    // The update replaces the access of the credentials as well as the password, so the current
+    // access has to be sent back unchanged.
+    let credential = client
+        .list_credentials(
+            org_id.clone(),
+            project_id.clone(),
+            json_cluster.id(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?
+        .data()
+        .into_iter()
+        .find(|c| c.id() == credential_id)
+        .ok_or_else(|| {
+            generic_error(
+                format!("Credentials {} not found", credential_id),
+                "Use the `credentials` command to see credential IDs".to_string(),
+                span,
+            )
+        })?;
+
+    let payload = CredentialsUpdateRequest::new(credential.access(), Some(password.clone()));
+
+    client
+        .update_credentials(
+            org_id,
+            project_id,
+            json_cluster.id(),
+            credential_id.clone(),
+            serde_json::to_string(&payload).unwrap(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    if let Some(identifier) = register {
+        let registered = RemoteCluster::new(
+            RemoteClusterResources {
+                conn_string: cluster.conn_string().clone(),
+                username: credential.name(),
+                password: password.clone().into(),
+                sasl_mechanism: cluster.sasl_mechanism(),
+                proxy: cluster.proxy().cloned(),
+                active_bucket: cluster.active_bucket(),
+                active_scope: cluster.active_scope(),
+                active_collection: cluster.active_collection(),
+                display_name: None,
+            },
+            cluster.tls_config().clone(),
+            cluster.timeouts(),
+            cluster.capella_org(),
+            cluster.project(),
+            cluster.kv_batch_size(),
+            cluster.cluster_type(),
+        );
+        guard.add_cluster(identifier.clone(), registered)?;
+        info!(
+            "registered cluster {} with the new credentials",
+            &identifier
+        );
+
+        if save {
+            save_new_cluster_config(&mut guard, span, identifier)?;
+        }
+    }
+
+    let mut collected = NuValueMap::default();
+    collected.add_string("id", credential_id, span);
+    collected.add_string("name", credential.name(), span);
+    collected.add_string("password", password, span);
+
+    Ok(collected.into_value(span).into_pipeline_data())
+}
+
+// Capella requires passwords to contain upper and lower case letters, digits and a special
+// character.
+fn generate_password() -> String {
+    loop {
+        let password: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(GENERATED_PASSWORD_LENGTH)
+            .map(char::from)
+            .collect();
+
+        if password.chars().any(|c| c.is_ascii_uppercase())
+            && password.chars().any(|c| c.is_ascii_lowercase())
+            && password.chars().any(|c| c.is_ascii_digit())
+        {
+            return format!("{}#", password);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::credentials_rotate_password::{generate_password, GENERATED_PASSWORD_LENGTH};
+
+    #[test]
+    fn generated_password_meets_capella_rules() {
+        let password = generate_password();
+
+        assert_eq!(GENERATED_PASSWORD_LENGTH + 1, password.len());
+        assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+        assert!(password.chars().any(|c| c.is_ascii_lowercase()));
+        assert!(password.chars().any(|c| c.is_ascii_digit()));
+        assert!(password.chars().any(|c| !c.is_ascii_alphanumeric()));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::cli::credentials_create::{scopes_from_flag, validate_access};
+use crate::cli::util::{
+    cluster_from_conn_str, cluster_identifiers_from, find_org_id, find_project_id,
+    get_active_cluster,
+};
+use crate::cli::{client_error_to_shell_error, generic_error};
+use crate::client::cloud_json::{Access, CredentialsUpdateRequest};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape, Value};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct CredentialsUpdate {
+    state: Arc<Mutex<State>>,
+}
+
+impl CredentialsUpdate {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for CredentialsUpdate {
+    fn name(&self) -> &str {
+        "credentials update"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("credentials update")
+            .category(Category::Custom("couchbase".to_string()))
+            .required(
+                "credentials ID",
+                SyntaxShape::String,
+                "the id of the credentials to update",
+            )
+            .switch("read", "enable read access", None)
+            .switch("write", "enable write access", None)
+            .named(
+                "bucket",
+                SyntaxShape::String,
+                "the bucket the credentials have access to, leave empty to allow access to all buckets",
+                None,
+            )
+            .named(
+                "scopes",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "the scopes the credentials have access to, leave empty to allow access to all scopes",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+    }
+
+    fn description(&self) -> &str {
+        "Updates the access of credentials on a Capella cluster"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Restrict credentials to reading the inventory scope of travel-sample",
+            example: "credentials update 4f1f6d5c-8a2b-4b6e-9f0e-2f1c3e9a7d10 --read --bucket travel-sample --scopes [inventory]",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        credentials_update(self.state.clone(), engine_state, stack, call, input)
+    }
+}
+
+fn credentials_update(
+    state: Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    _input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let span = call.head;
+    let signals = engine_state.signals().clone();
+
+    let credential_id: String = call.req(engine_state, stack, 0)?;
+    let read = call.has_flag(engine_state, stack, "read")?;
+    let write = call.has_flag(engine_state, stack, "write")?;
+    let bucket = call.get_flag(engine_state, stack, "bucket")?;
+    let scopes_flag: Option<Value> = call.get_flag(engine_state, stack, "scopes")?;
+
+    let scopes = scopes_from_flag(scopes_flag, &bucket)?;
+    validate_access(read, write)?;
+
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, &state, call, true)?;
+    if cluster_identifiers.len() != 1 {
+        return Err(generic_error(
+            "multiple clusters specified",
+            "Credentials update can only be used against one cluster at a time".to_string(),
+            span,
+        ));
+    }
+
+    debug!("Running credentials update for {}", &credential_id);
+
+    let guard = state.lock().unwrap();
+    let cluster = get_active_cluster(cluster_identifiers[0].clone(), &guard, span)?;
+
+    let org = guard.named_or_active_org(cluster.capella_org())?;
+
+    let client = org
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    let org_id = find_org_id(signals.clone(), &client, span)?;
+
+    let project_id = find_project_id(
+        signals.clone(),
+        guard.active_project().unwrap(),
+        &client,
+        span,
+        org_id.clone(),
+    )?;
+
+    let json_cluster = cluster_from_conn_str(
+        cluster_identifiers[0].clone(),
+        signals.clone(),
+        cluster.hostnames().clone(),
+        &client,
+        span,
+        org_id.clone(),
+        project_id.clone(),
+    )?;
+
+    let payload =
+        CredentialsUpdateRequest::new(vec![Access::new(read, write, bucket, scopes)], None);
+
+    client
+        .update_credentials(
+            org_id,
+            project_id,
+            json_cluster.id(),
+            credential_id,
+            serde_json::to_string(&payload).unwrap(),
+            signals.clone(),
+        )
+        .map_err(|e| client_error_to_shell_error(e, span))?;
+
+    Ok(PipelineData::empty())
+}
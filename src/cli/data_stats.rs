@@ -67,7 +67,7 @@ async fn run_stats(
 
     for identifier in cluster_identifiers {
         let core = match state.lock().unwrap().clusters().get(&identifier) {
-            Some(c) => c.cluster().core(),
+            Some(c) => c.cluster().map_err(|e| client_error_to_shell_error(e, span))?.core(),
             None => {
                 return Err(ShellError::unexpected("Cluster not found"));
             }
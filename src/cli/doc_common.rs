@@ -1,8 +1,9 @@
+use crate::cli::transactions_common::{run_transactional_mutations, StagedMutationType};
 use crate::cli::util::{
     cluster_identifiers_from, convert_nu_value_to_json_value, get_active_cluster,
     namespace_from_args, NuValueMap,
 };
-use crate::cli::{client_error_to_shell_error, serialize_error};
+use crate::cli::{client_error_to_shell_error, deserialize_error, generic_error, serialize_error};
 use crate::client::{ClientError, KeyValueRequest, KvClient, KvResponse};
 use crate::remote_cluster::RemoteCluster;
 use crate::state::State;
@@ -117,6 +118,43 @@ pub fn run_kv_mutations(
     let signals = engine_state.signals().clone();
 
     let expiry: i64 = call.get_flag(engine_state, stack, "expiry")?.unwrap_or(0);
+
+    if state.lock().unwrap().kv_transaction().is_some() {
+        let mutation_type = match req_builder(String::new(), vec![], 0) {
+            KeyValueRequest::Insert { .. } => StagedMutationType::Insert,
+            KeyValueRequest::Replace { .. } => StagedMutationType::Replace,
+            _ => {
+                return Err(generic_error(
+                    "Only insert, replace and remove can be used in a transaction",
+                    "Use 'transactions commit' or 'transactions rollback' to end the active transaction".to_string(),
+                    span,
+                ));
+            }
+        };
+        if expiry != 0 {
+            return Err(generic_error(
+                "Expiry cannot be set on documents mutated in a transaction",
+                None,
+                span,
+            ));
+        }
+
+        let mut items = vec![];
+        for (id, value) in all_items {
+            let content: serde_json::Value = serde_json::from_slice(&value)
+                .map_err(|e| deserialize_error(e.to_string(), span))?;
+            items.push((id, Some(content)));
+        }
+
+        return run_transactional_mutations(
+            &state,
+            engine_state,
+            stack,
+            call,
+            items,
+            mutation_type,
+        );
+    }
     let batch_size: Option<i64> = call.get_flag(engine_state, stack, "batch-size")?;
 
     let bucket_flag = call.get_flag(engine_state, stack, "bucket")?;
@@ -296,11 +334,7 @@ pub(crate) fn get_active_cluster_client_cid<'a>(
 
     let deadline = Instant::now().add(active_cluster.timeouts().data_timeout());
     let client = rt
-        .block_on(active_cluster.cluster().key_value_client(
-            bucket.clone(),
-            deadline,
-            signals.clone(),
-        ))
+        .block_on(active_cluster.key_value_client(bucket.clone(), deadline, signals.clone()))
         .map_err(|e| client_error_to_shell_error(e, span))?;
 
     let cid = rt
@@ -312,7 +346,7 @@ pub(crate) fn get_active_cluster_client_cid<'a>(
         ))
         .map_err(|e| client_error_to_shell_error(e, span))?;
 
-    Ok((active_cluster, Arc::new(client), cid))
+    Ok((active_cluster, client, cid))
 }
 
 #[derive(Debug)]
@@ -4,7 +4,8 @@ use super::util::convert_json_value_to_nu_value;
 use crate::state::State;
 
 use crate::cli::doc_common::{build_batched_kv_items, get_active_cluster_client_cid};
-use crate::cli::util::{cluster_identifiers_from, NuValueMap};
+use crate::cli::transactions_common::run_transactional_get;
+use crate::cli::util::{cluster_identifiers_from, timings_to_nu_value, NuValueMap};
 use crate::client::KeyValueRequest;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
@@ -74,6 +75,7 @@ impl Command for DocGet {
                 None,
             )
             .switch("halt-on-error", "halt on any errors", Some('e'))
+            .switch("with-timings", "include how long each operation took", None)
             .category(Category::Custom("couchbase".to_string()))
     }
 
@@ -103,6 +105,11 @@ impl Command for DocGet {
                 example: "echo [[id]; [airline_10] [airline_11]] | doc get",
                 result: None,
             },
+            Example {
+                description: "Fetches a document along with how long the server took to fetch it",
+                example: "doc get my_doc_id --with-timings | get timings.server",
+                result: None,
+            },
         ]
     }
 
@@ -128,6 +135,11 @@ fn run_get(
         .unwrap_or_else(|| "id".to_string());
     let ids = ids_from_input(input, id_column.clone(), call.positional_nth(stack, 0))?;
 
+    if state.lock().unwrap().kv_transaction().is_some() {
+        let results = run_transactional_get(&state, engine_state, stack, call, ids, id_column)?;
+        return Ok(Value::list(results, call.head).into_pipeline_data());
+    }
+
     let mut workers = FuturesUnordered::new();
     let guard = state.lock().unwrap();
 
@@ -140,6 +152,7 @@ fn run_get(
     let scope_flag = call.get_flag(engine_state, stack, "scope")?;
     let collection_flag = call.get_flag(engine_state, stack, "collection")?;
     let halt_on_error = call.has_flag(engine_state, stack, "halt-on-error")?;
+    let with_timings = call.has_flag(engine_state, stack, "with-timings")?;
 
     let mut results = vec![];
     for identifier in cluster_identifiers {
@@ -198,6 +211,10 @@ fn run_get(
                                 .id_column(&id_column)
                                 .key(res.key())
                                 .cas(res.cas() as i64);
+                            if with_timings {
+                                collected = collected
+                                    .timings(timings_to_nu_value(res.timings(), call.head));
+                            }
 
                             let content = res.content().unwrap_or_default();
                             match convert_json_value_to_nu_value(&content, call.head) {
@@ -280,6 +297,7 @@ pub(crate) struct GetResult {
     cluster: String,
     cas: Option<i64>,
     id_column: Option<String>,
+    timings: Option<Value>,
 }
 
 impl GetResult {
@@ -291,6 +309,7 @@ impl GetResult {
             cluster: cluster.into(),
             cas: None,
             id_column: None,
+            timings: None,
         }
     }
 
@@ -319,6 +338,11 @@ impl GetResult {
         self
     }
 
+    pub fn timings(mut self, timings: Value) -> GetResult {
+        self.timings = Some(timings);
+        self
+    }
+
     pub fn into_value(self, span: Span) -> Value {
         let mut collected = NuValueMap::default();
         collected.add_string(
@@ -330,6 +354,9 @@ impl GetResult {
         collected.add_i64("cas", self.cas.unwrap_or_default(), span);
         collected.add_string("error", self.error.unwrap_or_default(), span);
         collected.add_string("cluster", self.cluster, span);
+        if let Some(timings) = self.timings {
+            collected.add("timings", timings);
+        }
         collected.into_value(span)
     }
 }
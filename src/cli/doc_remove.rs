@@ -4,6 +4,7 @@ use crate::cli::doc_common::{
     build_batched_kv_items, get_active_cluster_client_cid, process_kv_workers, MutationResult,
 };
 use crate::cli::doc_get::ids_from_input;
+use crate::cli::transactions_common::{run_transactional_mutations, StagedMutationType};
 use crate::cli::util::cluster_identifiers_from;
 use crate::client::KeyValueRequest;
 use crate::state::State;
@@ -108,6 +109,18 @@ fn run_remove(
         .unwrap_or_else(|| String::from("id"));
 
     let ids = ids_from_input(input, id_column.clone(), call.positional_nth(stack, 0))?;
+
+    if state.lock().unwrap().kv_transaction().is_some() {
+        let results = run_transactional_mutations(
+            &state,
+            engine_state,
+            stack,
+            call,
+            ids.into_iter().map(|id| (id, None)).collect(),
+            StagedMutationType::Remove,
+        )?;
+        return Ok(Value::list(results, span).into_pipeline_data());
+    }
     let batch_size: Option<i64> = call.get_flag(engine_state, stack, "batch-size")?;
     let mut all_ids: Vec<Vec<String>> = vec![];
     if let Some(size) = batch_size {
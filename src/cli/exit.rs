@@ -0,0 +1,80 @@
+//! The `exit` command wraps the nushell command of the same name so that the shell isn't left
+//! without warning while a transaction is open, leaving its locks held until it times out.
+
+use crate::state::State;
+use log::warn;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Example, PipelineData, ShellError, Signature, Value};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct Exit {
+    state: Arc<Mutex<State>>,
+    // The transaction the last warning was given for, so that exiting again with the same one
+    // open exits while a transaction started since is warned about again.
+    warned_transaction: Arc<Mutex<Option<String>>>,
+}
+
+impl Exit {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self {
+            state,
+            warned_transaction: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+impl Command for Exit {
+    fn name(&self) -> &str {
+        "exit"
+    }
+
+    fn signature(&self) -> Signature {
+        nu_command::Exit.signature()
+    }
+
+    fn description(&self) -> &str {
+        "Exit cbsh, warning first if a transaction is still open."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        nu_command::Exit.search_terms()
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Exit the shell",
+            example: "exit",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let abort = call.has_flag(engine_state, stack, "abort")?;
+
+        if engine_state.is_interactive && !abort {
+            if let Some(id) = self.state.lock().unwrap().open_transaction_id() {
+                let mut warned = self.warned_transaction.lock().unwrap();
+                if warned.as_deref() != Some(id.as_str()) {
+                    warn!(
+                        "Transaction {} is still open and holds its locks until it times out",
+                        id
+                    );
+                    warn!("Running `exit` a second time will exit anyway");
+                    *warned = Some(id);
+                    return Ok(PipelineData::Value(Value::nothing(call.head), None));
+                }
+            }
+        }
+
+        nu_command::Exit.run(engine_state, stack, call, input)
+    }
+}
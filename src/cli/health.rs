@@ -108,6 +108,7 @@ fn grab_bucket_names(
 ) -> Result<Vec<String>, ShellError> {
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::GetBuckets,
@@ -133,6 +134,7 @@ fn check_autofailover(
 ) -> Result<Value, ShellError> {
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::SettingsAutoFailover,
@@ -182,6 +184,7 @@ fn check_resident_ratio(
 ) -> Result<Value, ShellError> {
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::BucketStats {
@@ -1,13 +1,33 @@
 mod allow_ip;
+mod allow_ip_drop;
+mod allow_ip_list;
 mod analytics;
 mod analytics_buckets;
 mod analytics_common;
 mod analytics_datasets;
+mod analytics_datasets_create_external;
 mod analytics_dataverses;
 mod analytics_indexes;
 mod analytics_links;
+mod analytics_links_alter;
+mod analytics_links_create;
+mod analytics_links_drop;
 mod analytics_pending_mutations;
+mod app_services;
+mod app_services_create;
+mod app_services_drop;
+mod app_services_off;
+mod app_services_on;
 mod ask;
+mod ask_query;
+mod ask_session;
+mod ask_session_end;
+mod ask_session_start;
+mod backups;
+mod backups_create;
+mod backups_restore;
+mod backups_schedule;
+mod backups_schedule_update;
 mod buckets;
 mod buckets_builder;
 mod buckets_config;
@@ -24,6 +44,12 @@ mod clusters;
 mod clusters_create;
 mod clusters_drop;
 mod clusters_get;
+mod clusters_network_peers;
+mod clusters_off;
+mod clusters_on;
+mod clusters_private_endpoints;
+mod clusters_schedule;
+mod clusters_update;
 mod collections;
 mod collections_create;
 mod collections_drop;
@@ -31,11 +57,14 @@ mod columnar;
 mod columnar_clusters;
 mod columnar_clusters_create;
 mod columnar_clusters_drop;
+mod columnar_collections_create_external;
 mod columnar_databases;
 mod columnar_query;
 mod credentials;
 mod credentials_create;
 mod credentials_drop;
+mod credentials_rotate_password;
+mod credentials_update;
 mod ctrlc_future;
 mod doc;
 mod doc_common;
@@ -60,8 +89,10 @@ mod cbenv_llm;
 mod cbenv_project;
 mod cbenv_scope;
 mod cbenv_timeouts;
+mod cbenv_trace;
 mod doc_import;
 mod error;
+mod exit;
 mod projects;
 mod projects_create;
 mod projects_drop;
@@ -75,7 +106,13 @@ mod scopes_drop;
 mod search;
 mod subdoc_get;
 mod transactions;
+mod transactions_begin;
+mod transactions_cleanup;
+mod transactions_commit;
+mod transactions_common;
 mod transactions_list_atrs;
+mod transactions_rollback;
+mod transactions_status;
 mod tutorial;
 mod tutorial_next;
 mod tutorial_page;
@@ -95,14 +132,34 @@ mod vector_search;
 mod version;
 
 pub use allow_ip::AllowIP;
+pub use allow_ip_drop::AllowIPDrop;
+pub use allow_ip_list::AllowIPList;
 pub use analytics::Analytics;
 pub use analytics_buckets::AnalyticsBuckets;
 pub use analytics_datasets::AnalyticsDatasets;
+pub use analytics_datasets_create_external::AnalyticsDatasetsCreateExternal;
 pub use analytics_dataverses::AnalyticsDataverses;
 pub use analytics_indexes::AnalyticsIndexes;
 pub use analytics_links::AnalyticsLinks;
+pub use analytics_links_alter::AnalyticsLinksAlter;
+pub use analytics_links_create::AnalyticsLinksCreate;
+pub use analytics_links_drop::AnalyticsLinksDrop;
 pub use analytics_pending_mutations::AnalyticsPendingMutations;
+pub use app_services::AppServices;
+pub use app_services_create::AppServicesCreate;
+pub use app_services_drop::AppServicesDrop;
+pub use app_services_off::AppServicesOff;
+pub use app_services_on::AppServicesOn;
 pub use ask::Ask;
+pub use ask_query::AskQuery;
+pub use ask_session::AskSession;
+pub use ask_session_end::AskSessionEnd;
+pub use ask_session_start::AskSessionStart;
+pub use backups::Backups;
+pub use backups_create::BackupsCreate;
+pub use backups_restore::BackupsRestore;
+pub use backups_schedule::BackupsSchedule;
+pub use backups_schedule_update::BackupsScheduleUpdate;
 pub use buckets::Buckets;
 pub use buckets_config::BucketsConfig;
 pub use buckets_create::BucketsCreate;
@@ -119,6 +176,12 @@ pub use clusters::Clusters;
 pub use clusters_create::ClustersCreate;
 pub use clusters_drop::ClustersDrop;
 pub use clusters_get::ClustersGet;
+pub use clusters_network_peers::ClustersNetworkPeers;
+pub use clusters_off::ClustersOff;
+pub use clusters_on::ClustersOn;
+pub use clusters_private_endpoints::ClustersPrivateEndpoints;
+pub use clusters_schedule::ClustersSchedule;
+pub use clusters_update::ClustersUpdate;
 pub use collections::Collections;
 pub use collections_create::CollectionsCreate;
 pub use collections_drop::CollectionsDrop;
@@ -126,11 +189,14 @@ pub use columnar::Columnar;
 pub use columnar_clusters::ColumnarClusters;
 pub use columnar_clusters_create::ColumnarClustersCreate;
 pub use columnar_clusters_drop::ColumnarClustersDrop;
+pub use columnar_collections_create_external::ColumnarCollectionsCreateExternal;
 pub use columnar_databases::ColumnarDatabases;
 pub use columnar_query::ColumnarQuery;
 pub use credentials::Credentials;
 pub use credentials_create::CredentialsCreate;
 pub use credentials_drop::CredentialsDrop;
+pub use credentials_rotate_password::CredentialsRotatePassword;
+pub use credentials_update::CredentialsUpdate;
 pub use ctrlc_future::CtrlcFuture;
 pub use doc::Doc;
 pub use doc_get::DocGet;
@@ -140,6 +206,7 @@ pub use doc_remove::DocRemove;
 pub use doc_replace::DocReplace;
 pub use doc_upsert::DocUpsert;
 pub use error::*;
+pub use exit::Exit;
 pub use fake_data::FakeData;
 pub use health::HealthCheck;
 pub use help::Help;
@@ -155,6 +222,7 @@ pub use cbenv_collection::UseCollection;
 pub use cbenv_project::UseProject;
 pub use cbenv_scope::UseScope;
 pub use cbenv_timeouts::UseTimeouts;
+pub use cbenv_trace::UseTrace;
 pub use projects::Projects;
 pub use projects_create::ProjectsCreate;
 pub use projects_drop::ProjectsDrop;
@@ -168,7 +236,13 @@ pub use scopes_drop::ScopesDrop;
 pub use search::Search;
 pub use subdoc_get::SubDocGet;
 pub use transactions::Transactions;
+pub use transactions_begin::TransactionsBegin;
+pub use transactions_cleanup::TransactionsCleanup;
+pub use transactions_commit::TransactionsCommit;
+pub(crate) use transactions_common::KvTransaction;
 pub use transactions_list_atrs::TransactionsListAtrs;
+pub use transactions_rollback::TransactionsRollback;
+pub use transactions_status::TransactionsStatus;
 pub use tutorial::Tutorial;
 pub use tutorial_next::TutorialNext;
 pub use tutorial_page::TutorialPage;
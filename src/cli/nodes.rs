@@ -73,6 +73,7 @@ fn nodes(
         let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
         let response = active_cluster
             .cluster()
+            .map_err(|e| client_error_to_shell_error(e, span))?
             .http_client()
             .management_request(
                 ManagementRequest::GetNodes,
@@ -60,7 +60,9 @@ fn organizations(
     let orgs = guard.capella_orgs();
     let mut results = vec![];
     for (identifier, org) in orgs.iter() {
-        let client = org.client();
+        let client = org
+            .client()
+            .map_err(|e| client_error_to_shell_error(e, span))?;
         let mut collected = NuValueMap::default();
         collected.add_string("identifier", identifier, span);
 
@@ -88,7 +88,10 @@ fn run_ping(
         let cluster = get_active_cluster(identifier.clone(), &guard, span)?;
         let deadline = Instant::now().add(cluster.timeouts().management_timeout());
 
-        let client = cluster.cluster().http_client();
+        let client = cluster
+            .cluster()
+            .map_err(|e| client_error_to_shell_error(e, span))?
+            .http_client();
         let result = client.ping_all_request(deadline, signals.clone());
         match result {
             Ok(res) => {
@@ -126,12 +129,13 @@ fn run_ping(
 
         // TODO: do this in parallel to http ops.
         let kv_deadline = Instant::now().add(cluster.timeouts().data_timeout());
-        let mut client = rt
-            .block_on(cluster.cluster().key_value_client(
-                bucket_name.clone(),
-                kv_deadline,
-                signals.clone(),
-            ))
+        let client = rt
+            .block_on(
+                cluster
+                    .cluster()
+                    .map_err(|e| client_error_to_shell_error(e, span))?
+                    .key_value_client(bucket_name.clone(), kv_deadline, signals.clone()),
+            )
             .map_err(|e| client_error_to_shell_error(e, span))?;
 
         let kv_result = rt.block_on(client.ping_all(kv_deadline, signals.clone()));
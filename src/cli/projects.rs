@@ -62,7 +62,9 @@ fn projects(
 
     let guard = &mut state.lock().unwrap();
     let control = guard.active_capella_org()?;
-    let client = control.client();
+    let client = control
+        .client()
+        .map_err(|e| client_error_to_shell_error(e, span))?;
 
     let org_id = find_org_id(signals.clone(), &client, span)?;
 
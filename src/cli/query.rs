@@ -1,7 +1,7 @@
 use crate::cli::util::convert_nu_value_to_json_value;
 use crate::cli::util::{
     cluster_identifiers_from, convert_row_to_nu_value, duration_to_golang_string,
-    get_active_cluster, is_http_status,
+    get_active_cluster, is_http_status, timings_to_nu_value,
 };
 use crate::client::{QueryRequest, QueryTransactionRequest};
 use crate::state::State;
@@ -71,6 +71,7 @@ impl Command for Query {
                 None,
             )
             .switch("with-meta", "include toplevel metadata", None)
+            .switch("with-timings", "include how long the query took in each row", None)
             .switch("disable-context", "disable automatically detecting the query context based on the active bucket and scope", None)
             .category(Category::Custom("couchbase".to_string()))
     }
@@ -105,6 +106,11 @@ impl Command for Query {
                 description:  "Pass query parameters as a list",
                 example: "query \"SELECT airline FROM `travel-sample`.inventory.route WHERE sourceairport = $1 AND distance > $2\" --params [LAX 13000]",
                 result: None,
+            },
+            Example {
+                description: "Find out which query node ran a query and how long it took",
+                example: "query \"SELECT 1\" --with-timings | get timings",
+                result: None,
             }
         ]
     }
@@ -140,6 +146,8 @@ fn query(
             None => None,
         };
 
+    let with_timings = call.has_flag(engine_state, stack, "with-timings")?;
+
     let mut results: Vec<Value> = vec![];
     for identifier in cluster_identifiers {
         let guard = state.lock().unwrap();
@@ -162,13 +170,23 @@ fn query(
         drop(guard);
 
         let status = response.status();
-        results.extend(handle_query_response(
+        let (content, timings) = response.content_with_timings()?;
+        let mut rows = handle_query_response(
             call.has_flag(engine_state, stack, "with-meta")?,
             identifier.clone(),
             status,
-            response.content()?,
+            content,
             span,
-        )?);
+        )?;
+        if with_timings {
+            let timings = timings_to_nu_value(&timings, span);
+            for row in rows.iter_mut() {
+                if let Value::Record { val, .. } = row {
+                    val.to_mut().push("timings", timings.clone());
+                }
+            }
+        }
+        results.extend(rows);
     }
 
     if !results.is_empty() {
@@ -189,20 +207,60 @@ pub fn send_query(
     transaction: impl Into<Option<QueryTransactionRequest>>,
 ) -> Result<HttpStreamResponse, ShellError> {
     let timeout = timeout.into().unwrap_or(cluster.timeouts().query_timeout());
+    send_query_request(
+        cluster,
+        QueryRequest::Execute {
+            statement: statement.into(),
+            parameters,
+            scope,
+            timeout: duration_to_golang_string(timeout),
+            transaction: transaction.into(),
+            readonly: false,
+        },
+        timeout,
+        signals,
+        span,
+    )
+}
+
+// Sends a query which the query service rejects if it would modify any data or indexes, for
+// statements the user has not written themselves.
+pub fn send_readonly_query(
+    cluster: &RemoteCluster,
+    statement: impl Into<String>,
+    scope: Option<(String, String)>,
+    signals: Signals,
+    span: Span,
+) -> Result<HttpStreamResponse, ShellError> {
+    let timeout = cluster.timeouts().query_timeout();
+    send_query_request(
+        cluster,
+        QueryRequest::Execute {
+            statement: statement.into(),
+            parameters: None,
+            scope,
+            timeout: duration_to_golang_string(timeout),
+            transaction: None,
+            readonly: true,
+        },
+        timeout,
+        signals,
+        span,
+    )
+}
+
+fn send_query_request(
+    cluster: &RemoteCluster,
+    request: QueryRequest,
+    timeout: Duration,
+    signals: Signals,
+    span: Span,
+) -> Result<HttpStreamResponse, ShellError> {
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
-        .query_request(
-            QueryRequest::Execute {
-                statement: statement.into(),
-                parameters,
-                scope,
-                timeout: duration_to_golang_string(timeout),
-                transaction: transaction.into(),
-            },
-            Instant::now().add(timeout),
-            signals,
-        )
+        .query_request(request, Instant::now().add(timeout), signals)
         .map_err(|e| client_error_to_shell_error(e, span))?;
 
     Ok(response)
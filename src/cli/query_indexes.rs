@@ -157,6 +157,7 @@ fn index_definitions(
 
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::IndexStatus,
@@ -1,4 +1,4 @@
-use crate::cli::util::is_http_status;
+use crate::cli::util::{get_active_cluster, is_http_status};
 use crate::client::QueryTransactionRequest;
 use crate::state::State;
 use log::{debug, info};
@@ -87,14 +87,32 @@ fn query(
     let st = state.clone();
     let mut guard = st.lock().unwrap();
 
-    let active_cluster = match guard.active_cluster() {
-        Some(c) => c,
-        None => {
+    if guard.kv_transaction().is_some() {
+        return Err(generic_error(
+            "A transaction started with 'transactions begin' is already active",
+            "Use 'transactions commit' or 'transactions rollback' to end it".to_string(),
+            span,
+        ));
+    }
+
+    let active_txn = guard.active_transaction();
+
+    // An open transaction stays on the cluster it was started against, even if the active cluster
+    // has since been changed.
+    let identifier = match &active_txn {
+        Some(txn_state) => txn_state.cluster(),
+        None => guard.active(),
+    };
+    let active_cluster = match get_active_cluster(identifier.clone(), &guard, span) {
+        Ok(c) => c,
+        Err(_) => {
             return Err(no_active_cluster_error(span));
         }
     };
-
-    let active_txn = guard.active_transaction();
+    let txn_timeout = call
+        .get_flag(engine_state, stack, "transaction-timeout")?
+        .map(|t: i64| Duration::from_millis(t as u64))
+        .unwrap_or(active_cluster.timeouts().transaction_timeout());
 
     let txn_request = if let Some(txn_state) = active_txn {
         info!(
@@ -104,16 +122,11 @@ fn query(
         );
         QueryTransactionRequest::new(None, txn_state.id(), txn_state.endpoint())
     } else if statement_type == TransactionStatementType::Start {
-        let timeout = call
-            .get_flag(engine_state, stack, "transaction-timeout")?
-            .map(|t: i64| Duration::from_millis(t as u64))
-            .unwrap_or(active_cluster.timeouts().transaction_timeout());
-
         info!(
             "Starting a new transaction with timeout {}ms",
-            &timeout.as_millis()
+            &txn_timeout.as_millis()
         );
-        QueryTransactionRequest::new(timeout, None, None)
+        QueryTransactionRequest::new(txn_timeout, None, None)
     } else {
         return Err(generic_error(
             "No active transaction",
@@ -167,13 +180,13 @@ fn query(
                 "Updating state to start transaction for {} on {}",
                 &txid, endpoint
             );
-            guard.start_transaction(txid, endpoint)?;
+            guard.start_transaction(txid, endpoint, identifier.clone(), txn_timeout)?;
         }
     }
 
     let results = handle_query_response(
         call.has_flag(engine_state, stack, "with-meta")?,
-        guard.active(),
+        identifier,
         status,
         content,
         span,
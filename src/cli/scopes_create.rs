@@ -95,7 +95,8 @@ fn run(
         if active_cluster.is_capella() {
             let client = guard
                 .named_or_active_org(active_cluster.capella_org())?
-                .client();
+                .client()
+                .map_err(|e| client_error_to_shell_error(e, span))?;
 
             let (org_id, project_id, cluster_id) = find_org_project_cluster_ids(
                 &client,
@@ -142,6 +143,7 @@ fn create_server_scope(
         serde_urlencoded::to_string(form).map_err(|e| serialize_error(e.to_string(), span))?;
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::CreateScope { payload, bucket },
@@ -85,6 +85,7 @@ fn run(
         let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
         let response = active_cluster
             .cluster()
+            .map_err(|e| client_error_to_shell_error(e, span))?
             .http_client()
             .search_query_request(
                 TextSearchQueryRequest::Execute {
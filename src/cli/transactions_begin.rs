@@ -0,0 +1,114 @@
+use crate::cli::generic_error;
+use crate::cli::transactions_common::{transaction_to_value, KvTransaction};
+use crate::cli::util::get_active_cluster;
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape,
+};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct TransactionsBegin {
+    state: Arc<Mutex<State>>,
+}
+
+impl TransactionsBegin {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for TransactionsBegin {
+    fn name(&self) -> &str {
+        "transactions begin"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("transactions begin")
+            .named(
+                "timeout",
+                SyntaxShape::Int,
+                "timeout (milliseconds) after which the transaction can no longer be committed",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Begins a transaction against the active cluster, which doc get, insert, replace and remove take part in"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Begin a transaction",
+                example: "transactions begin",
+                result: None,
+            },
+            Example {
+                description: "Begin a transaction which expires after 30 seconds",
+                example: "transactions begin --timeout 30000",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+        let timeout: Option<i64> = call.get_flag(engine_state, stack, "timeout")?;
+
+        let guard = self.state.lock().unwrap();
+        if let Some(txn) = guard.kv_transaction() {
+            return Err(generic_error(
+                format!("Transaction {} is already active", txn.id),
+                "Use 'transactions commit' or 'transactions rollback' to end it".to_string(),
+                span,
+            ));
+        }
+        if guard.active_transaction().is_some() {
+            return Err(generic_error(
+                "A query transaction is already active",
+                "Commit or roll back the query transaction using 'query transactions'".to_string(),
+                span,
+            ));
+        }
+
+        let identifier = guard.active();
+        let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+        let timeout = match timeout {
+            Some(t) if t <= 0 => {
+                return Err(generic_error(
+                    format!("Invalid timeout {}", t),
+                    "The timeout must be more than zero milliseconds".to_string(),
+                    span,
+                ));
+            }
+            Some(t) => Duration::from_millis(t as u64),
+            None => active_cluster.timeouts().transaction_timeout(),
+        };
+
+        let txn = KvTransaction::new(identifier, timeout);
+
+        debug!(
+            "Beginning transaction {} with timeout {}ms",
+            &txn.id,
+            timeout.as_millis()
+        );
+
+        let value = transaction_to_value(&txn, "active", 0, span);
+        guard.update_kv_transaction(txn);
+
+        Ok(value.into_pipeline_data())
+    }
+}
@@ -0,0 +1,131 @@
+use crate::cli::transactions_common::{
+    cleanup_attempt, fetch_atr_attempts, TransactionContext, TransactionKeyspace,
+};
+use crate::cli::util::{
+    cluster_identifiers_from, get_active_cluster, namespace_from_args, NuValueMap,
+};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
+};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Runtime;
+
+#[derive(Clone)]
+pub struct TransactionsCleanup {
+    state: Arc<Mutex<State>>,
+}
+
+impl TransactionsCleanup {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for TransactionsCleanup {
+    fn name(&self) -> &str {
+        "transactions cleanup"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("transactions cleanup")
+            .named(
+                "bucket",
+                SyntaxShape::String,
+                "the name of the bucket",
+                None,
+            )
+            .named("scope", SyntaxShape::String, "the name of the scope", None)
+            .named(
+                "collection",
+                SyntaxShape::String,
+                "the name of the collection",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Completes or rolls back the expired transaction attempts recorded in a collection"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Clean up the expired attempts in the active collection",
+            example: "transactions cleanup",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+        let signals = engine_state.signals().clone();
+
+        let bucket_flag: Option<String> = call.get_flag(engine_state, stack, "bucket")?;
+        let scope_flag: Option<String> = call.get_flag(engine_state, stack, "scope")?;
+        let collection_flag: Option<String> = call.get_flag(engine_state, stack, "collection")?;
+
+        let cluster_identifiers =
+            cluster_identifiers_from(engine_state, stack, &self.state, call, true)?;
+        let guard = self.state.lock().unwrap();
+        let rt = Runtime::new().unwrap();
+
+        // The attempt of the shell's own transaction is left for commit or rollback to deal with.
+        let own_attempt = guard.kv_transaction().map(|t| t.attempt_id);
+
+        let mut results = vec![];
+        for identifier in cluster_identifiers {
+            let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+            let (bucket, scope, collection) = namespace_from_args(
+                bucket_flag.clone(),
+                scope_flag.clone(),
+                collection_flag.clone(),
+                active_cluster,
+                span,
+            )?;
+            let keyspace = TransactionKeyspace::new(bucket, scope, collection);
+
+            let mut ctx =
+                TransactionContext::new(&guard, &rt, identifier.clone(), signals.clone(), span);
+            for attempt in fetch_atr_attempts(&mut ctx, &keyspace)? {
+                if !attempt.expired() || own_attempt.as_ref() == Some(&attempt.attempt_id) {
+                    continue;
+                }
+
+                debug!(
+                    "Cleaning up {} attempt {} in {}",
+                    &attempt.state, &attempt.attempt_id, &attempt.atr.0
+                );
+
+                let action = cleanup_attempt(&mut ctx, &attempt)?;
+
+                let mut collected = NuValueMap::default();
+                collected.add_string("atr", attempt.atr.0.clone(), span);
+                collected.add_string("attempt", attempt.attempt_id.clone(), span);
+                collected.add_string("transaction", attempt.transaction_id.clone(), span);
+                collected.add_string("state", attempt.state.clone(), span);
+                collected.add_string("action", action, span);
+                collected.add_string("cluster", identifier.clone(), span);
+                results.push(collected.into_value(span));
+            }
+        }
+
+        Ok(Value::list(results, span).into_pipeline_data())
+    }
+}
@@ -0,0 +1,87 @@
+use crate::cli::transactions_common::{
+    commit_transaction, no_kv_transaction_error, transaction_to_value, TransactionContext,
+};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, IntoPipelineData, PipelineData, ShellError, Signature};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Runtime;
+
+#[derive(Clone)]
+pub struct TransactionsCommit {
+    state: Arc<Mutex<State>>,
+}
+
+impl TransactionsCommit {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for TransactionsCommit {
+    fn name(&self) -> &str {
+        "transactions commit"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("transactions commit").category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Commits the mutations staged in the active transaction"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Insert two documents atomically",
+            example:
+                "transactions begin; doc insert a {x: 1}; doc insert b {x: 2}; transactions commit",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        _stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+
+        let guard = self.state.lock().unwrap();
+        let mut txn = guard
+            .kv_transaction()
+            .ok_or_else(|| no_kv_transaction_error(span))?;
+
+        debug!(
+            "Committing transaction {} with {} staged mutations",
+            &txn.id,
+            txn.staged.len()
+        );
+
+        let rt = Runtime::new().unwrap();
+        let mut ctx = TransactionContext::new(
+            &guard,
+            &rt,
+            txn.cluster.clone(),
+            engine_state.signals().clone(),
+            span,
+        );
+
+        // A failed commit leaves the transaction active so that it can be rolled back or, once it
+        // has been marked as committed, committed again to finish unstaging.
+        let mutations = match commit_transaction(&mut ctx, &mut txn) {
+            Ok(m) => m,
+            Err(e) => {
+                guard.update_kv_transaction(txn);
+                return Err(e);
+            }
+        };
+        guard.end_kv_transaction();
+
+        Ok(transaction_to_value(&txn, "committed", mutations, span).into_pipeline_data())
+    }
+}
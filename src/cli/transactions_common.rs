@@ -0,0 +1,1423 @@
+//! The client side of the KV transactions protocol, used by the doc commands when a transaction has
+//! been started with `transactions begin`.
+//!
+//! Mutations are staged in a `txn` xattr on each document and tracked in an entry in an Active
+//! Transaction Record (ATR), which lists every staged document so that cleanup can find them.
+//! Committing flips the ATR entry to COMMITTED, which is the point at which the transaction is
+//! visible to other transactional readers, and then unstages each document. From then on the
+//! transaction can only go forward. Rolling back flips the entry to ABORTED and removes the staged
+//! data.
+
+use crate::cli::doc_common::{get_active_cluster_client_cid, MutationResult};
+use crate::cli::doc_get::GetResult;
+use crate::cli::transactions::ATR_IDS;
+use crate::cli::util::{
+    cluster_identifiers_from, convert_json_value_to_nu_value, get_active_cluster,
+    namespace_from_args, NuValueMap,
+};
+use crate::cli::{client_error_to_shell_error, generic_error, serialize_error};
+use crate::client::{
+    ClientError, KeyValueRequest, KvClient, KvResponse, LookupInSpec, MutateInSpec,
+    SUBDOC_DOC_FLAG_ACCESS_DELETED, SUBDOC_DOC_FLAG_ADD, SUBDOC_DOC_FLAG_CREATE_AS_DELETED,
+    SUBDOC_DOC_FLAG_MKDOC, SUBDOC_DOC_FLAG_REVIVE_DOCUMENT,
+};
+use crate::remote_cluster::RemoteCluster;
+use crate::state::State;
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_engine::CallExt;
+use nu_protocol::engine::{EngineState, Stack};
+use nu_protocol::{ShellError, Signals, Span, Value};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::ops::Add;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct TransactionKeyspace {
+    pub(crate) bucket: String,
+    pub(crate) scope: String,
+    pub(crate) collection: String,
+}
+
+impl TransactionKeyspace {
+    pub(crate) fn new(bucket: String, scope: String, collection: String) -> Self {
+        let scope = if scope.is_empty() {
+            "_default".to_string()
+        } else {
+            scope
+        };
+        let collection = if collection.is_empty() {
+            "_default".to_string()
+        } else {
+            collection
+        };
+
+        Self {
+            bucket,
+            scope,
+            collection,
+        }
+    }
+
+    fn doc_record(&self, key: &str) -> serde_json::Value {
+        json!({"bkt": self.bucket, "scp": self.scope, "col": self.collection, "id": key})
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StagedMutationType {
+    Insert,
+    Replace,
+    Remove,
+}
+
+impl StagedMutationType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Insert => "insert",
+            Self::Replace => "replace",
+            Self::Remove => "remove",
+        }
+    }
+
+    fn from_str(mutation_type: &str) -> Option<Self> {
+        match mutation_type {
+            "insert" => Some(Self::Insert),
+            "replace" => Some(Self::Replace),
+            "remove" => Some(Self::Remove),
+            _ => None,
+        }
+    }
+
+    // The field of the ATR entry listing the documents staged with this type of mutation.
+    fn atr_field(&self) -> &'static str {
+        match self {
+            Self::Insert => "ins",
+            Self::Replace => "rep",
+            Self::Remove => "rem",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct StagedMutation {
+    pub(crate) keyspace: TransactionKeyspace,
+    pub(crate) key: String,
+    pub(crate) mutation_type: StagedMutationType,
+    pub(crate) content: Option<serde_json::Value>,
+    pub(crate) cas: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct KvTransaction {
+    pub(crate) cluster: String,
+    pub(crate) id: String,
+    pub(crate) attempt_id: String,
+    pub(crate) started: std::time::Instant,
+    pub(crate) timeout: Duration,
+    pub(crate) atr: Option<(String, TransactionKeyspace)>,
+    pub(crate) staged: Vec<StagedMutation>,
+    // Set once the ATR entry has been marked as committed, after which the transaction can only
+    // be completed and not rolled back.
+    pub(crate) committed: bool,
+}
+
+impl KvTransaction {
+    pub(crate) fn new(cluster: String, timeout: Duration) -> Self {
+        Self {
+            cluster,
+            id: Uuid::new_v4().to_string(),
+            attempt_id: Uuid::new_v4().to_string(),
+            started: std::time::Instant::now(),
+            timeout,
+            atr: None,
+            staged: vec![],
+            committed: false,
+        }
+    }
+
+    pub(crate) fn remaining(&self) -> Duration {
+        self.timeout.saturating_sub(self.started.elapsed())
+    }
+
+    pub(crate) fn expired(&self) -> bool {
+        self.started.elapsed() >= self.timeout
+    }
+
+    fn staged_index(&self, keyspace: &TransactionKeyspace, key: &str) -> Option<usize> {
+        self.staged
+            .iter()
+            .position(|m| &m.keyspace == keyspace && m.key == key)
+    }
+}
+
+// The transactional metadata of a document, fetched along with its body.
+struct TransactionalDoc {
+    cas: u64,
+    deleted: bool,
+    content: serde_json::Value,
+    txn: Option<serde_json::Value>,
+}
+
+impl TransactionalDoc {
+    fn attempt_id(&self) -> Option<String> {
+        self.txn
+            .as_ref()
+            .and_then(|t| t.pointer("/id/atmpt"))
+            .and_then(|a| a.as_str())
+            .map(|a| a.to_string())
+    }
+
+    fn staged_mutation_type(&self) -> Option<StagedMutationType> {
+        self.txn
+            .as_ref()
+            .and_then(|t| t.pointer("/op/type"))
+            .and_then(|t| t.as_str())
+            .and_then(StagedMutationType::from_str)
+    }
+}
+
+// Holds the KV clients used over the course of a single command, so that each keyspace is only
+// connected to once.
+pub(crate) struct TransactionContext<'a> {
+    guard: &'a MutexGuard<'a, State>,
+    rt: &'a Runtime,
+    cluster: String,
+    clients: HashMap<TransactionKeyspace, (Arc<KvClient>, u32)>,
+    signals: Signals,
+    span: Span,
+}
+
+impl<'a> TransactionContext<'a> {
+    pub(crate) fn new(
+        guard: &'a MutexGuard<'a, State>,
+        rt: &'a Runtime,
+        cluster: String,
+        signals: Signals,
+        span: Span,
+    ) -> Self {
+        Self {
+            guard,
+            rt,
+            cluster,
+            clients: HashMap::new(),
+            signals,
+            span,
+        }
+    }
+
+    fn active_cluster(&self) -> Result<&RemoteCluster, ShellError> {
+        get_active_cluster(self.cluster.clone(), self.guard, self.span)
+    }
+
+    fn client(
+        &mut self,
+        keyspace: &TransactionKeyspace,
+    ) -> Result<(Arc<KvClient>, u32), ShellError> {
+        if let Some(c) = self.clients.get(keyspace) {
+            return Ok(c.clone());
+        }
+
+        let (_, client, cid) = get_active_cluster_client_cid(
+            self.rt,
+            self.cluster.clone(),
+            self.guard,
+            Some(keyspace.bucket.clone()),
+            Some(keyspace.scope.clone()),
+            Some(keyspace.collection.clone()),
+            self.signals.clone(),
+            self.span,
+        )?;
+
+        self.clients.insert(keyspace.clone(), (client.clone(), cid));
+        Ok((client, cid))
+    }
+
+    fn request(
+        &mut self,
+        keyspace: &TransactionKeyspace,
+        request: KeyValueRequest,
+    ) -> Result<Result<KvResponse, ClientError>, ShellError> {
+        let (client, cid) = self.client(keyspace)?;
+        let deadline = Instant::now().add(self.active_cluster()?.timeouts().data_timeout());
+
+        Ok(self
+            .rt
+            .block_on(client.request(request, cid, deadline, self.signals.clone())))
+    }
+
+    fn lookup_doc(
+        &mut self,
+        keyspace: &TransactionKeyspace,
+        key: &str,
+    ) -> Result<Option<TransactionalDoc>, ShellError> {
+        let result = self.request(
+            keyspace,
+            KeyValueRequest::LookupIn {
+                key: key.to_string(),
+                specs: vec![
+                    LookupInSpec::Get {
+                        path: "txn".to_string(),
+                        xattr: true,
+                    },
+                    LookupInSpec::Get {
+                        path: "$document".to_string(),
+                        xattr: true,
+                    },
+                    LookupInSpec::GetDoc,
+                ],
+                access_deleted: true,
+            },
+        )?;
+
+        let mut response = match result {
+            Ok(r) => r,
+            Err(ClientError::KeyNotFound { .. }) => return Ok(None),
+            Err(e) => return Err(client_error_to_shell_error(e, self.span)),
+        };
+
+        let mut fields = match response.content() {
+            Some(serde_json::Value::Array(fields)) if fields.len() == 3 => fields,
+            _ => {
+                return Err(generic_error(
+                    format!("Unexpected lookup response for {}", key),
+                    None,
+                    self.span,
+                ))
+            }
+        };
+
+        let content = fields.remove(2);
+        let document = fields.remove(1);
+        let txn = fields.remove(0);
+
+        Ok(Some(TransactionalDoc {
+            cas: response.cas(),
+            deleted: document
+                .get("deleted")
+                .and_then(|d| d.as_bool())
+                .unwrap_or_default(),
+            content,
+            txn: if txn.is_null() { None } else { Some(txn) },
+        }))
+    }
+
+    fn mutate(
+        &mut self,
+        keyspace: &TransactionKeyspace,
+        key: &str,
+        specs: Vec<MutateInSpec>,
+        cas: u64,
+        doc_flags: u8,
+    ) -> Result<Result<KvResponse, ClientError>, ShellError> {
+        self.request(
+            keyspace,
+            KeyValueRequest::MutateIn {
+                key: key.to_string(),
+                specs,
+                cas,
+                doc_flags,
+            },
+        )
+    }
+
+    // Fetches the state of an attempt from the ATR referenced in a document's txn xattr, None means
+    // that the entry no longer exists.
+    fn attempt_state(&mut self, txn: &serde_json::Value) -> Result<Option<String>, ShellError> {
+        let (atr_key, keyspace, attempt_id) = match atr_from_txn_xattr(txn) {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+
+        let result = self.request(
+            &keyspace,
+            KeyValueRequest::LookupIn {
+                key: atr_key,
+                specs: vec![LookupInSpec::Get {
+                    path: format!("attempts.{}.st", attempt_id),
+                    xattr: true,
+                }],
+                access_deleted: false,
+            },
+        )?;
+
+        match result {
+            Ok(mut r) => Ok(r
+                .content()
+                .and_then(|c| c.get(0).cloned())
+                .and_then(|st| st.as_str().map(|s| s.to_string()))),
+            Err(ClientError::KeyNotFound { .. }) => Ok(None),
+            Err(e) => Err(client_error_to_shell_error(e, self.span)),
+        }
+    }
+
+    fn update_atr_entry(
+        &mut self,
+        (atr_key, keyspace): &(String, TransactionKeyspace),
+        attempt_id: &str,
+        fields: Vec<(&str, serde_json::Value, bool)>,
+        doc_flags: u8,
+    ) -> Result<(), ShellError> {
+        let mut specs = vec![];
+        for (field, value, expand_macros) in fields {
+            specs.push(MutateInSpec::Upsert {
+                path: format!("attempts.{}.{}", attempt_id, field),
+                value: serde_json::to_vec(&value)
+                    .map_err(|e| serialize_error(e.to_string(), self.span))?,
+                xattr: true,
+                expand_macros,
+            });
+        }
+
+        self.mutate(keyspace, atr_key, specs, 0, doc_flags)?
+            .map_err(|e| client_error_to_shell_error(e, self.span))?;
+
+        Ok(())
+    }
+
+    // Adds a document to the list of those staged by the attempt, before it is staged so that the
+    // entry never misses a document that cleanup would need to find.
+    fn record_staged_doc(
+        &mut self,
+        (atr_key, atr_keyspace): &(String, TransactionKeyspace),
+        attempt_id: &str,
+        mutation_type: StagedMutationType,
+        keyspace: &TransactionKeyspace,
+        key: &str,
+    ) -> Result<(), ShellError> {
+        let record = serde_json::to_vec(&keyspace.doc_record(key))
+            .map_err(|e| serialize_error(e.to_string(), self.span))?;
+
+        self.mutate(
+            atr_keyspace,
+            atr_key,
+            vec![MutateInSpec::ArrayAppend {
+                path: format!("attempts.{}.{}", attempt_id, mutation_type.atr_field()),
+                value: record,
+                xattr: true,
+            }],
+            0,
+            0,
+        )?
+        .map_err(|e| client_error_to_shell_error(e, self.span))?;
+
+        Ok(())
+    }
+
+    fn remove_atr_entry(
+        &mut self,
+        (atr_key, keyspace): &(String, TransactionKeyspace),
+        attempt_id: &str,
+    ) -> Result<(), ShellError> {
+        match self.mutate(
+            keyspace,
+            atr_key,
+            vec![MutateInSpec::Remove {
+                path: format!("attempts.{}", attempt_id),
+                xattr: true,
+            }],
+            0,
+            0,
+        )? {
+            Ok(_) | Err(ClientError::PathNotFound { .. }) => Ok(()),
+            Err(e) => Err(client_error_to_shell_error(e, self.span)),
+        }
+    }
+}
+
+fn atr_from_txn_xattr(txn: &serde_json::Value) -> Option<(String, TransactionKeyspace, String)> {
+    let field = |pointer: &str| {
+        txn.pointer(pointer)
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string())
+    };
+
+    Some((
+        field("/atr/id")?,
+        TransactionKeyspace::new(field("/atr/bkt")?, field("/atr/scp")?, field("/atr/coll")?),
+        field("/id/atmpt")?,
+    ))
+}
+
+// Resolves the keyspace of a doc command, transactions are bound to a single cluster.
+pub(crate) fn transaction_keyspace(
+    guard: &MutexGuard<State>,
+    txn: &KvTransaction,
+    cluster_identifiers: &[String],
+    bucket: Option<String>,
+    scope: Option<String>,
+    collection: Option<String>,
+    span: Span,
+) -> Result<TransactionKeyspace, ShellError> {
+    if cluster_identifiers.iter().any(|c| c != &txn.cluster) {
+        return Err(generic_error(
+            "Transactions can only be used against a single cluster",
+            format!("The active transaction was started against {}", txn.cluster),
+            span,
+        ));
+    }
+
+    let active_cluster = get_active_cluster(txn.cluster.clone(), guard, span)?;
+    let (bucket, scope, collection) =
+        namespace_from_args(bucket, scope, collection, active_cluster, span)?;
+
+    Ok(TransactionKeyspace::new(bucket, scope, collection))
+}
+
+pub(crate) fn no_kv_transaction_error(span: Span) -> ShellError {
+    generic_error(
+        "No active transaction",
+        "Use 'transactions begin' to start one".to_string(),
+        span,
+    )
+}
+
+fn transaction_expired_error(span: Span) -> ShellError {
+    generic_error(
+        "The transaction has expired",
+        "Use 'transactions rollback' to remove its staged mutations".to_string(),
+        span,
+    )
+}
+
+pub(crate) fn transaction_committed_error(span: Span) -> ShellError {
+    generic_error(
+        "The transaction has already been committed",
+        "Use 'transactions commit' to finish applying its mutations".to_string(),
+        span,
+    )
+}
+
+fn write_write_conflict_error(key: &str, span: Span) -> ShellError {
+    generic_error(
+        format!("Document {} is being modified by another transaction", key),
+        "Try again once the other transaction has completed or expired".to_string(),
+        span,
+    )
+}
+
+fn concurrent_modification_error(key: &str, span: Span) -> ShellError {
+    generic_error(
+        format!("Document {} was modified outside of the transaction", key),
+        None,
+        span,
+    )
+}
+
+// Reads a document as seen from inside the transaction, which includes its own staged mutations
+// and those of other transactions which have committed but are not yet unstaged.
+pub(crate) fn transactional_get(
+    ctx: &mut TransactionContext,
+    txn: &KvTransaction,
+    keyspace: &TransactionKeyspace,
+    key: &str,
+) -> Result<(serde_json::Value, u64), ShellError> {
+    let not_found = |span| {
+        client_error_to_shell_error(
+            ClientError::KeyNotFound {
+                key: key.to_string(),
+            },
+            span,
+        )
+    };
+
+    if let Some(i) = txn.staged_index(keyspace, key) {
+        let staged = &txn.staged[i];
+        return match staged.mutation_type {
+            StagedMutationType::Remove => Err(not_found(ctx.span)),
+            _ => Ok((staged.content.clone().unwrap_or_default(), staged.cas)),
+        };
+    }
+
+    let doc = match ctx.lookup_doc(keyspace, key)? {
+        Some(d) => d,
+        None => return Err(not_found(ctx.span)),
+    };
+
+    if let Some(txn_xattr) = &doc.txn {
+        if ctx.attempt_state(txn_xattr)?.as_deref() == Some("COMMITTED") {
+            return match txn_xattr.pointer("/op/type").and_then(|t| t.as_str()) {
+                Some("remove") => Err(not_found(ctx.span)),
+                _ => Ok((
+                    txn_xattr.pointer("/op/stgd").cloned().unwrap_or_default(),
+                    doc.cas,
+                )),
+            };
+        }
+    }
+
+    if doc.deleted {
+        return Err(not_found(ctx.span));
+    }
+
+    Ok((doc.content, doc.cas))
+}
+
+// Stages an insert, replace or remove of a document in the transaction.
+pub(crate) fn stage_mutation(
+    ctx: &mut TransactionContext,
+    txn: &mut KvTransaction,
+    keyspace: &TransactionKeyspace,
+    key: &str,
+    mutation_type: StagedMutationType,
+    content: Option<serde_json::Value>,
+) -> Result<(), ShellError> {
+    let span = ctx.span;
+    if txn.committed {
+        return Err(transaction_committed_error(span));
+    }
+    if txn.expired() {
+        return Err(transaction_expired_error(span));
+    }
+
+    let exists_error = || {
+        client_error_to_shell_error(
+            ClientError::KeyAlreadyExists {
+                key: key.to_string(),
+            },
+            span,
+        )
+    };
+    let not_found_error = || {
+        client_error_to_shell_error(
+            ClientError::KeyNotFound {
+                key: key.to_string(),
+            },
+            span,
+        )
+    };
+
+    let doc = ctx.lookup_doc(keyspace, key)?;
+    let staged_index = txn.staged_index(keyspace, key);
+
+    let mutation_type = if let Some(i) = staged_index {
+        // The document has already been staged by this transaction, so the new mutation is
+        // combined with the existing one.
+        match (txn.staged[i].mutation_type, mutation_type) {
+            (StagedMutationType::Insert, StagedMutationType::Insert)
+            | (StagedMutationType::Replace, StagedMutationType::Insert) => {
+                return Err(exists_error())
+            }
+            (StagedMutationType::Remove, StagedMutationType::Replace)
+            | (StagedMutationType::Remove, StagedMutationType::Remove) => {
+                return Err(not_found_error())
+            }
+            (StagedMutationType::Insert, StagedMutationType::Remove) => {
+                // Removing a document inserted by this transaction leaves nothing to commit.
+                let staged = txn.staged.remove(i);
+                ctx.mutate(
+                    keyspace,
+                    key,
+                    vec![MutateInSpec::Remove {
+                        path: "txn".to_string(),
+                        xattr: true,
+                    }],
+                    staged.cas,
+                    SUBDOC_DOC_FLAG_ACCESS_DELETED,
+                )?
+                .map_err(|e| client_error_to_shell_error(e, span))?;
+                return Ok(());
+            }
+            (StagedMutationType::Insert, _) => StagedMutationType::Insert,
+            (StagedMutationType::Remove, StagedMutationType::Insert) => StagedMutationType::Replace,
+            (_, t) => t,
+        }
+    } else {
+        if let Some(d) = &doc {
+            if let Some(txn_xattr) = &d.txn {
+                if d.attempt_id().as_deref() != Some(txn.attempt_id.as_str()) {
+                    match ctx.attempt_state(txn_xattr)?.as_deref() {
+                        Some("PENDING") | Some("COMMITTED") => {
+                            return Err(write_write_conflict_error(key, span));
+                        }
+                        _ => {
+                            debug!(
+                                "Overwriting abandoned staged mutation on {} from {:?}",
+                                key,
+                                d.attempt_id()
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        let exists = doc.as_ref().map(|d| !d.deleted).unwrap_or_default();
+        match mutation_type {
+            StagedMutationType::Insert if exists => return Err(exists_error()),
+            StagedMutationType::Replace | StagedMutationType::Remove if !exists => {
+                return Err(not_found_error())
+            }
+            _ => {}
+        }
+
+        mutation_type
+    };
+
+    if txn.atr.is_none() {
+        let (client, _) = ctx.client(keyspace)?;
+        let partition = client.partition_for_key(key.to_string()) as usize;
+        let atr_key = ATR_IDS[partition % ATR_IDS.len()].to_string();
+
+        debug!("Using ATR {} for transaction {}", &atr_key, &txn.id);
+
+        let atr = (atr_key, keyspace.clone());
+        ctx.update_atr_entry(
+            &atr,
+            &txn.attempt_id,
+            vec![
+                ("tid", json!(txn.id), false),
+                ("st", json!("PENDING"), false),
+                ("tst", json!("${Mutation.CAS}"), true),
+                ("exp", json!(txn.remaining().as_millis() as u64), false),
+            ],
+            SUBDOC_DOC_FLAG_MKDOC,
+        )?;
+        txn.atr = Some(atr);
+    }
+
+    let (atr_key, atr_keyspace) = txn.atr.clone().unwrap();
+    // A document staged again keeps its record, its current mutation type is in its txn xattr.
+    if staged_index.is_none() {
+        ctx.record_staged_doc(
+            &(atr_key.clone(), atr_keyspace.clone()),
+            &txn.attempt_id,
+            mutation_type,
+            keyspace,
+            key,
+        )?;
+    }
+
+    let mut op = json!({ "type": mutation_type.as_str() });
+    if let Some(c) = &content {
+        op["stgd"] = c.clone();
+    }
+    let txn_xattr = json!({
+        "id": {"txn": txn.id, "atmpt": txn.attempt_id},
+        "atr": {
+            "id": atr_key,
+            "bkt": atr_keyspace.bucket,
+            "scp": atr_keyspace.scope,
+            "coll": atr_keyspace.collection,
+        },
+        "op": op,
+    });
+
+    let (cas, doc_flags) = match &doc {
+        Some(d) if d.deleted => (d.cas, SUBDOC_DOC_FLAG_ACCESS_DELETED),
+        Some(d) => (d.cas, 0),
+        None => (
+            0,
+            SUBDOC_DOC_FLAG_ACCESS_DELETED
+                | SUBDOC_DOC_FLAG_CREATE_AS_DELETED
+                | SUBDOC_DOC_FLAG_ADD,
+        ),
+    };
+
+    let response = ctx.mutate(
+        keyspace,
+        key,
+        vec![MutateInSpec::Upsert {
+            path: "txn".to_string(),
+            value: serde_json::to_vec(&txn_xattr)
+                .map_err(|e| serialize_error(e.to_string(), span))?,
+            xattr: true,
+            expand_macros: false,
+        }],
+        cas,
+        doc_flags,
+    )?;
+
+    let response = match response {
+        Ok(r) => r,
+        Err(ClientError::KeyAlreadyExists { .. }) => {
+            return Err(concurrent_modification_error(key, span))
+        }
+        Err(e) => return Err(client_error_to_shell_error(e, span)),
+    };
+
+    let staged = StagedMutation {
+        keyspace: keyspace.clone(),
+        key: key.to_string(),
+        mutation_type,
+        content,
+        cas: response.cas(),
+    };
+    match staged_index {
+        Some(i) => txn.staged[i] = staged,
+        None => txn.staged.push(staged),
+    }
+
+    Ok(())
+}
+
+// Builds the mutation which unstages a document, either applying the staged mutation on commit or
+// discarding it on rollback.
+fn unstage_specs(
+    mutation_type: StagedMutationType,
+    commit: bool,
+    content: Option<&serde_json::Value>,
+    span: Span,
+) -> Result<(Vec<MutateInSpec>, u8), ShellError> {
+    let remove_txn = MutateInSpec::Remove {
+        path: "txn".to_string(),
+        xattr: true,
+    };
+    let content = || {
+        serde_json::to_vec(&content.cloned().unwrap_or_default())
+            .map_err(|e| serialize_error(e.to_string(), span))
+    };
+
+    Ok(match (mutation_type, commit) {
+        (StagedMutationType::Insert, true) => (
+            vec![remove_txn, MutateInSpec::SetDoc { value: content()? }],
+            SUBDOC_DOC_FLAG_ACCESS_DELETED | SUBDOC_DOC_FLAG_REVIVE_DOCUMENT,
+        ),
+        (StagedMutationType::Replace, true) => (
+            vec![remove_txn, MutateInSpec::SetDoc { value: content()? }],
+            0,
+        ),
+        (StagedMutationType::Remove, true) => (vec![MutateInSpec::DeleteDoc], 0),
+        (StagedMutationType::Insert, false) => (vec![remove_txn], SUBDOC_DOC_FLAG_ACCESS_DELETED),
+        (_, false) => (vec![remove_txn], 0),
+    })
+}
+
+// Applies a mutation to a staged document, retrying with a fresh CAS if the document was changed
+// but is still staged by the attempt.
+fn apply_to_staged(
+    ctx: &mut TransactionContext,
+    keyspace: &TransactionKeyspace,
+    key: &str,
+    attempt_id: &str,
+    cas: u64,
+    (specs, doc_flags): (Vec<MutateInSpec>, u8),
+) -> Result<(), ShellError> {
+    let mut cas = cas;
+    loop {
+        match ctx.mutate(keyspace, key, specs.clone(), cas, doc_flags)? {
+            Ok(_) => return Ok(()),
+            Err(ClientError::KeyNotFound { .. }) | Err(ClientError::PathNotFound { .. }) => {
+                debug!("Staged mutation on {} no longer present", key);
+                return Ok(());
+            }
+            Err(ClientError::KeyAlreadyExists { .. }) => match ctx.lookup_doc(keyspace, key)? {
+                Some(d) if d.attempt_id().as_deref() == Some(attempt_id) => {
+                    cas = d.cas;
+                }
+                _ => {
+                    debug!("Staged mutation on {} was removed by another actor", key);
+                    return Ok(());
+                }
+            },
+            Err(e) => return Err(client_error_to_shell_error(e, ctx.span)),
+        }
+    }
+}
+
+// The documents staged by the transaction, in the form recorded in the ATR entry.
+fn staged_doc_records(txn: &KvTransaction) -> Vec<(&str, serde_json::Value, bool)> {
+    let docs = |mutation_type| {
+        serde_json::Value::Array(
+            txn.staged
+                .iter()
+                .filter(|m| m.mutation_type == mutation_type)
+                .map(|m| m.keyspace.doc_record(&m.key))
+                .collect(),
+        )
+    };
+
+    vec![
+        ("ins", docs(StagedMutationType::Insert), false),
+        ("rep", docs(StagedMutationType::Replace), false),
+        ("rem", docs(StagedMutationType::Remove), false),
+    ]
+}
+
+// Commits the transaction, returning the number of documents mutated. An error before the ATR
+// entry is marked as committed leaves the transaction in place so that it can be rolled back,
+// after that it is marked as committed on the transaction so that committing again resumes
+// unstaging the documents.
+pub(crate) fn commit_transaction(
+    ctx: &mut TransactionContext,
+    txn: &mut KvTransaction,
+) -> Result<usize, ShellError> {
+    let span = ctx.span;
+    let atr = match &txn.atr {
+        Some(a) => a.clone(),
+        None => return Ok(0),
+    };
+
+    if !txn.committed {
+        if txn.expired() {
+            return Err(transaction_expired_error(span));
+        }
+
+        let mut fields = vec![
+            ("st", json!("COMMITTED"), false),
+            ("tsc", json!("${Mutation.CAS}"), true),
+        ];
+        fields.extend(staged_doc_records(txn));
+        ctx.update_atr_entry(&atr, &txn.attempt_id, fields, 0)?;
+        txn.committed = true;
+    }
+
+    // Documents which have already been unstaged are skipped by apply_to_staged, as they no
+    // longer hold the attempt's staged mutation.
+    for staged in &txn.staged {
+        debug!(
+            "Unstaging {} of {} in transaction {}",
+            staged.mutation_type.as_str(),
+            &staged.key,
+            &txn.id
+        );
+
+        let specs = unstage_specs(staged.mutation_type, true, staged.content.as_ref(), span)?;
+        apply_to_staged(
+            ctx,
+            &staged.keyspace,
+            &staged.key,
+            &txn.attempt_id,
+            staged.cas,
+            specs,
+        )?;
+    }
+
+    ctx.remove_atr_entry(&atr, &txn.attempt_id)?;
+
+    Ok(txn.staged.len())
+}
+
+// Rolls back the transaction, returning the number of staged documents which were discarded.
+pub(crate) fn rollback_transaction(
+    ctx: &mut TransactionContext,
+    txn: &KvTransaction,
+) -> Result<usize, ShellError> {
+    let span = ctx.span;
+    if txn.committed {
+        return Err(transaction_committed_error(span));
+    }
+    let atr = match &txn.atr {
+        Some(a) => a,
+        None => return Ok(0),
+    };
+
+    let mut fields = vec![
+        ("st", json!("ABORTED"), false),
+        ("tsrs", json!("${Mutation.CAS}"), true),
+    ];
+    fields.extend(staged_doc_records(txn));
+    ctx.update_atr_entry(atr, &txn.attempt_id, fields, 0)?;
+
+    for staged in &txn.staged {
+        debug!("Discarding staged mutation of {}", &staged.key);
+
+        let specs = unstage_specs(staged.mutation_type, false, None, span)?;
+        apply_to_staged(
+            ctx,
+            &staged.keyspace,
+            &staged.key,
+            &txn.attempt_id,
+            staged.cas,
+            specs,
+        )?;
+    }
+
+    ctx.remove_atr_entry(atr, &txn.attempt_id)?;
+
+    Ok(txn.staged.len())
+}
+
+// An attempt recorded in an ATR, which may belong to a transaction in any process.
+pub(crate) struct AtrAttempt {
+    pub(crate) atr: (String, TransactionKeyspace),
+    pub(crate) attempt_id: String,
+    pub(crate) transaction_id: String,
+    pub(crate) state: String,
+    pub(crate) age: Option<Duration>,
+    pub(crate) expires_after: Option<Duration>,
+    entry: serde_json::Value,
+}
+
+impl AtrAttempt {
+    fn from_entry(
+        atr: (String, TransactionKeyspace),
+        attempt_id: String,
+        entry: serde_json::Value,
+        now: Option<Duration>,
+    ) -> Self {
+        let field = |name: &str| {
+            entry
+                .get(name)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        let started = entry
+            .get("tst")
+            .and_then(|t| t.as_str())
+            .and_then(parse_cas_macro);
+
+        Self {
+            atr,
+            transaction_id: field("tid"),
+            state: field("st"),
+            age: now
+                .zip(started)
+                .map(|(now, started)| now.saturating_sub(started)),
+            expires_after: entry
+                .get("exp")
+                .and_then(|e| e.as_u64())
+                .map(Duration::from_millis),
+            attempt_id,
+            entry,
+        }
+    }
+
+    pub(crate) fn expired(&self) -> bool {
+        matches!((self.age, self.expires_after), (Some(age), Some(exp)) if age >= exp)
+    }
+
+    // Whether the entry lists the documents staged by the attempt. Entries written by other
+    // clients may only list them once the attempt is committed or aborted.
+    fn docs_recorded(&self) -> bool {
+        ["ins", "rep", "rem"]
+            .iter()
+            .any(|field| self.entry.get(field).is_some())
+    }
+
+    fn staged_docs(&self) -> Vec<(TransactionKeyspace, String, StagedMutationType)> {
+        let mut docs = vec![];
+        for mutation_type in [
+            StagedMutationType::Insert,
+            StagedMutationType::Replace,
+            StagedMutationType::Remove,
+        ] {
+            let records = match self
+                .entry
+                .get(mutation_type.atr_field())
+                .and_then(|r| r.as_array())
+            {
+                Some(r) => r,
+                None => continue,
+            };
+            for record in records {
+                let field = |name: &str| {
+                    record
+                        .get(name)
+                        .and_then(|v| v.as_str())
+                        .map(|v| v.to_string())
+                };
+                if let (Some(bucket), Some(scope), Some(collection), Some(key)) =
+                    (field("bkt"), field("scp"), field("col"), field("id"))
+                {
+                    docs.push((
+                        TransactionKeyspace::new(bucket, scope, collection),
+                        key,
+                        mutation_type,
+                    ));
+                }
+            }
+        }
+        docs
+    }
+}
+
+// Mutation CAS macros are expanded to the CAS as a little endian hex string, the CAS being the
+// nanoseconds since the epoch at which the mutation was made.
+fn parse_cas_macro(cas: &str) -> Option<Duration> {
+    let hex = cas.strip_prefix("0x")?;
+    let cas = u64::from_str_radix(hex, 16).ok()?.swap_bytes();
+    Some(Duration::from_nanos(cas))
+}
+
+// Reads every ATR in the keyspace, the ATR used by a transaction is picked by the partition of
+// its first document so there is one for each partition.
+pub(crate) fn fetch_atr_attempts(
+    ctx: &mut TransactionContext,
+    keyspace: &TransactionKeyspace,
+) -> Result<Vec<AtrAttempt>, ShellError> {
+    let span = ctx.span;
+    let (client, cid) = ctx.client(keyspace)?;
+    let data_timeout = ctx.active_cluster()?.timeouts().data_timeout();
+
+    let mut workers = FuturesUnordered::new();
+    for atr_key in ATR_IDS.iter().take(client.num_partitions() as usize) {
+        let client = client.clone();
+        let signals = ctx.signals.clone();
+        workers.push(async move {
+            client
+                .request(
+                    KeyValueRequest::LookupIn {
+                        key: atr_key.to_string(),
+                        specs: vec![
+                            LookupInSpec::Get {
+                                path: "attempts".to_string(),
+                                xattr: true,
+                            },
+                            LookupInSpec::Get {
+                                path: "$vbucket.HLC".to_string(),
+                                xattr: true,
+                            },
+                        ],
+                        access_deleted: false,
+                    },
+                    cid,
+                    Instant::now().add(data_timeout),
+                    signals,
+                )
+                .await
+        });
+    }
+
+    let mut attempts = vec![];
+    ctx.rt.block_on(async {
+        while let Some(response) = workers.next().await {
+            let mut response = match response {
+                Ok(r) => r,
+                Err(ClientError::KeyNotFound { .. }) => continue,
+                Err(e) => return Err(client_error_to_shell_error(e, span)),
+            };
+
+            let key = response.key();
+            let content = response.content().unwrap_or_default();
+            let entries = match content.get(0).and_then(|a| a.as_object()) {
+                Some(e) => e.clone(),
+                None => continue,
+            };
+            // The HLC is reported in seconds.
+            let now = content
+                .pointer("/1/now")
+                .and_then(|n| n.as_str())
+                .and_then(|n| n.parse::<u64>().ok())
+                .map(Duration::from_secs);
+
+            for (attempt_id, entry) in entries {
+                attempts.push(AtrAttempt::from_entry(
+                    (key.clone(), keyspace.clone()),
+                    attempt_id,
+                    entry,
+                    now,
+                ));
+            }
+        }
+        Ok(())
+    })?;
+
+    attempts.sort_by(|a, b| a.atr.0.cmp(&b.atr.0));
+
+    Ok(attempts)
+}
+
+// Completes an attempt abandoned by its transaction, committed attempts are rolled forward and
+// anything else is rolled back. Returns the action taken.
+pub(crate) fn cleanup_attempt(
+    ctx: &mut TransactionContext,
+    attempt: &AtrAttempt,
+) -> Result<&'static str, ShellError> {
+    let span = ctx.span;
+
+    if attempt.state == "PENDING" {
+        // Marking the attempt as aborted allows other transactions to overwrite its staged
+        // mutations, even those which cannot be found through the entry.
+        ctx.update_atr_entry(
+            &attempt.atr,
+            &attempt.attempt_id,
+            vec![("st", json!("ABORTED"), false)],
+            0,
+        )?;
+    }
+
+    let commit = attempt.state == "COMMITTED";
+    if !commit && !attempt.docs_recorded() {
+        // The entry is the only record of the attempt, so it is kept rather than leaving the
+        // staged mutations it points to orphaned.
+        return Ok("aborted");
+    }
+
+    for (keyspace, key, recorded_type) in attempt.staged_docs() {
+        let doc = match ctx.lookup_doc(&keyspace, &key)? {
+            Some(d) if d.attempt_id().as_deref() == Some(attempt.attempt_id.as_str()) => d,
+            _ => continue,
+        };
+
+        // The mutation staged on the document may have changed since it was recorded.
+        let mutation_type = doc.staged_mutation_type().unwrap_or(recorded_type);
+
+        debug!(
+            "Cleaning up {} of {} from attempt {}",
+            mutation_type.as_str(),
+            &key,
+            &attempt.attempt_id
+        );
+
+        let content = doc.txn.as_ref().and_then(|t| t.pointer("/op/stgd"));
+        let specs = unstage_specs(mutation_type, commit, content, span)?;
+        apply_to_staged(ctx, &keyspace, &key, &attempt.attempt_id, doc.cas, specs)?;
+    }
+
+    ctx.remove_atr_entry(&attempt.atr, &attempt.attempt_id)?;
+
+    Ok(match attempt.state.as_str() {
+        "COMMITTED" => "committed",
+        "PENDING" | "ABORTED" => "rolled back",
+        _ => "removed",
+    })
+}
+
+// Fetches documents for `doc get` from inside the active transaction.
+pub(crate) fn run_transactional_get(
+    state: &Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    ids: Vec<String>,
+    id_column: String,
+) -> Result<Vec<Value>, ShellError> {
+    let span = call.head;
+    let halt_on_error = call.has_flag(engine_state, stack, "halt-on-error")?;
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, state, call, true)?;
+
+    let guard = state.lock().unwrap();
+    let txn = guard
+        .kv_transaction()
+        .ok_or_else(|| no_kv_transaction_error(span))?;
+    let keyspace = transaction_keyspace(
+        &guard,
+        &txn,
+        &cluster_identifiers,
+        call.get_flag(engine_state, stack, "bucket")?,
+        call.get_flag(engine_state, stack, "scope")?,
+        call.get_flag(engine_state, stack, "collection")?,
+        span,
+    )?;
+
+    let rt = Runtime::new().unwrap();
+    let mut ctx = TransactionContext::new(
+        &guard,
+        &rt,
+        txn.cluster.clone(),
+        engine_state.signals().clone(),
+        span,
+    );
+
+    let mut results = vec![];
+    for id in ids {
+        let collected = GetResult::new(&txn.cluster)
+            .id_column(&id_column)
+            .key(id.clone());
+        let collected = match transactional_get(&mut ctx, &txn, &keyspace, &id)
+            .and_then(|(content, cas)| Ok((convert_json_value_to_nu_value(&content, span)?, cas)))
+        {
+            Ok((content, cas)) => collected.content(content).cas(cas as i64),
+            Err(e) => {
+                if halt_on_error {
+                    return Err(e);
+                }
+                collected.error(e.to_string())
+            }
+        };
+        results.push(collected.into_value(span));
+    }
+
+    Ok(results)
+}
+
+// Stages the mutations of `doc insert`, `doc replace` and `doc remove` in the active transaction.
+pub(crate) fn run_transactional_mutations(
+    state: &Arc<Mutex<State>>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    items: Vec<(String, Option<serde_json::Value>)>,
+    mutation_type: StagedMutationType,
+) -> Result<Vec<Value>, ShellError> {
+    let span = call.head;
+    let halt_on_error = call.has_flag(engine_state, stack, "halt-on-error")?;
+    let cluster_identifiers = cluster_identifiers_from(engine_state, stack, state, call, true)?;
+
+    let guard = state.lock().unwrap();
+    let mut txn = guard
+        .kv_transaction()
+        .ok_or_else(|| no_kv_transaction_error(span))?;
+    let keyspace = transaction_keyspace(
+        &guard,
+        &txn,
+        &cluster_identifiers,
+        call.get_flag(engine_state, stack, "bucket")?,
+        call.get_flag(engine_state, stack, "scope")?,
+        call.get_flag(engine_state, stack, "collection")?,
+        span,
+    )?;
+
+    let rt = Runtime::new().unwrap();
+    let mut ctx = TransactionContext::new(
+        &guard,
+        &rt,
+        txn.cluster.clone(),
+        engine_state.signals().clone(),
+        span,
+    );
+
+    let mut success = 0;
+    let mut failed = 0;
+    let mut fail_reasons = HashSet::new();
+    let mut result = Ok(());
+    for (key, content) in items {
+        if key.is_empty() {
+            failed += 1;
+            fail_reasons.insert("Missing doc id".to_string());
+            continue;
+        }
+
+        match stage_mutation(&mut ctx, &mut txn, &keyspace, &key, mutation_type, content) {
+            Ok(()) => success += 1,
+            Err(e) => {
+                if halt_on_error {
+                    result = Err(e);
+                    break;
+                }
+                failed += 1;
+                fail_reasons.insert(e.to_string());
+            }
+        }
+    }
+
+    // Anything staged before a failure is still part of the transaction.
+    guard.update_kv_transaction(txn.clone());
+    result?;
+
+    Ok(vec![MutationResult::new(txn.cluster)
+        .success(success)
+        .failed(failed)
+        .fail_reasons(fail_reasons)
+        .into_value(span)])
+}
+
+pub(crate) fn attempt_to_value(attempt: &AtrAttempt, cluster: &str, span: Span) -> Value {
+    let duration = |d: Option<Duration>| match d {
+        Some(d) => Value::duration(d.as_nanos() as i64, span),
+        None => Value::nothing(span),
+    };
+
+    let mut collected = NuValueMap::default();
+    collected.add_string("atr", attempt.atr.0.clone(), span);
+    collected.add_string("attempt", attempt.attempt_id.clone(), span);
+    collected.add_string("transaction", attempt.transaction_id.clone(), span);
+    collected.add_string("state", attempt.state.clone(), span);
+    collected.add("age", duration(attempt.age));
+    collected.add("expires_after", duration(attempt.expires_after));
+    collected.add_bool("expired", attempt.expired(), span);
+    collected.add_string("bucket", attempt.atr.1.bucket.clone(), span);
+    collected.add_string("scope", attempt.atr.1.scope.clone(), span);
+    collected.add_string("collection", attempt.atr.1.collection.clone(), span);
+    collected.add_string("cluster", cluster, span);
+    collected.into_value(span)
+}
+
+pub(crate) fn transaction_to_value(
+    txn: &KvTransaction,
+    status: &str,
+    mutations: usize,
+    span: Span,
+) -> Value {
+    let mut collected = NuValueMap::default();
+    collected.add_string("id", txn.id.clone(), span);
+    collected.add_string("cluster", txn.cluster.clone(), span);
+    collected.add_string("status", status, span);
+    collected.add_i64("mutations", mutations as i64, span);
+    collected.into_value(span)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::transactions_common::{
+        atr_from_txn_xattr, parse_cas_macro, AtrAttempt, StagedMutationType, TransactionKeyspace,
+    };
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn atr_from_staged_doc() {
+        let txn = json!({
+            "id": {"txn": "t1", "atmpt": "a1"},
+            "atr": {"id": "_txn:atr-0-#14", "bkt": "default", "scp": "_default", "coll": "_default"},
+            "op": {"type": "insert", "stgd": {"x": 1}},
+        });
+
+        assert_eq!(
+            Some((
+                "_txn:atr-0-#14".to_string(),
+                TransactionKeyspace::new("default".to_string(), "".to_string(), "".to_string()),
+                "a1".to_string()
+            )),
+            atr_from_txn_xattr(&txn)
+        );
+    }
+
+    #[test]
+    fn atr_from_doc_missing_atr() {
+        assert_eq!(None, atr_from_txn_xattr(&json!({"id": {"atmpt": "a1"}})));
+    }
+
+    #[test]
+    fn parse_expanded_cas_macro() {
+        assert_eq!(
+            Some(Duration::from_nanos(0x17e5_3c1a_9d2b_0000)),
+            parse_cas_macro("0x00002b9d1a3ce517")
+        );
+        assert_eq!(None, parse_cas_macro("${Mutation.CAS}"));
+    }
+
+    #[test]
+    fn atr_attempt_staged_docs() {
+        let atr = (
+            "_txn:atr-0-#14".to_string(),
+            TransactionKeyspace::new("default".to_string(), "".to_string(), "".to_string()),
+        );
+        let attempt = AtrAttempt::from_entry(
+            atr.clone(),
+            "a1".to_string(),
+            json!({
+                "tid": "t1",
+                "st": "PENDING",
+                "ins": [{"bkt": "default", "scp": "_default", "col": "_default", "id": "a"}],
+                "rem": [{"bkt": "default", "scp": "inventory", "col": "hotel", "id": "b"}],
+            }),
+            None,
+        );
+
+        assert!(attempt.docs_recorded());
+        assert_eq!(
+            vec![
+                (atr.1.clone(), "a".to_string(), StagedMutationType::Insert),
+                (
+                    TransactionKeyspace::new(
+                        "default".to_string(),
+                        "inventory".to_string(),
+                        "hotel".to_string()
+                    ),
+                    "b".to_string(),
+                    StagedMutationType::Remove
+                ),
+            ],
+            attempt.staged_docs()
+        );
+
+        let attempt = AtrAttempt::from_entry(
+            atr,
+            "a2".to_string(),
+            json!({"tid": "t2", "st": "PENDING"}),
+            None,
+        );
+        assert!(!attempt.docs_recorded());
+    }
+}
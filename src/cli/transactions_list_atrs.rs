@@ -1,23 +1,18 @@
-use crate::cli::error::{
-    client_error_to_shell_error, deserialize_error, no_active_bucket_error,
-    no_active_cluster_error, unexpected_status_code_error,
+use crate::cli::transactions_common::{
+    attempt_to_value, fetch_atr_attempts, TransactionContext, TransactionKeyspace,
 };
-use crate::cli::util::{convert_json_value_to_nu_value, duration_to_golang_string};
-use crate::client::QueryRequest;
+use crate::cli::util::{cluster_identifiers_from, get_active_cluster, namespace_from_args};
 use crate::state::State;
+use log::debug;
 use nu_engine::command_prelude::Call;
 use nu_engine::CallExt;
 use nu_protocol::engine::{Command, EngineState, Stack};
 use nu_protocol::{
-    Category, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape, Value,
 };
-use std::collections::HashMap;
-use std::ops::Add;
 use std::sync::{Arc, Mutex};
-use tokio::time::Instant;
+use tokio::runtime::Runtime;
 
-// For now you need a covered index like
-// create index id3 on `travel-sample`(meta().id, meta().xattrs.attempts);
 #[derive(Clone)]
 pub struct TransactionsListAtrs {
     state: Arc<Mutex<State>>,
@@ -42,18 +37,39 @@ impl Command for TransactionsListAtrs {
                 "the name of the bucket",
                 None,
             )
+            .named("scope", SyntaxShape::String, "the name of the scope", None)
+            .named(
+                "collection",
+                SyntaxShape::String,
+                "the name of the collection",
+                None,
+            )
+            .named(
+                "clusters",
+                SyntaxShape::String,
+                "the clusters which should be contacted",
+                None,
+            )
             .category(Category::Custom("couchbase".to_string()))
-        /* .named("scope", SyntaxShape::String, "the name of the scope", None)
-        .named(
-            "collection",
-            SyntaxShape::String,
-            "the name of the collection",
-            None,
-        )*/
     }
 
     fn description(&self) -> &str {
-        "Lists all active transaction records"
+        "Lists the transaction attempts recorded in the active transaction records of a collection"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "List the attempts in the active collection",
+                example: "transactions list-atrs",
+                result: None,
+            },
+            Example {
+                description: "List the expired attempts in a collection",
+                example: "transactions list-atrs --bucket travel-sample --scope inventory --collection airline | where expired",
+                result: None,
+            },
+        ]
     }
 
     fn run(
@@ -66,85 +82,36 @@ impl Command for TransactionsListAtrs {
         let span = call.head;
         let signals = engine_state.signals().clone();
 
-        let guard = self.state.lock().unwrap();
-        let active_cluster = match guard.active_cluster() {
-            Some(c) => c,
-            None => {
-                return Err(no_active_cluster_error(span));
-            }
-        };
-        let bucket = match call
-            .get_flag(engine_state, stack, "bucket")?
-            .or_else(|| active_cluster.active_bucket())
-        {
-            Some(v) => Ok(v),
-            None => Err(no_active_bucket_error(span)),
-        }?;
+        let bucket_flag: Option<String> = call.get_flag(engine_state, stack, "bucket")?;
+        let scope_flag: Option<String> = call.get_flag(engine_state, stack, "scope")?;
+        let collection_flag: Option<String> = call.get_flag(engine_state, stack, "collection")?;
 
-        /*
-        let scope = match args.get_flag("scope")? {
-            Some(s) => s,
-            None => match active_cluster.active_scope() {
-                Some(s) => s,
-                None => "".into(),
-            },
-        };
+        let cluster_identifiers =
+            cluster_identifiers_from(engine_state, stack, &self.state, call, true)?;
+        let guard = self.state.lock().unwrap();
+        let rt = Runtime::new().unwrap();
 
-        let collection = match args.get_flag("collection")? {
-            Some(c) => c,
-            None => match active_cluster.active_collection() {
-                Some(c) => c,
-                None => "".into(),
-            },
-        };*/
+        let mut results = vec![];
+        for identifier in cluster_identifiers {
+            let active_cluster = get_active_cluster(identifier.clone(), &guard, span)?;
+            let (bucket, scope, collection) = namespace_from_args(
+                bucket_flag.clone(),
+                scope_flag.clone(),
+                collection_flag.clone(),
+                active_cluster,
+                span,
+            )?;
+            let keyspace = TransactionKeyspace::new(bucket, scope, collection);
 
-        let statement = format!(
-            "select meta().id, meta().xattrs.attempts from `{}` where meta().id like '_txn:atr%'",
-            bucket
-        );
-        let response = active_cluster
-            .cluster()
-            .http_client()
-            .query_request(
-                QueryRequest::Execute {
-                    statement,
-                    parameters: None,
-                    scope: None,
-                    timeout: duration_to_golang_string(active_cluster.timeouts().query_timeout()),
-                    transaction: None,
-                },
-                Instant::now().add(active_cluster.timeouts().query_timeout()),
-                signals,
-            )
-            .map_err(|e| client_error_to_shell_error(e, span))?;
+            debug!("Listing ATRs in {:?} on {}", &keyspace, &identifier);
 
-        match response.status() {
-            200 => {}
-            _ => {
-                return Err(unexpected_status_code_error(
-                    response.status(),
-                    response.content()?,
-                    span,
-                ));
+            let mut ctx =
+                TransactionContext::new(&guard, &rt, identifier.clone(), signals.clone(), span);
+            for attempt in fetch_atr_attempts(&mut ctx, &keyspace)? {
+                results.push(attempt_to_value(&attempt, &identifier, span));
             }
         }
 
-        let mut content: HashMap<String, serde_json::Value> =
-            serde_json::from_str(&response.content()?)
-                .map_err(|e| deserialize_error(e.to_string(), span))?;
-        let removed = if content.contains_key("errors") {
-            content.remove("errors").unwrap()
-        } else {
-            content.remove("results").unwrap()
-        };
-
-        let values = removed
-            .as_array()
-            .unwrap()
-            .iter()
-            .map(|a| convert_json_value_to_nu_value(a, span).unwrap())
-            .collect::<Vec<_>>();
-
-        Ok(Value::list(values, span).into_pipeline_data())
+        Ok(Value::list(results, span).into_pipeline_data())
     }
 }
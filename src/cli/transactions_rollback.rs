@@ -0,0 +1,83 @@
+use crate::cli::transactions_common::{
+    no_kv_transaction_error, rollback_transaction, transaction_committed_error,
+    transaction_to_value, TransactionContext,
+};
+use crate::state::State;
+use log::debug;
+use nu_engine::command_prelude::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, IntoPipelineData, PipelineData, ShellError, Signature};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Runtime;
+
+#[derive(Clone)]
+pub struct TransactionsRollback {
+    state: Arc<Mutex<State>>,
+}
+
+impl TransactionsRollback {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for TransactionsRollback {
+    fn name(&self) -> &str {
+        "transactions rollback"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("transactions rollback")
+            .category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Rolls back the active transaction, discarding its staged mutations"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Discard a staged replace",
+            example: "transactions begin; doc replace a {x: 2}; transactions rollback",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        _stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+
+        let guard = self.state.lock().unwrap();
+        let txn = guard
+            .kv_transaction()
+            .ok_or_else(|| no_kv_transaction_error(span))?;
+
+        // Once committed the transaction is visible to others, so it can only be completed.
+        if txn.committed {
+            return Err(transaction_committed_error(span));
+        }
+
+        debug!("Rolling back transaction {}", &txn.id);
+
+        // The transaction is ended even if rolling back fails, any staged mutations left behind
+        // are removed by cleanup once the transaction expires.
+        guard.end_kv_transaction();
+
+        let rt = Runtime::new().unwrap();
+        let mut ctx = TransactionContext::new(
+            &guard,
+            &rt,
+            txn.cluster.clone(),
+            engine_state.signals().clone(),
+            span,
+        );
+        let mutations = rollback_transaction(&mut ctx, &txn)?;
+
+        Ok(transaction_to_value(&txn, "rolled back", mutations, span).into_pipeline_data())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::cli::util::NuValueMap;
+use crate::state::State;
+use nu_engine::command_prelude::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Span, Value,
+};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct TransactionsStatus {
+    state: Arc<Mutex<State>>,
+}
+
+impl TransactionsStatus {
+    pub fn new(state: Arc<Mutex<State>>) -> Self {
+        Self { state }
+    }
+}
+
+impl Command for TransactionsStatus {
+    fn name(&self) -> &str {
+        "transactions status"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("transactions status").category(Category::Custom("couchbase".to_string()))
+    }
+
+    fn description(&self) -> &str {
+        "Shows the transaction open in the shell, if there is one"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Show how long the open transaction has left",
+            example: "transactions status",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _engine_state: &EngineState,
+        _stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+        let guard = self.state.lock().unwrap();
+
+        let mut results = vec![];
+        if let Some(txn) = guard.active_transaction() {
+            results.push(status_to_value(
+                "query",
+                txn.id(),
+                txn.cluster(),
+                txn.endpoint().to_string(),
+                txn.elapsed(),
+                txn.remaining(),
+                span,
+            ));
+        }
+        if let Some(txn) = guard.kv_transaction() {
+            results.push(status_to_value(
+                "kv",
+                txn.id.clone(),
+                txn.cluster.clone(),
+                "".to_string(),
+                txn.started.elapsed(),
+                txn.remaining(),
+                span,
+            ));
+        }
+
+        Ok(Value::list(results, span).into_pipeline_data())
+    }
+}
+
+fn status_to_value(
+    transaction_type: &str,
+    id: String,
+    cluster: String,
+    endpoint: String,
+    elapsed: Duration,
+    remaining: Duration,
+    span: Span,
+) -> Value {
+    let mut collected = NuValueMap::default();
+    collected.add_string("type", transaction_type, span);
+    collected.add_string("id", id, span);
+    collected.add_string("cluster", cluster, span);
+    collected.add_string("endpoint", endpoint, span);
+    collected.add("elapsed", Value::duration(elapsed.as_nanos() as i64, span));
+    collected.add(
+        "remaining",
+        Value::duration(remaining.as_nanos() as i64, span),
+    );
+    collected.into_value(span)
+}
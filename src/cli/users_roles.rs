@@ -87,6 +87,7 @@ fn run_async(
 
         let response = active_cluster
             .cluster()
+            .map_err(|e| client_error_to_shell_error(e, span))?
             .http_client()
             .management_request(
                 ManagementRequest::GetRoles {
@@ -114,6 +114,7 @@ fn users_upsert(
 
         let response = active_cluster
             .cluster()
+            .map_err(|e| client_error_to_shell_error(e, span))?
             .http_client()
             .management_request(
                 ManagementRequest::UpsertUser {
@@ -1,4 +1,4 @@
-use crate::cli::error::CBShellError::{MustNotBeCapella, ProjectNotFound};
+use crate::cli::error::CBShellError::{MustBeCapella, MustNotBeCapella, ProjectNotFound};
 use crate::cli::error::{
     client_error_to_shell_error, cluster_not_found_error, malformed_response_error,
     no_active_bucket_error, unexpected_status_code_error,
@@ -6,7 +6,7 @@ use crate::cli::error::{
 use crate::cli::generic_error;
 use crate::cli::CBShellError::ClusterNotFound;
 use crate::client::cloud_json::Cluster;
-use crate::client::CapellaClient;
+use crate::client::{CapellaClient, OperationTimings};
 use crate::config::ShellConfig;
 use crate::state::State;
 use crate::{read_input, RemoteCluster};
@@ -286,6 +286,21 @@ pub fn validate_is_not_cloud(
     Ok(())
 }
 
+pub fn validate_is_cloud(
+    cluster: &RemoteCluster,
+    command_name: impl Into<String>,
+    span: Span,
+) -> Result<(), ShellError> {
+    if !cluster.is_capella() {
+        return Err(MustBeCapella {
+            command_name: command_name.into(),
+            span,
+        }
+        .into());
+    }
+    Ok(())
+}
+
 // We take a conn_string instead of name since cluster local identfiers can differ from names of
 // clusters
 pub(crate) fn cluster_from_conn_str(
@@ -388,6 +403,16 @@ pub(crate) fn find_org_project_cluster_ids(
     Ok((org_id, project_id, cluster_id))
 }
 
+// wait_timeout_from reads the --wait flag, returning how long to wait for a long running Capella
+// operation if the command has been asked to wait for it at all.
+pub(crate) fn wait_timeout_from(
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+) -> Result<Option<Duration>, ShellError> {
+    call.get_flag(engine_state, stack, "wait")
+}
+
 // duration_to_golang_string creates a golang formatted string to use with timeouts. Unlike Golang
 // strings it does not deal with fractional seconds, we do not need that accuracy.
 pub fn duration_to_golang_string(duration: Duration) -> String {
@@ -455,6 +480,34 @@ impl NuValueMap {
     }
 }
 
+// Quotes and escapes a value for use as a string literal in a SQL++ statement.
+pub fn string_literal(value: &str) -> String {
+    serde_json::Value::String(value.to_string()).to_string()
+}
+
+// The timings of an operation as a record, for commands run with --with-timings.
+pub fn timings_to_nu_value(timings: &OperationTimings, span: Span) -> Value {
+    let mut collected = NuValueMap::default();
+    collected.add_string("operation", timings.operation(), span);
+    collected.add_string("address", timings.address(), span);
+    collected.add(
+        "dispatch",
+        Value::duration(timings.dispatch().as_nanos() as i64, span),
+    );
+    collected.add(
+        "server",
+        match timings.server() {
+            Some(d) => Value::duration(d.as_nanos() as i64, span),
+            None => Value::nothing(span),
+        },
+    );
+    collected.add(
+        "total",
+        Value::duration(timings.total().as_nanos() as i64, span),
+    );
+    collected.into_value(span)
+}
+
 pub fn get_active_cluster<'a>(
     identifier: String,
     guard: &'a MutexGuard<State>,
@@ -555,7 +608,7 @@ pub fn update_config_file(
 
 #[cfg(test)]
 mod tests {
-    use crate::cli::util::duration_to_golang_string;
+    use crate::cli::util::{duration_to_golang_string, string_literal};
     use std::time::Duration;
 
     #[test]
@@ -581,4 +634,12 @@ mod tests {
             duration_to_golang_string(Duration::from_secs(3902))
         );
     }
+
+    #[test]
+    fn string_literal_escapes_quotes() {
+        assert_eq!(
+            r#""sales/\"2024\"\\q1""#.to_string(),
+            string_literal(r#"sales/"2024"\q1"#)
+        );
+    }
 }
@@ -135,6 +135,7 @@ fn run(
 
         let response = cluster
             .cluster()
+            .map_err(|e| client_error_to_shell_error(e, span))?
             .http_client()
             .management_request(
                 ManagementRequest::VectorCreateIndex {
@@ -189,6 +190,7 @@ fn get_bucket_uuid(
 ) -> Result<String, ShellError> {
     let response = cluster
         .cluster()
+        .map_err(|e| client_error_to_shell_error(e, span))?
         .http_client()
         .management_request(
             ManagementRequest::GetBucket { name: bucket },
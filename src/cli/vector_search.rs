@@ -214,6 +214,7 @@ fn run(
         let qualified_index = index_name_from_namespace(index.clone(), namespace);
         let response = active_cluster
             .cluster()
+            .map_err(|e| client_error_to_shell_error(e, span))?
             .http_client()
             .search_query_request(
                 VectorSearchQueryRequest::Execute {
@@ -252,7 +253,10 @@ fn run(
     Ok(Value::list(results, call.head).into_pipeline_data())
 }
 
-fn index_name_from_namespace(index: String, namespace: (String, String, String)) -> String {
+pub(crate) fn index_name_from_namespace(
+    index: String,
+    namespace: (String, String, String),
+) -> String {
     let scope = if namespace.1.is_empty() {
         "_default".to_string()
     } else {
@@ -262,14 +266,14 @@ fn index_name_from_namespace(index: String, namespace: (String, String, String))
 }
 
 #[derive(Debug, Deserialize)]
-struct SearchResultHit {
-    score: f32,
-    id: String,
+pub(crate) struct SearchResultHit {
+    pub(crate) score: f32,
+    pub(crate) id: String,
 }
 
 #[derive(Debug, Deserialize)]
-struct SearchResultData {
-    hits: Vec<SearchResultHit>,
+pub(crate) struct SearchResultData {
+    pub(crate) hits: Vec<SearchResultHit>,
 }
 
 fn input_to_vector(content: &Value) -> Result<Vec<f32>, ShellError> {
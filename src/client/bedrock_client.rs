@@ -1,6 +1,8 @@
 use crate::cli::{api_base_unsupported, generic_error};
+use crate::client::{ChatMessage, ChatRole};
 use aws_sdk_bedrockruntime::operation::invoke_model::InvokeModelError;
 use aws_sdk_bedrockruntime::primitives::Blob;
+use aws_sdk_bedrockruntime::types::ResponseStream;
 use nu_protocol::ShellError;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -113,27 +115,13 @@ impl BedrockClient {
         &self,
         question: String,
         context: Vec<String>,
+        history: &[ChatMessage],
         model: String,
     ) -> Result<String, ShellError> {
         let config = aws_config::load_from_env().await;
         let client = aws_sdk_bedrockruntime::Client::new(&config);
 
-        let question_with_ctx = if !context.is_empty() {
-            format!(
-                "Please answer this question: \\\"{}\\\". Using the following context: \\\"{}\\\"",
-                question,
-                context.join(" ")
-            )
-        } else {
-            question
-        };
-
-        let prompt = AskPromptBody {
-            input_text: question_with_ctx,
-            text_generation_config: TextGenerationConfig {
-                max_token_count: MAX_RESPONSE_TOKENS,
-            },
-        };
+        let prompt = ask_prompt(question, context, history);
 
         let result = match client
             .invoke_model()
@@ -175,6 +163,121 @@ impl BedrockClient {
 
         Ok(answer.to_string())
     }
+
+    pub async fn ask_stream(
+        &self,
+        question: String,
+        context: Vec<String>,
+        history: &[ChatMessage],
+        model: String,
+        mut on_chunk: impl FnMut(String),
+    ) -> Result<String, ShellError> {
+        let config = aws_config::load_from_env().await;
+        let client = aws_sdk_bedrockruntime::Client::new(&config);
+
+        let prompt = ask_prompt(question, context, history);
+
+        let mut result = match client
+            .invoke_model_with_response_stream()
+            .model_id(model)
+            .content_type("application/json")
+            .body(Blob::new(serde_json::to_string(&prompt).unwrap()))
+            .send()
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                return Err(generic_error(format!(
+                    "error returned from Bedrock API: {:?}", e),
+                    "Please supply AWS SDK config and credentials in ~/.aws/config and ~/.aws/credentials files".to_string(),
+                    None
+                ));
+            }
+        };
+
+        let mut answer = String::new();
+        loop {
+            let event = match result.body.recv().await {
+                Ok(Some(e)) => e,
+                Ok(None) => break,
+                Err(e) => {
+                    return Err(generic_error(
+                        format!("error reading streamed response from Bedrock API: {:?}", e),
+                        None,
+                        None,
+                    ));
+                }
+            };
+
+            let bytes = match event {
+                ResponseStream::Chunk(part) => match part.bytes {
+                    Some(b) => b,
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            let chunk: AskStreamChunk = match serde_json::from_slice(bytes.as_ref()) {
+                Ok(c) => c,
+                Err(e) => {
+                    return Err(generic_error(
+                        format!("could not parse Bedrock response: {}", e),
+                        None,
+                        None,
+                    ));
+                }
+            };
+
+            answer.push_str(&chunk.output_text);
+            on_chunk(chunk.output_text);
+
+            if chunk.completion_reason.as_deref() == Some("LENGTH") {
+                let truncated = format!(
+                    " \n\nAnswer truncated as it exceeded max token response limit of {:?}",
+                    MAX_RESPONSE_TOKENS
+                );
+                answer.push_str(&truncated);
+                on_chunk(truncated);
+            }
+        }
+
+        Ok(answer)
+    }
+}
+
+// Titan text models take a single prompt, so any history is passed as a transcript of the
+// conversation so far in the format the models are trained on.
+fn ask_prompt(question: String, context: Vec<String>, history: &[ChatMessage]) -> AskPromptBody {
+    let question_with_ctx = if !context.is_empty() {
+        format!(
+            "Please answer this question: \\\"{}\\\". Using the following context: \\\"{}\\\"",
+            question,
+            context.join(" ")
+        )
+    } else {
+        question
+    };
+
+    let input_text = if history.is_empty() {
+        question_with_ctx
+    } else {
+        let mut transcript = String::new();
+        for message in history {
+            let speaker = match message.role() {
+                ChatRole::User => "User",
+                ChatRole::Assistant => "Bot",
+            };
+            transcript.push_str(&format!("{}: {}\n", speaker, message.content()));
+        }
+        format!("{}User: {}\nBot:", transcript, question_with_ctx)
+    };
+
+    AskPromptBody {
+        input_text,
+        text_generation_config: TextGenerationConfig {
+            max_token_count: MAX_RESPONSE_TOKENS,
+        },
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -203,7 +306,39 @@ struct AskResult {
     completion_reason: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AskStreamChunk {
+    output_text: String,
+    completion_reason: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct EmbeddingResponse {
     embedding: Vec<f32>,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::client::bedrock_client::ask_prompt;
+    use crate::client::{ChatMessage, ChatRole};
+
+    #[test]
+    fn ask_prompt_without_history() {
+        let prompt = ask_prompt("what is couchbase?".to_string(), vec![], &[]);
+        assert_eq!("what is couchbase?".to_string(), prompt.input_text);
+    }
+
+    #[test]
+    fn ask_prompt_with_history() {
+        let history = vec![
+            ChatMessage::new(ChatRole::User, "what is couchbase?"),
+            ChatMessage::new(ChatRole::Assistant, "a database"),
+        ];
+        let prompt = ask_prompt("what kind?".to_string(), vec![], &history);
+        assert_eq!(
+            "User: what is couchbase?\nBot: a database\nUser: what kind?\nBot:".to_string(),
+            prompt.input_text
+        );
+    }
+}
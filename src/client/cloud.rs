@@ -1,31 +1,37 @@
 use crate::cli::CtrlcFuture;
 use crate::client::cloud_json::{
-    Cluster, ClustersResponse, Collection, CollectionsResponse, ColumnarCluster,
-    ColumnarClustersResponse, CredentialsResponse, OrganizationsResponse, ProjectsResponse,
-    ScopesResponse,
+    AllowedCidrRequest, AllowedCidrsResponse, AppService, AppServiceCreateRequest,
+    AppServicesResponse, BackupRestoreRequest, BackupSchedule, BackupsResponse, Cluster,
+    ClustersResponse, Collection, CollectionsResponse, ColumnarCluster, ColumnarClustersResponse,
+    CredentialsResponse, NetworkPeersResponse, OnOffSchedule, OrganizationsResponse,
+    PrivateEndpointsResponse, ProjectsResponse, ScopesResponse,
 };
 use crate::client::error::ClientError;
-use crate::client::http_handler::{HttpResponse, HttpVerb};
+use crate::client::http_handler::{HttpResponse, HttpVerb, HTTP_RUNTIME};
+use crate::client::proxy::ProxyConfig;
 use base64::prelude::BASE64_STANDARD;
 use base64::{engine::general_purpose, Engine as _};
 use hmac::{Hmac, Mac};
-use log::debug;
+use log::{debug, info};
 use nu_protocol::Signals;
 use reqwest::Client;
 use sha2::Sha256;
+use std::net::IpAddr;
 use std::ops::{Add, Sub};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::runtime::Runtime;
 use tokio::{select, time::Instant};
 
 pub const CLOUD_URL: &str = "https://cloudapi.cloud.couchbase.com";
 pub const CAPELLA_SRV_SUFFIX: &str = "cloud.couchbase.com";
+pub const DEFAULT_IP_LOOKUP_ENDPOINT: &str = "https://api.ipify.org";
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
 
 pub struct CapellaClient {
     secret_key: String,
     access_key: String,
     api_endpoint: String,
     timeout: Duration,
+    client: Client,
 }
 
 impl CapellaClient {
@@ -34,12 +40,23 @@ impl CapellaClient {
         access_key: String,
         api_endpoint: String,
         timeout: Duration,
+        proxy: Option<ProxyConfig>,
     ) -> Self {
+        // Without a proxy of its own the organization uses the proxy environment variables.
+        let client = match proxy {
+            Some(proxy) => Client::builder()
+                .proxy(proxy.reqwest_proxy())
+                .build()
+                .unwrap(),
+            None => Client::new(),
+        };
+
         Self {
             secret_key,
             access_key,
             api_endpoint,
             timeout,
+            client,
         }
     }
 
@@ -63,12 +80,11 @@ impl CapellaClient {
 
         let uri = format!("{}{}", self.api_endpoint, path);
 
-        let client = Client::new();
         let mut res_builder = match verb {
-            HttpVerb::Get => client.get(uri),
-            HttpVerb::Delete => client.delete(uri),
-            HttpVerb::Put => client.put(uri),
-            HttpVerb::Post => client.post(uri),
+            HttpVerb::Get => self.client.get(uri),
+            HttpVerb::Delete => self.client.delete(uri),
+            HttpVerb::Put => self.client.put(uri),
+            HttpVerb::Post => self.client.post(uri),
         };
 
         let now_millis = SystemTime::now()
@@ -105,8 +121,7 @@ impl CapellaClient {
 
         debug!("Performing Capella management request {:?}", &res_builder);
 
-        let rt = Runtime::new().unwrap();
-        rt.block_on(async {
+        HTTP_RUNTIME.block_on(async {
             let res_fut = res_builder.send();
             select! {
                 result = res_fut => {
@@ -308,6 +323,34 @@ impl CapellaClient {
         handle_cluster_management_response(response)
     }
 
+    pub fn update_cluster(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        payload: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::ClusterUpdate {
+            org_id,
+            project_id,
+            cluster_id,
+            payload,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        match response.status() {
+            204 => Ok(()),
+            403 => Err(ClientError::AccessDenied {
+                reason: "Make sure that the API key has the Cluster Manager role enabled for the target project".to_string()
+            }),
+            _ => Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            }),
+        }
+    }
+
     pub fn delete_cluster(
         &self,
         org_id: String,
@@ -325,6 +368,203 @@ impl CapellaClient {
         handle_cluster_management_response(response)
     }
 
+    pub fn turn_cluster_on(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        turn_on_app_service: bool,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::ClusterOn {
+            org_id,
+            project_id,
+            cluster_id,
+            payload: format!("{{\"turnOnLinkedAppService\": {}}}", turn_on_app_service),
+        };
+        let response = self.capella_request(request, signals)?;
+
+        handle_cluster_management_response(response)
+    }
+
+    pub fn turn_cluster_off(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::ClusterOff {
+            org_id,
+            project_id,
+            cluster_id,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        handle_cluster_management_response(response)
+    }
+
+    pub fn get_on_off_schedule(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        signals: Signals,
+    ) -> Result<Option<OnOffSchedule>, ClientError> {
+        let request = CapellaRequest::ClusterScheduleGet {
+            org_id,
+            project_id,
+            cluster_id,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        match response.status() {
+            200 => {}
+            404 => return Ok(None),
+            _ => {
+                return Err(ClientError::RequestFailed {
+                    reason: Some(response.content().into()),
+                    key: None,
+                });
+            }
+        }
+
+        let resp: OnOffSchedule = serde_json::from_str(response.content())?;
+        Ok(Some(resp))
+    }
+
+    pub fn set_on_off_schedule(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        schedule: &OnOffSchedule,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let existing = self.get_on_off_schedule(
+            org_id.clone(),
+            project_id.clone(),
+            cluster_id.clone(),
+            signals.clone(),
+        )?;
+        let payload = serde_json::to_string(schedule)?;
+
+        let request = if existing.is_some() {
+            CapellaRequest::ClusterScheduleUpdate {
+                org_id,
+                project_id,
+                cluster_id,
+                payload,
+            }
+        } else {
+            CapellaRequest::ClusterScheduleCreate {
+                org_id,
+                project_id,
+                cluster_id,
+                payload,
+            }
+        };
+        let response = self.capella_request(request, signals)?;
+
+        handle_schedule_response(response)
+    }
+
+    pub fn delete_on_off_schedule(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::ClusterScheduleDelete {
+            org_id,
+            project_id,
+            cluster_id,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        handle_schedule_response(response)
+    }
+
+    // Polls the cluster until it reaches one of the target states, failing if it lands in a
+    // failed state instead or does not get there before the timeout.
+    pub fn wait_for_cluster_state(
+        &self,
+        cluster_name: String,
+        org_id: String,
+        project_id: String,
+        targets: &[&str],
+        timeout: Duration,
+        signals: Signals,
+    ) -> Result<Cluster, ClientError> {
+        poll_until(
+            &format!("cluster {}", cluster_name),
+            timeout,
+            signals.clone(),
+            || {
+                let cluster = self.get_cluster(
+                    cluster_name.clone(),
+                    org_id.clone(),
+                    project_id.clone(),
+                    signals.clone(),
+                )?;
+                let status = state_poll_status(cluster.state(), targets);
+                Ok(status.map(|_| cluster))
+            },
+        )
+    }
+
+    pub fn wait_for_cluster_deleted(
+        &self,
+        cluster_name: String,
+        org_id: String,
+        project_id: String,
+        timeout: Duration,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        poll_until(
+            &format!("cluster {}", cluster_name),
+            timeout,
+            signals.clone(),
+            || match self.get_cluster(
+                cluster_name.clone(),
+                org_id.clone(),
+                project_id.clone(),
+                signals.clone(),
+            ) {
+                Ok(cluster) => Ok(state_poll_status(cluster.state(), &[])),
+                Err(ClientError::CapellaClusterNotFound { .. }) => Ok(PollStatus::Done(())),
+                Err(e) => Err(e),
+            },
+        )
+    }
+
+    pub fn wait_for_columnar_cluster_state(
+        &self,
+        cluster_name: String,
+        org_id: String,
+        project_id: String,
+        targets: &[&str],
+        timeout: Duration,
+        signals: Signals,
+    ) -> Result<ColumnarCluster, ClientError> {
+        poll_until(
+            &format!("columnar cluster {}", cluster_name),
+            timeout,
+            signals.clone(),
+            || {
+                let cluster = self.get_columnar_cluster(
+                    cluster_name.clone(),
+                    org_id.clone(),
+                    project_id.clone(),
+                    signals.clone(),
+                )?;
+                let status = state_poll_status(cluster.state(), targets);
+                Ok(status.map(|_| cluster))
+            },
+        )
+    }
+
     pub fn create_free_tier_cluster(
         &self,
         org_id: String,
@@ -414,166 +654,456 @@ impl CapellaClient {
         handle_cluster_management_response(response)
     }
 
-    pub fn create_credentials(
+    pub fn list_app_services(
         &self,
         org_id: String,
-        project_id: String,
-        cluster_id: String,
-        payload: String,
         signals: Signals,
-    ) -> Result<(), ClientError> {
-        let request = CapellaRequest::CredentialsCreate {
-            org_id,
-            project_id,
-            cluster_id,
-            payload,
-        };
+    ) -> Result<AppServicesResponse, ClientError> {
+        let request = CapellaRequest::AppServiceList { org_id };
         let response = self.capella_request(request, signals)?;
 
-        if response.status() != 201 {
+        if response.status() != 200 {
             return Err(ClientError::RequestFailed {
                 reason: Some(response.content().into()),
                 key: None,
             });
         }
-        Ok(())
+
+        let resp: AppServicesResponse = serde_json::from_str(response.content())?;
+        Ok(resp)
     }
 
-    pub fn list_credentials(
+    // App services are listed per organization, so they are narrowed down to the ones linked to a
+    // cluster in the given project.
+    pub fn list_project_app_services(
+        &self,
+        org_id: String,
+        project_id: String,
+        signals: Signals,
+    ) -> Result<Vec<AppService>, ClientError> {
+        let clusters = self.list_clusters(org_id.clone(), project_id, signals.clone())?;
+        let cluster_ids: Vec<String> = clusters.items().iter().map(|c| c.id()).collect();
+
+        let app_services = self.list_app_services(org_id, signals)?;
+        Ok(app_services
+            .items()
+            .into_iter()
+            .filter(|a| cluster_ids.contains(&a.cluster_id()))
+            .collect())
+    }
+
+    pub fn get_app_service(
+        &self,
+        name: String,
+        org_id: String,
+        project_id: String,
+        signals: Signals,
+    ) -> Result<AppService, ClientError> {
+        self.list_project_app_services(org_id, project_id, signals)?
+            .into_iter()
+            .find(|a| a.name() == name)
+            .ok_or(ClientError::CapellaAppServiceNotFound { name })
+    }
+
+    pub fn create_app_service(
         &self,
         org_id: String,
         project_id: String,
         cluster_id: String,
+        app_service: &AppServiceCreateRequest,
         signals: Signals,
-    ) -> Result<CredentialsResponse, ClientError> {
-        let request = CapellaRequest::CredentialsList {
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::AppServiceCreate {
             org_id,
             project_id,
             cluster_id,
+            payload: serde_json::to_string(app_service)?,
         };
         let response = self.capella_request(request, signals)?;
 
-        if response.status() != 200 {
-            return Err(ClientError::RequestFailed {
+        match response.status() {
+            201 => Ok(()),
+            403 => Err(ClientError::AccessDenied {
+                reason: "Make sure that the API key has the Project Owner role enabled for the target project".to_string()
+            }),
+            _ => Err(ClientError::RequestFailed {
                 reason: Some(response.content().into()),
                 key: None,
-            });
+            }),
         }
-
-        let resp: CredentialsResponse = serde_json::from_str(response.content())?;
-        Ok(resp)
     }
 
-    pub fn drop_credentials(
+    pub fn delete_app_service(
         &self,
         org_id: String,
         project_id: String,
         cluster_id: String,
-        credential_id: String,
+        app_service_id: String,
         signals: Signals,
     ) -> Result<(), ClientError> {
-        let request = CapellaRequest::CredentialsDrop {
+        let request = CapellaRequest::AppServiceDelete {
             org_id,
             project_id,
             cluster_id,
-            credential_id,
+            app_service_id,
         };
         let response = self.capella_request(request, signals)?;
 
-        if response.status() != 204 {
-            return Err(ClientError::RequestFailed {
-                reason: Some(response.content().into()),
-                key: None,
-            });
-        }
-        Ok(())
+        handle_cluster_management_response(response)
     }
 
-    pub fn create_bucket(
+    pub fn turn_app_service_on(
         &self,
         org_id: String,
         project_id: String,
         cluster_id: String,
-        payload: String,
+        app_service_id: String,
         signals: Signals,
     ) -> Result<(), ClientError> {
-        let request = CapellaRequest::BucketCreate {
+        let request = CapellaRequest::AppServiceOn {
             org_id,
             project_id,
             cluster_id,
-            payload,
+            app_service_id,
         };
         let response = self.capella_request(request, signals)?;
 
-        if response.status() != 201 {
-            return Err(ClientError::RequestFailed {
-                reason: Some(response.content().into()),
-                key: None,
-            });
-        }
-        Ok(())
+        handle_cluster_management_response(response)
     }
 
-    pub fn delete_bucket(
+    pub fn turn_app_service_off(
         &self,
         org_id: String,
         project_id: String,
         cluster_id: String,
-        bucket: String,
+        app_service_id: String,
         signals: Signals,
     ) -> Result<(), ClientError> {
-        let request = CapellaRequest::BucketDelete {
+        let request = CapellaRequest::AppServiceOff {
             org_id,
             project_id,
             cluster_id,
-            bucket_id: BASE64_STANDARD.encode(bucket),
+            app_service_id,
         };
         let response = self.capella_request(request, signals)?;
 
-        if response.status() != 204 {
-            return Err(ClientError::RequestFailed {
-                reason: Some(response.content().into()),
-                key: None,
-            });
-        }
-        Ok(())
+        handle_cluster_management_response(response)
     }
 
-    pub fn update_bucket(
+    pub fn wait_for_app_service_state(
+        &self,
+        name: String,
+        org_id: String,
+        project_id: String,
+        targets: &[&str],
+        timeout: Duration,
+        signals: Signals,
+    ) -> Result<AppService, ClientError> {
+        poll_until(
+            &format!("app service {}", name),
+            timeout,
+            signals.clone(),
+            || {
+                let app_service = self.get_app_service(
+                    name.clone(),
+                    org_id.clone(),
+                    project_id.clone(),
+                    signals.clone(),
+                )?;
+                let status = state_poll_status(app_service.state(), targets);
+                Ok(status.map(|_| app_service))
+            },
+        )
+    }
+
+    pub fn wait_for_app_service_deleted(
         &self,
+        name: String,
         org_id: String,
         project_id: String,
-        cluster_id: String,
-        bucket: String,
-        payload: String,
+        timeout: Duration,
         signals: Signals,
     ) -> Result<(), ClientError> {
-        let request = CapellaRequest::BucketUpdate {
+        poll_until(
+            &format!("app service {}", name),
+            timeout,
+            signals.clone(),
+            || match self.get_app_service(
+                name.clone(),
+                org_id.clone(),
+                project_id.clone(),
+                signals.clone(),
+            ) {
+                Ok(app_service) => Ok(state_poll_status(app_service.state(), &[])),
+                Err(ClientError::CapellaAppServiceNotFound { .. }) => Ok(PollStatus::Done(())),
+                Err(e) => Err(e),
+            },
+        )
+    }
+
+    pub fn list_private_endpoints(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        signals: Signals,
+    ) -> Result<PrivateEndpointsResponse, ClientError> {
+        let request = CapellaRequest::PrivateEndpointList {
             org_id,
             project_id,
             cluster_id,
-            bucket_id: BASE64_STANDARD.encode(bucket),
-            payload,
         };
         let response = self.capella_request(request, signals)?;
 
-        if response.status() != 204 {
+        if response.status() != 200 {
             return Err(ClientError::RequestFailed {
                 reason: Some(response.content().into()),
                 key: None,
             });
         }
-        Ok(())
+
+        let resp: PrivateEndpointsResponse = serde_json::from_str(response.content())?;
+        Ok(resp)
     }
 
-    pub fn load_sample_bucket(
+    pub fn list_network_peers(
         &self,
         org_id: String,
         project_id: String,
         cluster_id: String,
-        sample: String,
         signals: Signals,
-    ) -> Result<(), ClientError> {
+    ) -> Result<NetworkPeersResponse, ClientError> {
+        let request = CapellaRequest::NetworkPeerList {
+            org_id,
+            project_id,
+            cluster_id,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        if response.status() != 200 {
+            return Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            });
+        }
+
+        let resp: NetworkPeersResponse = serde_json::from_str(response.content())?;
+        Ok(resp)
+    }
+
+    pub fn create_credentials(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        payload: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::CredentialsCreate {
+            org_id,
+            project_id,
+            cluster_id,
+            payload,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        if response.status() != 201 {
+            return Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn list_credentials(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        signals: Signals,
+    ) -> Result<CredentialsResponse, ClientError> {
+        let request = CapellaRequest::CredentialsList {
+            org_id,
+            project_id,
+            cluster_id,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        if response.status() != 200 {
+            return Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            });
+        }
+
+        let resp: CredentialsResponse = serde_json::from_str(response.content())?;
+        Ok(resp)
+    }
+
+    pub fn update_credentials(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        credential_id: String,
+        payload: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::CredentialsUpdate {
+            org_id,
+            project_id,
+            cluster_id,
+            credential_id,
+            payload,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        if response.status() != 204 {
+            return Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn drop_credentials(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        credential_id: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::CredentialsDrop {
+            org_id,
+            project_id,
+            cluster_id,
+            credential_id,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        if response.status() != 204 {
+            return Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn create_bucket(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        payload: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::BucketCreate {
+            org_id,
+            project_id,
+            cluster_id,
+            payload,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        if response.status() != 201 {
+            return Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn delete_bucket(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        bucket: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::BucketDelete {
+            org_id,
+            project_id,
+            cluster_id,
+            bucket_id: BASE64_STANDARD.encode(bucket),
+        };
+        let response = self.capella_request(request, signals)?;
+
+        if response.status() != 204 {
+            return Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn update_bucket(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        bucket: String,
+        payload: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::BucketUpdate {
+            org_id,
+            project_id,
+            cluster_id,
+            bucket_id: BASE64_STANDARD.encode(bucket),
+            payload,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        if response.status() != 204 {
+            return Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn bucket_exists(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        bucket: String,
+        signals: Signals,
+    ) -> Result<bool, ClientError> {
+        let request = CapellaRequest::BucketGet {
+            org_id,
+            project_id,
+            cluster_id,
+            bucket_id: BASE64_STANDARD.encode(bucket),
+        };
+        let response = self.capella_request(request, signals)?;
+
+        match response.status() {
+            200 => Ok(true),
+            404 => Ok(false),
+            _ => Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            }),
+        }
+    }
+
+    pub fn load_sample_bucket(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        sample: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
         let request = CapellaRequest::BucketLoadSample {
             org_id,
             project_id,
@@ -593,19 +1123,156 @@ impl CapellaClient {
         }
     }
 
+    pub fn list_backups(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        signals: Signals,
+    ) -> Result<BackupsResponse, ClientError> {
+        let request = CapellaRequest::BackupList {
+            org_id,
+            project_id,
+            cluster_id,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        if response.status() != 200 {
+            return Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            });
+        }
+
+        let resp: BackupsResponse = serde_json::from_str(response.content())?;
+        Ok(resp)
+    }
+
+    pub fn create_backup(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        bucket: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::BackupCreate {
+            org_id,
+            project_id,
+            cluster_id,
+            bucket_id: BASE64_STANDARD.encode(bucket),
+        };
+        let response = self.capella_request(request, signals)?;
+
+        handle_backup_response(response)
+    }
+
+    pub fn restore_backup(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        backup_id: String,
+        restore: &BackupRestoreRequest,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::BackupRestore {
+            org_id,
+            project_id,
+            cluster_id,
+            backup_id,
+            payload: serde_json::to_string(restore)?,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        handle_backup_response(response)
+    }
+
+    pub fn get_backup_schedule(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        bucket: String,
+        signals: Signals,
+    ) -> Result<Option<BackupSchedule>, ClientError> {
+        let request = CapellaRequest::BackupScheduleGet {
+            org_id,
+            project_id,
+            cluster_id,
+            bucket_id: BASE64_STANDARD.encode(bucket),
+        };
+        let response = self.capella_request(request, signals)?;
+
+        match response.status() {
+            200 => {}
+            404 => return Ok(None),
+            _ => {
+                return Err(ClientError::RequestFailed {
+                    reason: Some(response.content().into()),
+                    key: None,
+                });
+            }
+        }
+
+        let resp: BackupSchedule = serde_json::from_str(response.content())?;
+        Ok(Some(resp))
+    }
+
+    pub fn set_backup_schedule(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        bucket: String,
+        schedule: &BackupSchedule,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let existing = self.get_backup_schedule(
+            org_id.clone(),
+            project_id.clone(),
+            cluster_id.clone(),
+            bucket.clone(),
+            signals.clone(),
+        )?;
+        let payload = serde_json::to_string(schedule)?;
+        let bucket_id = BASE64_STANDARD.encode(bucket);
+
+        let request = if existing.is_some() {
+            CapellaRequest::BackupScheduleUpdate {
+                org_id,
+                project_id,
+                cluster_id,
+                bucket_id,
+                payload,
+            }
+        } else {
+            CapellaRequest::BackupScheduleCreate {
+                org_id,
+                project_id,
+                cluster_id,
+                bucket_id,
+                payload,
+            }
+        };
+        let response = self.capella_request(request, signals)?;
+
+        handle_backup_response(response)
+    }
+
     pub fn allow_ip_address(
         &self,
         org_id: String,
         project_id: String,
         cluster_id: String,
-        address: String,
+        allowed: &AllowedCidrRequest,
         signals: Signals,
     ) -> Result<(), ClientError> {
         let request = CapellaRequest::AllowIPAddress {
             org_id,
             project_id,
             cluster_id,
-            payload: format!("{{\"cidr\": \"{}\"}}", address.clone()),
+            payload: serde_json::to_string(allowed)?,
         };
         let response = self.capella_request(request, signals)?;
 
@@ -618,6 +1285,56 @@ impl CapellaClient {
         }
     }
 
+    pub fn list_allowed_ip_addresses(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        signals: Signals,
+    ) -> Result<AllowedCidrsResponse, ClientError> {
+        let request = CapellaRequest::AllowedIPAddressList {
+            org_id,
+            project_id,
+            cluster_id,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        if response.status() != 200 {
+            return Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            });
+        }
+
+        let resp: AllowedCidrsResponse = serde_json::from_str(response.content())?;
+        Ok(resp)
+    }
+
+    pub fn drop_allowed_ip_address(
+        &self,
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        allowed_cidr_id: String,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let request = CapellaRequest::AllowedIPAddressDrop {
+            org_id,
+            project_id,
+            cluster_id,
+            allowed_cidr_id,
+        };
+        let response = self.capella_request(request, signals)?;
+
+        match response.status() {
+            204 => Ok(()),
+            _ => Err(ClientError::RequestFailed {
+                reason: Some(response.content().into()),
+                key: None,
+            }),
+        }
+    }
+
     pub fn list_scopes(
         &self,
         org_id: String,
@@ -789,56 +1506,171 @@ pub enum CapellaRequest {
         org_id: String,
         payload: String,
     },
-    AllowIPAddress {
+    AllowIPAddress {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        payload: String,
+    },
+    ProjectDelete {
+        org_id: String,
+        project_id: String,
+    },
+    ProjectList {
+        org_id: String,
+    },
+    ClusterCreate {
+        org_id: String,
+        project_id: String,
+        payload: String,
+    },
+    ClusterDelete {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+    },
+    ClusterGet {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+    },
+    ClusterList {
+        org_id: String,
+        project_id: String,
+    },
+    ClusterUpdate {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        payload: String,
+    },
+    ClusterOn {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        payload: String,
+    },
+    ClusterOff {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+    },
+    ClusterScheduleCreate {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        payload: String,
+    },
+    ClusterScheduleDelete {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+    },
+    ClusterScheduleGet {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+    },
+    ClusterScheduleUpdate {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        payload: String,
+    },
+    FreeTierClusterCreate {
+        org_id: String,
+        project_id: String,
+        payload: String,
+    },
+    ColumnarClusterCreate {
+        org_id: String,
+        project_id: String,
+        payload: String,
+    },
+    ColumnarClusterDelete {
         org_id: String,
         project_id: String,
         cluster_id: String,
-        payload: String,
     },
-    ProjectDelete {
+    ColumnarClusterList {
         org_id: String,
         project_id: String,
     },
-    ProjectList {
+    AllowedIPAddressDrop {
         org_id: String,
+        project_id: String,
+        cluster_id: String,
+        allowed_cidr_id: String,
     },
-    ClusterCreate {
+    AllowedIPAddressList {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+    },
+    AppServiceCreate {
         org_id: String,
         project_id: String,
+        cluster_id: String,
         payload: String,
     },
-    ClusterDelete {
+    AppServiceDelete {
         org_id: String,
         project_id: String,
         cluster_id: String,
+        app_service_id: String,
     },
-    ClusterGet {
+    AppServiceList {
+        org_id: String,
+    },
+    AppServiceOn {
         org_id: String,
         project_id: String,
         cluster_id: String,
+        app_service_id: String,
     },
-    ClusterList {
+    AppServiceOff {
         org_id: String,
         project_id: String,
+        cluster_id: String,
+        app_service_id: String,
     },
-    FreeTierClusterCreate {
+    BackupCreate {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        bucket_id: String,
+    },
+    BackupList {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+    },
+    BackupRestore {
         org_id: String,
         project_id: String,
+        cluster_id: String,
+        backup_id: String,
         payload: String,
     },
-    ColumnarClusterCreate {
+    BackupScheduleCreate {
         org_id: String,
         project_id: String,
+        cluster_id: String,
+        bucket_id: String,
         payload: String,
     },
-    ColumnarClusterDelete {
+    BackupScheduleGet {
         org_id: String,
         project_id: String,
         cluster_id: String,
+        bucket_id: String,
     },
-    ColumnarClusterList {
+    BackupScheduleUpdate {
         org_id: String,
         project_id: String,
+        cluster_id: String,
+        bucket_id: String,
+        payload: String,
     },
     BucketCreate {
         org_id: String,
@@ -936,6 +1768,23 @@ pub enum CapellaRequest {
         cluster_id: String,
         credential_id: String,
     },
+    CredentialsUpdate {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+        credential_id: String,
+        payload: String,
+    },
+    NetworkPeerList {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+    },
+    PrivateEndpointList {
+        org_id: String,
+        project_id: String,
+        cluster_id: String,
+    },
 }
 
 impl CapellaRequest {
@@ -956,6 +1805,27 @@ impl CapellaRequest {
                     org_id, project_id, cluster_id
                 )
             }
+            Self::AllowedIPAddressDrop {
+                org_id,
+                project_id,
+                cluster_id,
+                allowed_cidr_id,
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/allowedcidrs/{}",
+                    org_id, project_id, cluster_id, allowed_cidr_id
+                )
+            }
+            Self::AllowedIPAddressList {
+                org_id,
+                project_id,
+                cluster_id,
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/allowedcidrs",
+                    org_id, project_id, cluster_id
+                )
+            }
             Self::ProjectCreate { org_id, .. } => {
                 format!("/v4/organizations/{}/projects", org_id)
             }
@@ -996,6 +1866,60 @@ impl CapellaRequest {
                     org_id, project_id
                 )
             }
+            Self::ClusterUpdate {
+                org_id,
+                project_id,
+                cluster_id,
+                ..
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}",
+                    org_id, project_id, cluster_id
+                )
+            }
+            Self::ClusterOn {
+                org_id,
+                project_id,
+                cluster_id,
+                ..
+            }
+            | Self::ClusterOff {
+                org_id,
+                project_id,
+                cluster_id,
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/activationState",
+                    org_id, project_id, cluster_id
+                )
+            }
+            Self::ClusterScheduleCreate {
+                org_id,
+                project_id,
+                cluster_id,
+                ..
+            }
+            | Self::ClusterScheduleUpdate {
+                org_id,
+                project_id,
+                cluster_id,
+                ..
+            }
+            | Self::ClusterScheduleDelete {
+                org_id,
+                project_id,
+                cluster_id,
+            }
+            | Self::ClusterScheduleGet {
+                org_id,
+                project_id,
+                cluster_id,
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/onOffSchedule",
+                    org_id, project_id, cluster_id
+                )
+            }
             Self::FreeTierClusterCreate {
                 org_id, project_id, ..
             } => {
@@ -1028,6 +1952,106 @@ impl CapellaRequest {
                     org_id, project_id
                 )
             }
+            Self::AppServiceCreate {
+                org_id,
+                project_id,
+                cluster_id,
+                ..
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/appservices",
+                    org_id, project_id, cluster_id
+                )
+            }
+            Self::AppServiceDelete {
+                org_id,
+                project_id,
+                cluster_id,
+                app_service_id,
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/appservices/{}",
+                    org_id, project_id, cluster_id, app_service_id
+                )
+            }
+            Self::AppServiceList { org_id } => {
+                format!("/v4/organizations/{}/appservices", org_id)
+            }
+            Self::AppServiceOn {
+                org_id,
+                project_id,
+                cluster_id,
+                app_service_id,
+            }
+            | Self::AppServiceOff {
+                org_id,
+                project_id,
+                cluster_id,
+                app_service_id,
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/appservices/{}/activationState",
+                    org_id, project_id, cluster_id, app_service_id
+                )
+            }
+            Self::BackupCreate {
+                org_id,
+                project_id,
+                cluster_id,
+                bucket_id,
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/buckets/{}/backups",
+                    org_id, project_id, cluster_id, bucket_id
+                )
+            }
+            Self::BackupList {
+                org_id,
+                project_id,
+                cluster_id,
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/backups",
+                    org_id, project_id, cluster_id
+                )
+            }
+            Self::BackupRestore {
+                org_id,
+                project_id,
+                cluster_id,
+                backup_id,
+                ..
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/backups/{}/restore",
+                    org_id, project_id, cluster_id, backup_id
+                )
+            }
+            Self::BackupScheduleCreate {
+                org_id,
+                project_id,
+                cluster_id,
+                bucket_id,
+                ..
+            }
+            | Self::BackupScheduleUpdate {
+                org_id,
+                project_id,
+                cluster_id,
+                bucket_id,
+                ..
+            }
+            | Self::BackupScheduleGet {
+                org_id,
+                project_id,
+                cluster_id,
+                bucket_id,
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/buckets/{}/backup/schedules",
+                    org_id, project_id, cluster_id, bucket_id
+                )
+            }
             Self::BucketCreate {
                 org_id,
                 project_id,
@@ -1193,12 +2217,39 @@ impl CapellaRequest {
                 project_id,
                 cluster_id,
                 credential_id,
+            }
+            | Self::CredentialsUpdate {
+                org_id,
+                project_id,
+                cluster_id,
+                credential_id,
+                ..
             } => {
                 format!(
                     "/v4/organizations/{}/projects/{}/clusters/{}/users/{}",
                     org_id, project_id, cluster_id, credential_id
                 )
             }
+            Self::NetworkPeerList {
+                org_id,
+                project_id,
+                cluster_id,
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/networkPeers",
+                    org_id, project_id, cluster_id
+                )
+            }
+            Self::PrivateEndpointList {
+                org_id,
+                project_id,
+                cluster_id,
+            } => {
+                format!(
+                    "/v4/organizations/{}/projects/{}/clusters/{}/privateEndpointService/endpoints",
+                    org_id, project_id, cluster_id
+                )
+            }
         }
     }
 
@@ -1212,10 +2263,28 @@ impl CapellaRequest {
             Self::ClusterDelete { .. } => HttpVerb::Delete,
             Self::ClusterGet { .. } => HttpVerb::Get,
             Self::ClusterList { .. } => HttpVerb::Get,
+            Self::ClusterUpdate { .. } => HttpVerb::Put,
+            Self::ClusterOn { .. } => HttpVerb::Post,
+            Self::ClusterOff { .. } => HttpVerb::Delete,
+            Self::ClusterScheduleCreate { .. } => HttpVerb::Post,
+            Self::ClusterScheduleDelete { .. } => HttpVerb::Delete,
+            Self::ClusterScheduleGet { .. } => HttpVerb::Get,
+            Self::ClusterScheduleUpdate { .. } => HttpVerb::Put,
             Self::FreeTierClusterCreate { .. } => HttpVerb::Post,
             Self::ColumnarClusterCreate { .. } => HttpVerb::Post,
             Self::ColumnarClusterDelete { .. } => HttpVerb::Delete,
             Self::ColumnarClusterList { .. } => HttpVerb::Get,
+            Self::AppServiceCreate { .. } => HttpVerb::Post,
+            Self::AppServiceDelete { .. } => HttpVerb::Delete,
+            Self::AppServiceList { .. } => HttpVerb::Get,
+            Self::AppServiceOn { .. } => HttpVerb::Post,
+            Self::AppServiceOff { .. } => HttpVerb::Delete,
+            Self::BackupCreate { .. } => HttpVerb::Post,
+            Self::BackupList { .. } => HttpVerb::Get,
+            Self::BackupRestore { .. } => HttpVerb::Post,
+            Self::BackupScheduleCreate { .. } => HttpVerb::Post,
+            Self::BackupScheduleGet { .. } => HttpVerb::Get,
+            Self::BackupScheduleUpdate { .. } => HttpVerb::Put,
             Self::BucketCreate { .. } => HttpVerb::Post,
             Self::BucketDelete { .. } => HttpVerb::Delete,
             Self::BucketGet { .. } => HttpVerb::Get,
@@ -1223,6 +2292,8 @@ impl CapellaRequest {
             Self::BucketList { .. } => HttpVerb::Get,
             Self::BucketUpdate { .. } => HttpVerb::Put,
             Self::AllowIPAddress { .. } => HttpVerb::Post,
+            Self::AllowedIPAddressDrop { .. } => HttpVerb::Delete,
+            Self::AllowedIPAddressList { .. } => HttpVerb::Get,
             Self::ScopeCreate { .. } => HttpVerb::Post,
             Self::ScopeDelete { .. } => HttpVerb::Delete,
             Self::ScopeList { .. } => HttpVerb::Get,
@@ -1232,6 +2303,9 @@ impl CapellaRequest {
             Self::CredentialsCreate { .. } => HttpVerb::Post,
             Self::CredentialsList { .. } => HttpVerb::Get,
             Self::CredentialsDrop { .. } => HttpVerb::Delete,
+            Self::CredentialsUpdate { .. } => HttpVerb::Put,
+            Self::NetworkPeerList { .. } => HttpVerb::Get,
+            Self::PrivateEndpointList { .. } => HttpVerb::Get,
         }
     }
 
@@ -1239,8 +2313,16 @@ impl CapellaRequest {
         match self {
             Self::ProjectCreate { payload, .. } => Some(payload.as_bytes().into()),
             Self::ClusterCreate { payload, .. } => Some(payload.as_bytes().into()),
+            Self::ClusterUpdate { payload, .. } => Some(payload.as_bytes().into()),
+            Self::ClusterOn { payload, .. } => Some(payload.as_bytes().into()),
+            Self::ClusterScheduleCreate { payload, .. } => Some(payload.as_bytes().into()),
+            Self::ClusterScheduleUpdate { payload, .. } => Some(payload.as_bytes().into()),
             Self::FreeTierClusterCreate { payload, .. } => Some(payload.as_bytes().into()),
             Self::ColumnarClusterCreate { payload, .. } => Some(payload.as_bytes().into()),
+            Self::AppServiceCreate { payload, .. } => Some(payload.as_bytes().into()),
+            Self::BackupRestore { payload, .. } => Some(payload.as_bytes().into()),
+            Self::BackupScheduleCreate { payload, .. } => Some(payload.as_bytes().into()),
+            Self::BackupScheduleUpdate { payload, .. } => Some(payload.as_bytes().into()),
             Self::BucketCreate { payload, .. } => Some(payload.as_bytes().into()),
             Self::BucketLoadSample { payload, .. } => Some(payload.as_bytes().into()),
             Self::BucketUpdate { payload, .. } => Some(payload.as_bytes().into()),
@@ -1248,6 +2330,7 @@ impl CapellaRequest {
             Self::ScopeCreate { payload, .. } => Some(payload.as_bytes().into()),
             Self::CollectionCreate { payload, .. } => Some(payload.as_bytes().into()),
             Self::CredentialsCreate { payload, .. } => Some(payload.as_bytes().into()),
+            Self::CredentialsUpdate { payload, .. } => Some(payload.as_bytes().into()),
             _ => None,
         }
     }
@@ -1268,6 +2351,142 @@ fn handle_cluster_management_response(response: HttpResponse) -> Result<(), Clie
     }
 }
 
+// Finds the public IP address of this machine by asking an external service, which is expected to
+// respond with nothing but the address.
+pub fn lookup_public_ip(
+    endpoint: &str,
+    timeout: Duration,
+    proxy: Option<ProxyConfig>,
+    signals: Signals,
+) -> Result<IpAddr, ClientError> {
+    let signals_fut = CtrlcFuture::new(signals);
+    // The lookup goes through the same proxy as the organization's requests.
+    let client = match proxy {
+        Some(proxy) => Client::builder()
+            .proxy(proxy.reqwest_proxy())
+            .build()
+            .map_err(ClientError::from)?,
+        None => Client::new(),
+    };
+    let res_builder = client.get(endpoint).timeout(timeout);
+
+    debug!("Looking up public IP address using {}", endpoint);
+
+    let content = HTTP_RUNTIME.block_on(async {
+        select! {
+            result = res_builder.send() => {
+                let response = result.map_err(ClientError::from)?;
+                response.text().await.map_err(ClientError::from)
+            },
+            () = signals_fut => Err(ClientError::Cancelled{key: None}),
+        }
+    })?;
+
+    content
+        .trim()
+        .parse()
+        .map_err(|_| ClientError::RequestFailed {
+            reason: Some(format!(
+                "{} did not respond with an IP address: {}",
+                endpoint, content
+            )),
+            key: None,
+        })
+}
+
+pub enum PollStatus<T> {
+    Done(T),
+    Pending(String),
+    Failed(String),
+}
+
+impl<T> PollStatus<T> {
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> PollStatus<U> {
+        match self {
+            Self::Done(t) => PollStatus::Done(f(t)),
+            Self::Pending(state) => PollStatus::Pending(state),
+            Self::Failed(state) => PollStatus::Failed(state),
+        }
+    }
+}
+
+// Calls poll until the operation it is watching is done, logging each new pending state along the
+// way. Failed states are returned as errors, as is running out of time or being interrupted.
+pub fn poll_until<T>(
+    description: &str,
+    timeout: Duration,
+    signals: Signals,
+    mut poll: impl FnMut() -> Result<PollStatus<T>, ClientError>,
+) -> Result<T, ClientError> {
+    let deadline = Instant::now().add(timeout);
+    let mut last_state = String::new();
+    loop {
+        match poll()? {
+            PollStatus::Done(t) => return Ok(t),
+            PollStatus::Failed(state) => {
+                return Err(ClientError::RequestFailed {
+                    reason: Some(format!("{} is {}", description, state)),
+                    key: None,
+                });
+            }
+            PollStatus::Pending(state) => {
+                if state != last_state {
+                    info!("{} is {}", description, state);
+                    last_state = state;
+                }
+            }
+        }
+
+        let next_poll = Instant::now().add(POLL_INTERVAL);
+        while Instant::now() < next_poll {
+            if signals.interrupted() {
+                return Err(ClientError::Cancelled { key: None });
+            }
+            if Instant::now() >= deadline {
+                return Err(ClientError::Timeout { key: None });
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+// Capella reports failures through states such as deploymentFailed or turnOnFailed.
+fn state_poll_status(state: String, targets: &[&str]) -> PollStatus<()> {
+    if targets.contains(&state.as_str()) {
+        PollStatus::Done(())
+    } else if state.ends_with("Failed") {
+        PollStatus::Failed(state)
+    } else {
+        PollStatus::Pending(state)
+    }
+}
+
+fn handle_backup_response(response: HttpResponse) -> Result<(), ClientError> {
+    match response.status() {
+        202 | 204 => Ok(()),
+        403 => Err(ClientError::AccessDenied {
+            reason: "Make sure that the API key has the Project Manager role enabled for the target project".to_string()
+        }),
+        _ => Err(ClientError::RequestFailed {
+            reason: Some(response.content().into()),
+            key: None,
+        }),
+    }
+}
+
+fn handle_schedule_response(response: HttpResponse) -> Result<(), ClientError> {
+    match response.status() {
+        204 => Ok(()),
+        403 => Err(ClientError::AccessDenied {
+            reason: "Make sure that the API key has the Project Owner role enabled for the target project".to_string()
+        }),
+        _ => Err(ClientError::RequestFailed {
+            reason: Some(response.content().into()),
+            key: None,
+        }),
+    }
+}
+
 pub struct CollectionNamespace {
     org_id: String,
     project_id: String,
@@ -206,6 +206,10 @@ pub(crate) struct ClusterCreateRequest {
 }
 
 impl ClusterCreateRequest {
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
     pub fn new(
         name: String,
         description: String,
@@ -249,6 +253,50 @@ impl ClusterCreateRequest {
     }
 }
 
+// The parts of a cluster which can be changed once it has been created.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ClusterUpdateRequest {
+    name: String,
+    description: String,
+    support: Support,
+    service_groups: Vec<ServiceGroup>,
+}
+
+impl From<&Cluster> for ClusterUpdateRequest {
+    fn from(cluster: &Cluster) -> Self {
+        Self {
+            name: cluster.name.clone(),
+            description: cluster.description.clone(),
+            support: cluster.support.clone(),
+            service_groups: cluster.service_groups.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct OnOffSchedule {
+    timezone: String,
+    days: Vec<OnOffScheduleDay>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct OnOffScheduleDay {
+    state: String,
+    day: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<OnOffScheduleTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<OnOffScheduleTime>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct OnOffScheduleTime {
+    hour: u8,
+    #[serde(default)]
+    minute: u8,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct Support {
     plan: String,
@@ -412,6 +460,10 @@ pub(crate) struct ColumnarClusterCreateRequest {
 }
 
 impl ColumnarClusterCreateRequest {
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
     pub fn new(name: String, num_of_nodes: i32) -> Self {
         Self {
             name,
@@ -536,6 +588,28 @@ pub(crate) struct Access {
 }
 
 impl Access {
+    pub fn new(read: bool, write: bool, bucket: Option<String>, scopes: Vec<String>) -> Self {
+        let mut privileges = vec![];
+
+        if read {
+            privileges.push("read".to_string())
+        }
+
+        if write {
+            privileges.push("write".to_string())
+        }
+
+        Self {
+            privileges,
+            resources: Resources {
+                buckets: vec![Bucket {
+                    name: bucket.unwrap_or("*".to_string()),
+                    scopes: Some(scopes.iter().map(|s| Scope { name: s.into() }).collect()),
+                }],
+            },
+        }
+    }
+
     pub fn privileges(&self) -> Vec<String> {
         self.privileges.clone()
     }
@@ -576,32 +650,27 @@ impl CredentialsCreateRequest {
         bucket: Option<String>,
         scopes: Vec<String>,
     ) -> Self {
-        let mut privileges = vec![];
-
-        if read {
-            privileges.push("read".to_string())
-        }
-
-        if write {
-            privileges.push("write".to_string())
-        }
-
         Self {
             name,
             password,
-            access: vec![Access {
-                privileges,
-                resources: Resources {
-                    buckets: vec![Bucket {
-                        name: bucket.unwrap_or("*".to_string()),
-                        scopes: Some(scopes.iter().map(|s| Scope { name: s.into() }).collect()),
-                    }],
-                },
-            }],
+            access: vec![Access::new(read, write, bucket, scopes)],
         }
     }
 }
 
+#[derive(Debug, Serialize)]
+pub(crate) struct CredentialsUpdateRequest {
+    access: Vec<Access>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+}
+
+impl CredentialsUpdateRequest {
+    pub fn new(access: Vec<Access>, password: Option<String>) -> Self {
+        Self { access, password }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub(crate) struct CredentialsResponse {
     data: Vec<Credential>,
@@ -656,3 +725,386 @@ impl Scope {
         self.name.clone()
     }
 }
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct BackupsResponse {
+    data: Vec<Backup>,
+}
+
+impl BackupsResponse {
+    pub fn data(&self) -> &Vec<Backup> {
+        &self.data
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Backup {
+    id: String,
+    bucket: String,
+    status: String,
+    method: String,
+    #[serde(default)]
+    source: String,
+    date: String,
+    #[serde(default)]
+    restore_before: String,
+    bucket_stats: Option<BackupBucketStats>,
+}
+
+impl Backup {
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+    pub fn bucket(&self) -> String {
+        self.bucket.clone()
+    }
+    pub fn status(&self) -> String {
+        self.status.clone()
+    }
+    pub fn method(&self) -> String {
+        self.method.clone()
+    }
+    pub fn source(&self) -> String {
+        self.source.clone()
+    }
+    pub fn date(&self) -> String {
+        self.date.clone()
+    }
+    pub fn restore_before(&self) -> String {
+        self.restore_before.clone()
+    }
+    pub fn items(&self) -> i64 {
+        self.bucket_stats.as_ref().map(|s| s.items).unwrap_or(0)
+    }
+    pub fn size_in_mib(&self) -> i64 {
+        self.bucket_stats
+            .as_ref()
+            .map(|s| s.size_in_mib)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackupBucketStats {
+    #[serde(rename = "sizeInMiB")]
+    size_in_mib: i64,
+    items: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackupRestoreRequest {
+    #[serde(rename = "sourceClusterID")]
+    source_cluster_id: String,
+    #[serde(rename = "targetClusterID")]
+    target_cluster_id: String,
+    services: Vec<String>,
+    force_updates: bool,
+    auto_remove_collections: bool,
+}
+
+impl BackupRestoreRequest {
+    pub fn new(
+        source_cluster_id: String,
+        target_cluster_id: String,
+        services: Vec<String>,
+        force_updates: bool,
+        auto_remove_collections: bool,
+    ) -> Self {
+        Self {
+            source_cluster_id,
+            target_cluster_id,
+            services,
+            force_updates,
+            auto_remove_collections,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackupSchedule {
+    #[serde(rename = "type")]
+    schedule_type: String,
+    weekly_schedule: WeeklyBackupSchedule,
+}
+
+impl BackupSchedule {
+    pub fn weekly(
+        day_of_week: String,
+        start_at: i64,
+        incremental_every: i64,
+        retention_time: String,
+        cost_optimized_retention: bool,
+    ) -> Self {
+        Self {
+            schedule_type: "weekly".to_string(),
+            weekly_schedule: WeeklyBackupSchedule {
+                day_of_week,
+                start_at,
+                incremental_every,
+                retention_time,
+                cost_optimized_retention,
+            },
+        }
+    }
+    pub fn day_of_week(&self) -> String {
+        self.weekly_schedule.day_of_week.clone()
+    }
+    pub fn start_at(&self) -> i64 {
+        self.weekly_schedule.start_at
+    }
+    pub fn incremental_every(&self) -> i64 {
+        self.weekly_schedule.incremental_every
+    }
+    pub fn retention_time(&self) -> String {
+        self.weekly_schedule.retention_time.clone()
+    }
+    pub fn cost_optimized_retention(&self) -> bool {
+        self.weekly_schedule.cost_optimized_retention
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WeeklyBackupSchedule {
+    day_of_week: String,
+    start_at: i64,
+    incremental_every: i64,
+    retention_time: String,
+    cost_optimized_retention: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AllowedCidrRequest {
+    cidr: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: Option<String>,
+}
+
+impl AllowedCidrRequest {
+    pub fn new(cidr: String, comment: Option<String>, expires_at: Option<String>) -> Self {
+        Self {
+            cidr,
+            comment,
+            expires_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AllowedCidrsResponse {
+    data: Vec<AllowedCidr>,
+}
+
+impl AllowedCidrsResponse {
+    pub fn data(&self) -> &Vec<AllowedCidr> {
+        &self.data
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AllowedCidr {
+    id: String,
+    cidr: String,
+    #[serde(default)]
+    comment: String,
+    expires_at: Option<String>,
+    #[serde(default)]
+    status: String,
+    #[serde(rename = "type", default)]
+    cidr_type: String,
+}
+
+impl AllowedCidr {
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+    pub fn cidr(&self) -> String {
+        self.cidr.clone()
+    }
+    pub fn comment(&self) -> String {
+        self.comment.clone()
+    }
+    pub fn expires_at(&self) -> Option<String> {
+        self.expires_at.clone()
+    }
+    pub fn status(&self) -> String {
+        self.status.clone()
+    }
+    pub fn cidr_type(&self) -> String {
+        self.cidr_type.clone()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppServiceCreateRequest {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nodes: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compute: Option<Compute>,
+}
+
+impl AppServiceCreateRequest {
+    pub fn new(
+        name: String,
+        description: Option<String>,
+        nodes: Option<i32>,
+        compute: Option<(i32, i32)>,
+    ) -> Self {
+        Self {
+            name,
+            description,
+            nodes,
+            compute: compute.map(|(cpu, ram)| Compute { cpu, ram }),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AppServicesResponse {
+    data: Vec<AppService>,
+}
+
+impl AppServicesResponse {
+    pub fn items(&self) -> Vec<AppService> {
+        self.data.clone()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppService {
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    cloud_provider: String,
+    nodes: i64,
+    compute: Compute,
+    cluster_id: String,
+    current_state: String,
+    #[serde(default)]
+    version: String,
+}
+
+impl AppService {
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+    pub fn description(&self) -> String {
+        self.description.clone()
+    }
+    pub fn cloud_provider(&self) -> String {
+        self.cloud_provider.clone()
+    }
+    pub fn nodes(&self) -> i64 {
+        self.nodes
+    }
+    pub fn compute(&self) -> Compute {
+        self.compute.clone()
+    }
+    pub fn cluster_id(&self) -> String {
+        self.cluster_id.clone()
+    }
+    pub fn state(&self) -> String {
+        self.current_state.clone()
+    }
+    pub fn version(&self) -> String {
+        self.version.clone()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PrivateEndpointsResponse {
+    #[serde(default)]
+    endpoints: Vec<PrivateEndpoint>,
+}
+
+impl PrivateEndpointsResponse {
+    pub fn endpoints(&self) -> &Vec<PrivateEndpoint> {
+        &self.endpoints
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PrivateEndpoint {
+    id: String,
+    status: String,
+}
+
+impl PrivateEndpoint {
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+    pub fn status(&self) -> String {
+        self.status.clone()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct NetworkPeersResponse {
+    data: Vec<NetworkPeer>,
+}
+
+impl NetworkPeersResponse {
+    pub fn data(&self) -> &Vec<NetworkPeer> {
+        &self.data
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NetworkPeer {
+    id: String,
+    name: String,
+    // Keyed by the cloud provider, e.g. {"aws": {"vpcId": ...}}, with fields that differ per provider.
+    #[serde(default)]
+    provider_config: serde_json::Value,
+    status: NetworkPeerStatus,
+}
+
+impl NetworkPeer {
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+    pub fn provider(&self) -> String {
+        self.provider_config
+            .as_object()
+            .and_then(|config| config.keys().next().cloned())
+            .unwrap_or_default()
+    }
+    pub fn provider_config(&self) -> &serde_json::Value {
+        &self.provider_config
+    }
+    pub fn state(&self) -> String {
+        self.status.state.clone()
+    }
+    pub fn reasoning(&self) -> String {
+        self.status.reasoning.clone()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct NetworkPeerStatus {
+    state: String,
+    #[serde(default)]
+    reasoning: String,
+}
@@ -1,4 +1,5 @@
 use crate::client::protocol::{KvResponse, Status};
+use bytes::Bytes;
 use serde::Deserialize;
 use std::fmt;
 use std::fmt::Debug;
@@ -62,6 +63,12 @@ pub enum ClientError {
     KeyAlreadyExists {
         key: String,
     },
+    // The node no longer owns the partition of the key, the body of the response may contain a
+    // newer bucket config.
+    NotMyVbucket {
+        key: Option<String>,
+        config: Option<Bytes>,
+    },
     AccessError {
         reason: Option<String>,
     },
@@ -77,6 +84,9 @@ pub enum ClientError {
     CapellaClusterNotFound {
         name: String,
     },
+    CapellaAppServiceNotFound {
+        name: String,
+    },
     RequestFailed {
         reason: Option<String>,
         key: Option<String>,
@@ -85,6 +95,10 @@ pub enum ClientError {
         reason: String,
         address: String,
     },
+    HttpCouldNotConnect {
+        reason: String,
+        address: String,
+    },
     PathNotFound {
         key: String,
         path: String,
@@ -99,6 +113,10 @@ pub enum ClientError {
     AccessDenied {
         reason: String,
     },
+    SecretUnavailable {
+        from: String,
+        reason: String,
+    },
 }
 
 impl ClientError {
@@ -107,6 +125,7 @@ impl ClientError {
             ClientError::CollectionUnknownDuringRequest { key, .. } => Some(key.clone()),
             ClientError::KeyNotFound { key } => Some(key.clone()),
             ClientError::KeyAlreadyExists { key } => Some(key.clone()),
+            ClientError::NotMyVbucket { key, .. } => key.clone(),
             ClientError::Timeout { key, .. } => key.clone(),
             ClientError::Cancelled { key } => key.clone(),
             ClientError::RequestFailed { key, .. } => key.clone(),
@@ -146,11 +165,13 @@ impl ClientError {
             Self::ScopeNotFound { .. } => "Scope unknown".to_string(),
             Self::KeyNotFound { .. } => "Key not found".to_string(),
             Self::KeyAlreadyExists { .. } => "Key already exists".to_string(),
+            Self::NotMyVbucket { .. } => "Not my vbucket".to_string(),
             Self::AccessError { .. } => "Access error".to_string(),
             Self::AuthError { .. } => "Authentication error".to_string(),
             Self::Timeout { .. } => "Timeout".to_string(),
             Self::Cancelled { .. } => "Request cancelled".to_string(),
             Self::CapellaClusterNotFound { .. } => "Cluster not found".to_string(),
+            Self::CapellaAppServiceNotFound { .. } => "App service not found".to_string(),
             Self::RequestFailed { reason, .. } => {
                 let msg = "Request failed";
                 if let Some(r) = reason {
@@ -160,11 +181,13 @@ impl ClientError {
                 }
             }
             Self::KVCouldNotConnect { .. } => "Could not establish kv connection".to_string(),
+            Self::HttpCouldNotConnect { .. } => "Could not establish http connection".to_string(),
             Self::PathNotFound { .. } => "Path not found".to_string(),
             Self::InvalidSample { .. } => "Invalid sample bucket".to_string(),
             Self::SampleAlreadyLoaded { .. } => "Sample bucket already loaded".to_string(),
             Self::RequestUnauthorized {} => "Request unauthorized".to_string(),
             Self::AccessDenied { .. } => "Access Denied".to_string(),
+            Self::SecretUnavailable { .. } => "Secret unavailable".to_string(),
         }
     }
 
@@ -189,6 +212,10 @@ impl ClientError {
             },
             Self::KeyNotFound { key } => format!("Key {} was not found, does it exist in the specified collection?", key),
             Self::KeyAlreadyExists { key } => format!("Key {} already exists, is the correct collection being used?", key),
+            Self::NotMyVbucket { key, .. } => match key {
+                Some(k) => format!("The node contacted for key {} does not own its partition, is the cluster rebalancing?", k),
+                None => "The node contacted does not own the partition, is the cluster rebalancing?".to_string(),
+            },
             Self::AccessError { reason } => {
                 if let Some(r) = reason {
                     r.to_string()
@@ -212,6 +239,7 @@ impl ClientError {
                 None =>  "Request was cancelled".to_string(),
             }
             Self::CapellaClusterNotFound { name } => format!("Cluster {} was not found within the Capella organisation", name),
+            Self::CapellaAppServiceNotFound { name } => format!("App service {} was not found within the Capella project", name),
             Self::RequestFailed { reason, .. } => match reason.as_ref() {
                 Some(re) => re.to_string(),
                 None => "Request failed for an unspecified reason".to_string(),
@@ -219,6 +247,9 @@ impl ClientError {
             Self::KVCouldNotConnect { reason, address } => {
                 format!("could not connect to {}: {}", address, reason)
             }
+            Self::HttpCouldNotConnect { reason, address } => {
+                format!("could not connect to {}: {}", address, reason)
+            }
             Self::PathNotFound { key, path } => {
                 format!("Path {} was not found in doc with key {}", path, key)
             }
@@ -234,6 +265,9 @@ impl ClientError {
             Self::AccessDenied {reason} => {
                 reason.to_string()
             }
+            Self::SecretUnavailable { from, reason } => {
+                format!("Could not read the secret from {}: {}", from, reason)
+            }
         }
     }
 
@@ -1,4 +1,5 @@
 use crate::cli::{api_base_unsupported, generic_error, llm_api_key_missing};
+use crate::client::{ChatMessage, ChatRole};
 use bytes::Bytes;
 use log::info;
 use nu_protocol::ShellError;
@@ -135,6 +136,7 @@ impl GeminiClient {
         &self,
         question: String,
         context: Vec<String>,
+        history: &[ChatMessage],
         model: String,
     ) -> Result<String, ShellError> {
         let url = format!(
@@ -142,25 +144,7 @@ impl GeminiClient {
             model, self.api_key
         );
 
-        let question_with_ctx = if !context.is_empty() {
-            format!(
-                "Please answer this question: \\\"{}\\\". Using the following context: \\\"{}\\\"",
-                question,
-                context.join(" ")
-            )
-        } else {
-            question
-        };
-
-        let ask_request: AskRequest = AskRequest {
-            contents: vec![Parts {
-                parts: vec![Text {
-                    text: question_with_ctx.clone(),
-                }],
-            }],
-        };
-
-        let res = execute_request(url, ask_request).await?;
+        let res = execute_request(url, ask_request(question, context, history)).await?;
 
         let bytes = read_response(res).await?;
 
@@ -173,6 +157,113 @@ impl GeminiClient {
 
         Ok(ans.candidates[0].content.parts[0].text.clone())
     }
+
+    pub async fn ask_stream(
+        &self,
+        question: String,
+        context: Vec<String>,
+        history: &[ChatMessage],
+        model: String,
+        mut on_chunk: impl FnMut(String),
+    ) -> Result<String, ShellError> {
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/models/{}:streamGenerateContent?alt=sse&key={}",
+            model, self.api_key
+        );
+
+        let mut res = execute_request(url, ask_request(question, context, history)).await?;
+        if res.status().as_u16() != 200 {
+            read_response(res).await?;
+            return Err(generic_error(
+                "unexpected response from Gemini API",
+                None,
+                None,
+            ));
+        }
+
+        // The response is a stream of server sent events, each holding a partial AskResponse
+        let mut answer = String::new();
+        // Chunks can split multibyte characters, so only whole lines are decoded.
+        let mut buffer: Vec<u8> = Vec::new();
+        loop {
+            let chunk = match res.chunk().await {
+                Ok(Some(c)) => c,
+                Ok(None) => break,
+                Err(e) => {
+                    return Err(generic_error(
+                        format!("could not read response body: {}", e),
+                        None,
+                        None,
+                    ));
+                }
+            };
+            buffer.extend_from_slice(&chunk);
+
+            while let Some(idx) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=idx).collect();
+                let line = String::from_utf8_lossy(&line);
+                let data = match line.trim().strip_prefix("data:") {
+                    Some(d) => d.trim().to_string(),
+                    None => continue,
+                };
+
+                let ans: AskResponse = match serde_json::from_str(&data) {
+                    Ok(a) => a,
+                    Err(e) => {
+                        return Err(failed_to_parse_response_error(e));
+                    }
+                };
+
+                for candidate in ans.candidates {
+                    for part in candidate.content.parts {
+                        answer.push_str(&part.text);
+                        on_chunk(part.text);
+                    }
+                }
+            }
+        }
+
+        Ok(answer)
+    }
+}
+
+fn ask_request(question: String, context: Vec<String>, history: &[ChatMessage]) -> AskRequest {
+    let question_with_ctx = if !context.is_empty() {
+        format!(
+            "Please answer this question: \\\"{}\\\". Using the following context: \\\"{}\\\"",
+            question,
+            context.join(" ")
+        )
+    } else {
+        question
+    };
+
+    let mut contents: Vec<Parts> = history
+        .iter()
+        .map(|m| Parts {
+            role: Some(
+                match m.role() {
+                    ChatRole::User => "user",
+                    ChatRole::Assistant => "model",
+                }
+                .to_string(),
+            ),
+            parts: vec![Text { text: m.content() }],
+        })
+        .collect();
+
+    contents.push(Parts {
+        role: if history.is_empty() {
+            None
+        } else {
+            Some("user".to_string())
+        },
+        parts: vec![Text {
+            text: question_with_ctx,
+        }],
+    });
+
+    AskRequest { contents }
 }
 
 fn error_message(bytes: bytes::Bytes) -> String {
@@ -253,6 +344,8 @@ struct EmbeddingBatchRequest {
 
 #[derive(Serialize, Debug)]
 struct Parts {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
     parts: Vec<Text>,
 }
 
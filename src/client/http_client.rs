@@ -1,18 +1,23 @@
 use crate::client::error::{ClientError, ConfigurationLoadFailedReason};
-use crate::client::http_handler::{read_stream, HTTPHandler, HttpStreamResponse, HttpVerb};
-use crate::client::kv_client::NodeExtConfig;
+use crate::client::http_handler::{
+    read_stream, HTTPHandler, HttpStreamResponse, HttpVerb, ResultStream, HTTP_RUNTIME,
+};
+use crate::client::kv_client::{use_external_network, NodeExtConfig};
+use crate::client::proxy::ProxyConfig;
+use crate::client::timings::{OperationTimings, SlowOperationThreshold};
+use crate::connection_string::Network;
 use crate::RustTlsConfig;
 use log::{debug, trace};
 use nu_protocol::Signals;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use rand::Rng;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde_json::json;
 use std::fmt::{Debug, Display, Formatter};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use std::{collections::HashMap, ops::Sub};
-use tokio::runtime::Runtime;
 use tokio::time::Instant;
 
 const CLUSTER_CONFIG_URI: &str = "/pools/default/nodeServices";
@@ -20,7 +25,11 @@ const CLUSTER_CONFIG_URI: &str = "/pools/default/nodeServices";
 pub struct HTTPClient {
     seeds: Vec<String>,
     tls_enabled: bool,
+    network: Network,
     http_client: HTTPHandler,
+    // Query and search requests are spread across the nodes running the service in turn.
+    next_seed: AtomicUsize,
+    slow_operation_threshold: SlowOperationThreshold,
 }
 
 impl HTTPClient {
@@ -29,12 +38,18 @@ impl HTTPClient {
         username: String,
         password: String,
         tls_config: Option<RustTlsConfig>,
+        network: Network,
+        proxy: Option<ProxyConfig>,
+        slow_operation_threshold: SlowOperationThreshold,
     ) -> Self {
         let tls_enabled = tls_config.is_some();
         Self {
             seeds,
-            http_client: HTTPHandler::new(username, password, tls_config),
+            http_client: HTTPHandler::new(username, password, tls_config, proxy),
             tls_enabled,
+            network,
+            next_seed: AtomicUsize::new(0),
+            slow_operation_threshold,
         }
     }
 
@@ -178,8 +193,7 @@ impl HTTPClient {
         deadline: Instant,
         signals: Signals,
     ) -> Result<Vec<PingResponse>, ClientError> {
-        let rt = Runtime::new().unwrap();
-        rt.block_on(async {
+        HTTP_RUNTIME.block_on(async {
             let config: ClusterConfig = HTTPClient::get_config(
                 &self.seeds,
                 self.tls_enabled,
@@ -191,7 +205,7 @@ impl HTTPClient {
             .await?;
 
             let mut results: Vec<PingResponse> = Vec::new();
-            for seed in config.search_seeds(self.tls_enabled) {
+            for seed in config.search_seeds(self.tls_enabled, self.network) {
                 let uri = format!("{}:{}/api/ping", seed.hostname(), seed.port());
                 let address = format!("{}:{}", seed.hostname(), seed.port());
                 results.push(
@@ -205,7 +219,7 @@ impl HTTPClient {
                     .await?,
                 );
             }
-            for seed in config.query_seeds(self.tls_enabled) {
+            for seed in config.query_seeds(self.tls_enabled, self.network) {
                 let uri = format!("{}:{}/admin/ping", seed.hostname(), seed.port());
                 let address = format!("{}:{}", seed.hostname(), seed.port());
                 results.push(
@@ -213,7 +227,7 @@ impl HTTPClient {
                         .await?,
                 );
             }
-            for seed in config.analytics_seeds(self.tls_enabled) {
+            for seed in config.analytics_seeds(self.tls_enabled, self.network) {
                 let uri = format!("{}:{}/admin/ping", seed.hostname(), seed.port());
                 let address = format!("{}:{}", seed.hostname(), seed.port());
                 results.push(
@@ -227,7 +241,7 @@ impl HTTPClient {
                     .await?,
                 );
             }
-            for seed in config.view_seeds(self.tls_enabled) {
+            for seed in config.view_seeds(self.tls_enabled, self.network) {
                 let uri = format!("{}:{}/", seed.hostname(), seed.port());
                 let address = format!("{}:{}", seed.hostname(), seed.port());
                 results.push(
@@ -246,7 +260,8 @@ impl HTTPClient {
         deadline: Instant,
         signals: Signals,
     ) -> Result<HttpStreamResponse, ClientError> {
-        let rt = Arc::new(Runtime::new().unwrap());
+        let rt = HTTP_RUNTIME.clone();
+        let started = Instant::now();
         rt.clone().block_on(async {
             let config: ClusterConfig = HTTPClient::get_config(
                 &self.seeds,
@@ -259,10 +274,11 @@ impl HTTPClient {
             .await?;
 
             let path = request.path();
-            if let Some(seed) = config.random_management_seed(self.tls_enabled) {
+            if let Some(seed) = config.random_management_seed(self.tls_enabled, self.network) {
                 let uri = format!("{}:{}{}", seed.hostname(), seed.port(), &path);
-                let (stream, status) = match request.verb() {
-                    HttpVerb::Get => self.http_client.http_get(&uri, deadline, signals).await?,
+                let dispatched = Instant::now();
+                let result = match request.verb() {
+                    HttpVerb::Get => self.http_client.http_get(&uri, deadline, signals).await,
                     HttpVerb::Post => {
                         self.http_client
                             .http_post(
@@ -272,13 +288,9 @@ impl HTTPClient {
                                 deadline,
                                 signals,
                             )
-                            .await?
-                    }
-                    HttpVerb::Delete => {
-                        self.http_client
-                            .http_delete(&uri, deadline, signals)
-                            .await?
+                            .await
                     }
+                    HttpVerb::Delete => self.http_client.http_delete(&uri, deadline, signals).await,
                     HttpVerb::Put => {
                         self.http_client
                             .http_put(
@@ -288,10 +300,25 @@ impl HTTPClient {
                                 deadline,
                                 signals,
                             )
-                            .await?
+                            .await
                     }
                 };
-                return Ok(HttpStreamResponse::new(stream, status, seed, rt));
+                let timings = OperationTimings::new(
+                    "management",
+                    seed.to_string(),
+                    dispatched.elapsed(),
+                    started.elapsed(),
+                );
+                let (stream, status) = self.record_error(result, &timings)?;
+                return Ok(HttpStreamResponse::new(
+                    stream,
+                    status,
+                    seed,
+                    rt,
+                    timings,
+                    started,
+                    self.slow_operation_threshold.get(),
+                ));
             }
 
             Err(ClientError::RequestFailed {
@@ -307,7 +334,8 @@ impl HTTPClient {
         deadline: Instant,
         signals: Signals,
     ) -> Result<HttpStreamResponse, ClientError> {
-        let rt = Arc::new(Runtime::new().unwrap());
+        let rt = HTTP_RUNTIME.clone();
+        let started = Instant::now();
         rt.clone().block_on(async {
             let config: ClusterConfig = HTTPClient::get_config(
                 &self.seeds,
@@ -319,47 +347,49 @@ impl HTTPClient {
             )
             .await?;
 
-            let seed = if let Some(e) = request.endpoint() {
-                if !config.has_query_seed(&e, self.tls_enabled) {
-                    return Err(ClientError::RequestFailed {
-                        reason: Some(format!("Endpoint {} not known", e)),
-                        key: None,
-                    });
-                }
-                e
-            } else if let Some(s) = config.random_query_seed(self.tls_enabled) {
-                s
-            } else {
+            let verb = request.verb();
+            if !matches!(verb, HttpVerb::Get | HttpVerb::Post) {
                 return Err(ClientError::RequestFailed {
-                    reason: Some("No nodes found for service".to_string()),
+                    reason: Some("Method not allowed for queries".to_string()),
                     key: None,
                 });
-            };
+            }
 
-            let path = request.path();
-            let uri = format!("{}:{}{}", seed.hostname(), seed.port(), &path);
-            let (stream, status) = match request.verb() {
-                HttpVerb::Get => self.http_client.http_get(&uri, deadline, signals).await?,
-                HttpVerb::Post => {
-                    self.http_client
-                        .http_post(
-                            &uri,
-                            request.payload(),
-                            request.headers(),
-                            deadline,
-                            signals,
-                        )
-                        .await?
-                }
-                _ => {
+            let seeds = if let Some(e) = request.endpoint() {
+                if !config.has_query_seed(&e, self.tls_enabled, self.network) {
                     return Err(ClientError::RequestFailed {
-                        reason: Some("Method not allowed for queries".to_string()),
+                        reason: Some(format!("Endpoint {} not known", e)),
                         key: None,
                     });
                 }
+                vec![e]
+            } else {
+                config.query_seeds(self.tls_enabled, self.network)
             };
 
-            Ok(HttpStreamResponse::new(stream, status, seed, rt))
+            let (seed, stream, status, timings) = self
+                .send_round_robin(
+                    seeds,
+                    &request.path(),
+                    verb,
+                    request.payload(),
+                    request.headers(),
+                    deadline,
+                    signals,
+                    "query",
+                    started,
+                )
+                .await?;
+
+            Ok(HttpStreamResponse::new(
+                stream,
+                status,
+                seed,
+                rt,
+                timings,
+                started,
+                self.slow_operation_threshold.get(),
+            ))
         })
     }
 
@@ -368,8 +398,9 @@ impl HTTPClient {
         request: AnalyticsQueryRequest,
         deadline: Instant,
         signals: Signals,
-        rt: Arc<Runtime>,
     ) -> Result<HttpStreamResponse, ClientError> {
+        let rt = HTTP_RUNTIME.clone();
+        let started = Instant::now();
         rt.clone().block_on(async {
             let config: ClusterConfig = HTTPClient::get_config(
                 &self.seeds,
@@ -382,10 +413,11 @@ impl HTTPClient {
             .await?;
 
             let path = request.path();
-            if let Some(seed) = config.random_analytics_seed(self.tls_enabled) {
+            if let Some(seed) = config.random_analytics_seed(self.tls_enabled, self.network) {
                 let uri = format!("{}:{}{}", seed.hostname(), seed.port(), &path);
-                let (stream, status) = match request.verb() {
-                    HttpVerb::Get => self.http_client.http_get(&uri, deadline, signals).await?,
+                let dispatched = Instant::now();
+                let result = match request.verb() {
+                    HttpVerb::Get => self.http_client.http_get(&uri, deadline, signals).await,
                     HttpVerb::Post => {
                         self.http_client
                             .http_post(
@@ -395,17 +427,38 @@ impl HTTPClient {
                                 deadline,
                                 signals,
                             )
-                            .await?
+                            .await
                     }
-                    _ => {
-                        return Err(ClientError::RequestFailed {
-                            reason: Some("Method not allowed for analytics queries".to_string()),
-                            key: None,
-                        });
+                    HttpVerb::Put => {
+                        self.http_client
+                            .http_put(
+                                &uri,
+                                request.payload(),
+                                request.headers(),
+                                deadline,
+                                signals,
+                            )
+                            .await
                     }
+                    HttpVerb::Delete => self.http_client.http_delete(&uri, deadline, signals).await,
                 };
 
-                return Ok(HttpStreamResponse::new(stream, status, seed, rt));
+                let timings = OperationTimings::new(
+                    "analytics",
+                    seed.to_string(),
+                    dispatched.elapsed(),
+                    started.elapsed(),
+                );
+                let (stream, status) = self.record_error(result, &timings)?;
+                return Ok(HttpStreamResponse::new(
+                    stream,
+                    status,
+                    seed,
+                    rt,
+                    timings,
+                    started,
+                    self.slow_operation_threshold.get(),
+                ));
             }
 
             Err(ClientError::RequestFailed {
@@ -421,7 +474,8 @@ impl HTTPClient {
         deadline: Instant,
         signals: Signals,
     ) -> Result<HttpStreamResponse, ClientError> {
-        let rt = Arc::new(Runtime::new().unwrap());
+        let rt = HTTP_RUNTIME.clone();
+        let started = Instant::now();
         rt.clone().block_on(async {
             let config: ClusterConfig = HTTPClient::get_config(
                 &self.seeds,
@@ -433,37 +487,111 @@ impl HTTPClient {
             )
             .await?;
 
-            let path = request.path();
-            if let Some(seed) = config.random_search_seed(self.tls_enabled) {
-                let uri = format!("{}:{}{}", seed.hostname(), seed.port(), &path);
-                let (stream, status) = match request.verb() {
-                    HttpVerb::Post => {
-                        self.http_client
-                            .http_post(
-                                &uri,
-                                request.payload(),
-                                request.headers(),
-                                deadline,
-                                signals,
-                            )
-                            .await?
-                    }
-                    _ => {
-                        return Err(ClientError::RequestFailed {
-                            reason: Some("Method not allowed for analytics queries".to_string()),
-                            key: None,
-                        });
-                    }
-                };
-
-                return Ok(HttpStreamResponse::new(stream, status, seed, rt));
+            if !matches!(request.verb(), HttpVerb::Post) {
+                return Err(ClientError::RequestFailed {
+                    reason: Some("Method not allowed for search queries".to_string()),
+                    key: None,
+                });
             }
 
-            Err(ClientError::RequestFailed {
+            let (seed, stream, status, timings) = self
+                .send_round_robin(
+                    config.search_seeds(self.tls_enabled, self.network),
+                    &request.path(),
+                    HttpVerb::Post,
+                    request.payload(),
+                    request.headers(),
+                    deadline,
+                    signals,
+                    "search",
+                    started,
+                )
+                .await?;
+
+            Ok(HttpStreamResponse::new(
+                stream,
+                status,
+                seed,
+                rt,
+                timings,
+                started,
+                self.slow_operation_threshold.get(),
+            ))
+        })
+    }
+
+    // Sends the request to the seeds in turn, starting after the one used by the previous request.
+    // The next seed is only tried when no connection could be made to one, since the request
+    // cannot have reached the service then and so is safe to send again. Alongside the response
+    // it returns the timings of the operation, which are recorded here when it fails.
+    #[allow(clippy::too_many_arguments)]
+    async fn send_round_robin(
+        &self,
+        seeds: Vec<Endpoint>,
+        path: &str,
+        verb: HttpVerb,
+        payload: Option<Vec<u8>>,
+        headers: HashMap<&str, &str>,
+        deadline: Instant,
+        signals: Signals,
+        operation: &str,
+        started: Instant,
+    ) -> Result<(Endpoint, ResultStream, u16, OperationTimings), ClientError> {
+        if seeds.is_empty() {
+            return Err(ClientError::RequestFailed {
                 reason: Some("No nodes found for service".to_string()),
                 key: None,
-            })
-        })
+            });
+        }
+
+        let start = self.next_seed.fetch_add(1, Ordering::Relaxed);
+        let mut last_error = None;
+        for i in 0..seeds.len() {
+            let seed = &seeds[(start + i) % seeds.len()];
+            let uri = format!("{}:{}{}", seed.hostname(), seed.port(), path);
+            let dispatched = Instant::now();
+            let result = self
+                .http_client
+                .http_do(
+                    &uri,
+                    verb,
+                    payload.clone(),
+                    headers.clone(),
+                    deadline,
+                    signals.clone(),
+                )
+                .await;
+            let timings = OperationTimings::new(
+                operation,
+                seed.to_string(),
+                dispatched.elapsed(),
+                started.elapsed(),
+            );
+            match result {
+                Ok((stream, status)) => return Ok((seed.clone(), stream, status, timings)),
+                Err(e @ ClientError::HttpCouldNotConnect { .. }) => {
+                    debug!("{}, trying the next node", e.expanded_message());
+                    last_error = Some((e, timings));
+                }
+                Err(e) => return self.record_error(Err(e), &timings),
+            }
+        }
+
+        let (e, timings) = last_error.unwrap();
+        self.record_error(Err(e), &timings)
+    }
+
+    // Responses are recorded once they have been read, failed requests have no response so are
+    // recorded here instead.
+    fn record_error<T>(
+        &self,
+        result: Result<T, ClientError>,
+        timings: &OperationTimings,
+    ) -> Result<T, ClientError> {
+        if result.is_err() {
+            timings.record(self.slow_operation_threshold.get());
+        }
+        result
     }
 }
 
@@ -505,10 +633,14 @@ pub enum ManagementRequest {
     GetBucket {
         name: String,
     },
+    GetBucketTerse {
+        name: String,
+    },
     GetCollections {
         bucket: String,
     },
     GetNodes,
+    GetTasks,
     GetRoles {
         permission: Option<String>,
     },
@@ -545,6 +677,7 @@ impl ManagementRequest {
         match self {
             Self::GetBuckets => "/pools/default/buckets".to_string(),
             Self::GetBucket { name } => format!("/pools/default/buckets/{}", name),
+            Self::GetBucketTerse { name } => format!("/pools/default/b/{}", name),
             Self::IndexStatus => "/indexStatus".to_string(),
             Self::SettingsAutoFailover => "/settings/autoFailover".to_string(),
             Self::BucketStats { name } => format!("/pools/default/buckets/{}/stats", name),
@@ -572,6 +705,7 @@ impl ManagementRequest {
             ),
             Self::GetCollections { bucket } => format!("/pools/default/buckets/{}/scopes", bucket),
             Self::GetNodes => "/pools/default".to_string(),
+            Self::GetTasks => "/pools/default/tasks".to_string(),
             Self::GetUsers => "/settings/rbac/users/local".to_string(),
             Self::GetUser { username } => format!("/settings/rbac/users/local/{}", username),
             Self::GetRoles { permission } => match permission {
@@ -602,6 +736,7 @@ impl ManagementRequest {
         match self {
             Self::GetBuckets => HttpVerb::Get,
             Self::GetBucket { .. } => HttpVerb::Get,
+            Self::GetBucketTerse { .. } => HttpVerb::Get,
             Self::IndexStatus => HttpVerb::Get,
             Self::SettingsAutoFailover => HttpVerb::Get,
             Self::BucketStats { .. } => HttpVerb::Get,
@@ -619,6 +754,7 @@ impl ManagementRequest {
             Self::GetRoles { .. } => HttpVerb::Get,
             Self::UpsertUser { .. } => HttpVerb::Put,
             Self::GetNodes => HttpVerb::Get,
+            Self::GetTasks => HttpVerb::Get,
             Self::CreateScope { .. } => HttpVerb::Post,
             Self::DropScope { .. } => HttpVerb::Delete,
             Self::GetScopes { .. } => HttpVerb::Get,
@@ -698,6 +834,7 @@ pub enum QueryRequest {
         scope: Option<(String, String)>,
         timeout: String,
         transaction: Option<QueryTransactionRequest>,
+        readonly: bool,
     },
 }
 
@@ -722,6 +859,7 @@ impl QueryRequest {
                 timeout,
                 transaction,
                 parameters,
+                readonly,
             } => {
                 let mut json = HashMap::new();
                 if let Some(scope) = scope {
@@ -737,6 +875,9 @@ impl QueryRequest {
                     "timeout".to_string(),
                     serde_json::Value::String(timeout.to_string()),
                 );
+                if *readonly {
+                    json.insert("readonly".to_string(), serde_json::Value::Bool(true));
+                }
                 if let Some(txn) = transaction {
                     if let Some(t) = txn.tx_timeout {
                         json.insert(
@@ -797,13 +938,67 @@ impl QueryRequest {
     }
 }
 
+#[derive(Clone, Default)]
+pub struct AnalyticsQueryOptions {
+    parameters: Option<serde_json::Value>,
+    scan_consistency: Option<String>,
+    readonly: bool,
+    priority: bool,
+    client_context_id: Option<String>,
+}
+
+impl AnalyticsQueryOptions {
+    pub fn new(
+        parameters: impl Into<Option<serde_json::Value>>,
+        scan_consistency: impl Into<Option<String>>,
+        readonly: bool,
+        priority: bool,
+        client_context_id: impl Into<Option<String>>,
+    ) -> Self {
+        Self {
+            parameters: parameters.into(),
+            scan_consistency: scan_consistency.into(),
+            readonly,
+            priority,
+            client_context_id: client_context_id.into(),
+        }
+    }
+}
+
 pub enum AnalyticsQueryRequest {
     Execute {
         statement: String,
         scope: Option<(String, String)>,
         timeout: String,
+        options: AnalyticsQueryOptions,
     },
     PendingMutations,
+    CreateLink {
+        scope: String,
+        name: String,
+        payload: String,
+    },
+    AlterLink {
+        scope: String,
+        name: String,
+        payload: String,
+    },
+    DropLink {
+        scope: String,
+        name: String,
+    },
+}
+
+// Everything but the unreserved characters is encoded, so that a value such as a multi part scope
+// stays a single segment of the path.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+fn path_segment(value: &str) -> String {
+    utf8_percent_encode(value, PATH_SEGMENT).to_string()
 }
 
 impl AnalyticsQueryRequest {
@@ -811,6 +1006,13 @@ impl AnalyticsQueryRequest {
         match self {
             Self::Execute { .. } => "/query/service".to_string(),
             Self::PendingMutations => "/analytics/node/agg/stats/remaining".to_string(),
+            Self::CreateLink { scope, name, .. }
+            | Self::AlterLink { scope, name, .. }
+            | Self::DropLink { scope, name } => format!(
+                "/analytics/link/{}/{}",
+                path_segment(scope),
+                path_segment(name)
+            ),
         }
     }
 
@@ -818,6 +1020,9 @@ impl AnalyticsQueryRequest {
         match self {
             Self::Execute { .. } => HttpVerb::Post,
             Self::PendingMutations => HttpVerb::Get,
+            Self::CreateLink { .. } => HttpVerb::Post,
+            Self::AlterLink { .. } => HttpVerb::Put,
+            Self::DropLink { .. } => HttpVerb::Delete,
         }
     }
 
@@ -827,29 +1032,70 @@ impl AnalyticsQueryRequest {
                 statement,
                 scope,
                 timeout,
+                options,
             } => {
+                let mut json = json!({ "statement": statement });
                 if let Some(scope) = scope {
                     let ctx = format!("`default`:`{}`.`{}`", scope.0, scope.1);
-                    let json =
-                        json!({ "statement": statement, "query_context": ctx, "timeout": timeout });
-                    Some(serde_json::to_vec(&json).unwrap())
-                } else {
-                    let json = json!({ "statement": statement });
-                    Some(serde_json::to_vec(&json).unwrap())
+                    json["query_context"] = serde_json::Value::String(ctx);
+                    json["timeout"] = serde_json::Value::String(timeout.to_string());
+                }
+
+                if let Some(params) = &options.parameters {
+                    match params {
+                        serde_json::Value::Array(_) => {
+                            json["args"] = params.clone();
+                        }
+                        serde_json::Value::Object(map) => {
+                            for (k, v) in map.iter() {
+                                let key = if k.starts_with('$') {
+                                    k.clone()
+                                } else {
+                                    format!("${}", *k)
+                                };
+                                json[key] = v.clone();
+                            }
+                        }
+                        _ => {}
+                    }
                 }
+                if let Some(consistency) = &options.scan_consistency {
+                    json["scan_consistency"] = serde_json::Value::String(consistency.clone());
+                }
+                if options.readonly {
+                    json["readonly"] = serde_json::Value::Bool(true);
+                }
+                if let Some(id) = &options.client_context_id {
+                    json["client_context_id"] = serde_json::Value::String(id.clone());
+                }
+
+                Some(serde_json::to_vec(&json).unwrap())
             }
             Self::PendingMutations => None,
+            Self::CreateLink { payload, .. } => Some(payload.as_bytes().into()),
+            Self::AlterLink { payload, .. } => Some(payload.as_bytes().into()),
+            Self::DropLink { .. } => None,
         }
     }
 
     pub fn headers(&self) -> HashMap<&str, &str> {
         match self {
-            Self::Execute { .. } => {
+            Self::Execute { options, .. } => {
                 let mut h = HashMap::new();
                 h.insert("Content-Type", "application/json");
+                // The analytics service runs requests with a priority of -1 ahead of others.
+                if options.priority {
+                    h.insert("Analytics-Priority", "-1");
+                }
                 h
             }
             Self::PendingMutations => HashMap::new(),
+            Self::CreateLink { .. } | Self::AlterLink { .. } => {
+                let mut h = HashMap::new();
+                h.insert("Content-Type", "application/x-www-form-urlencoded");
+                h
+            }
+            Self::DropLink { .. } => HashMap::new(),
         }
     }
 }
@@ -1065,106 +1311,58 @@ impl Config for ClusterConfig {
 }
 
 impl ClusterConfig {
-    pub fn management_seeds(&self, tls: bool) -> Vec<Endpoint> {
+    pub fn management_seeds(&self, tls: bool, network: Network) -> Vec<Endpoint> {
         let key = if tls { "mgmtSSL" } else { "mgmt" };
 
-        self.seeds(key)
+        self.seeds(key, network)
     }
 
-    pub fn query_seeds(&self, tls: bool) -> Vec<Endpoint> {
+    pub fn query_seeds(&self, tls: bool, network: Network) -> Vec<Endpoint> {
         let key = if tls { "n1qlSSL" } else { "n1ql" };
 
-        self.seeds(key)
+        self.seeds(key, network)
     }
 
-    pub fn analytics_seeds(&self, tls: bool) -> Vec<Endpoint> {
+    pub fn analytics_seeds(&self, tls: bool, network: Network) -> Vec<Endpoint> {
         let key = if tls { "cbasSSL" } else { "cbas" };
 
-        self.seeds(key)
+        self.seeds(key, network)
     }
 
-    pub fn search_seeds(&self, tls: bool) -> Vec<Endpoint> {
+    pub fn search_seeds(&self, tls: bool, network: Network) -> Vec<Endpoint> {
         let key = if tls { "ftsSSL" } else { "fts" };
 
-        self.seeds(key)
+        self.seeds(key, network)
     }
 
-    pub fn view_seeds(&self, tls: bool) -> Vec<Endpoint> {
+    pub fn view_seeds(&self, tls: bool, network: Network) -> Vec<Endpoint> {
         let key = if tls { "capiSSL" } else { "capi" };
 
-        self.seeds(key)
+        self.seeds(key, network)
     }
 
-    fn seeds(&self, key: &str) -> Vec<Endpoint> {
-        let default: Vec<Endpoint> = self
-            .nodes_ext
-            .iter()
-            .filter(|node| node.services.contains_key(key))
-            .map(|node| {
-                let hostname = if node.hostname.is_some() {
-                    node.hostname.as_ref().unwrap().clone()
-                } else {
-                    self.loaded_from.as_ref().unwrap().clone()
-                };
-                Endpoint::new(hostname, *node.services.get(key).unwrap())
-            })
-            .collect();
+    fn seeds(&self, key: &str, network: Network) -> Vec<Endpoint> {
+        let loaded_from = self.loaded_from.as_deref().unwrap_or_default();
+        let external = use_external_network(&self.nodes_ext, loaded_from, network);
 
-        for seed in &default {
-            if seed.hostname() == self.loaded_from.as_ref().unwrap().clone() {
-                return default;
-            }
-        }
-
-        let external: Vec<Endpoint> = self
-            .nodes_ext
+        self.nodes_ext
             .iter()
-            .filter(|node| {
-                if let Some(external_addresses) = node.alternate_addresses.get("external") {
-                    return external_addresses.ports.contains_key(key);
-                }
-
-                false
-            })
-            .map(|node| {
-                let address = node.alternate_addresses.get("external").unwrap();
-                let hostname = if address.hostname.is_some() {
-                    address.hostname.as_ref().unwrap().clone()
-                } else {
-                    self.loaded_from.as_ref().unwrap().clone()
-                };
-                Endpoint::new(hostname, *address.ports.get(key).unwrap())
-            })
-            .collect();
-
-        for seed in &external {
-            if seed.hostname() == self.loaded_from.as_ref().unwrap().clone() {
-                return external;
-            }
-        }
-
-        default
+            .filter_map(|node| node.address(key, external, loaded_from))
+            .map(|(hostname, port)| Endpoint::new(hostname, port))
+            .collect()
     }
 
-    pub fn has_query_seed(&self, endpoint: &Endpoint, tls: bool) -> bool {
-        let seeds = self.query_seeds(tls);
+    pub fn has_query_seed(&self, endpoint: &Endpoint, tls: bool, network: Network) -> bool {
+        let seeds = self.query_seeds(tls, network);
         seeds.contains(endpoint)
     }
 
-    fn random_management_seed(&self, tls: bool) -> Option<Endpoint> {
-        self.random_seed(self.management_seeds(tls))
-    }
-
-    fn random_query_seed(&self, tls: bool) -> Option<Endpoint> {
-        self.random_seed(self.query_seeds(tls))
-    }
-
-    pub fn random_analytics_seed(&self, tls: bool) -> Option<Endpoint> {
-        self.random_seed(self.analytics_seeds(tls))
+    fn random_management_seed(&self, tls: bool, network: Network) -> Option<Endpoint> {
+        self.random_seed(self.management_seeds(tls, network))
     }
 
-    fn random_search_seed(&self, tls: bool) -> Option<Endpoint> {
-        self.random_seed(self.search_seeds(tls))
+    pub fn random_analytics_seed(&self, tls: bool, network: Network) -> Option<Endpoint> {
+        self.random_seed(self.analytics_seeds(tls, network))
     }
 
     fn random_seed(&self, seeds: Vec<Endpoint>) -> Option<Endpoint> {
@@ -1184,3 +1382,27 @@ impl ClusterConfig {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn link_paths_encode_scope_and_name() {
+        let request = AnalyticsQueryRequest::DropLink {
+            scope: "travel/inventory".to_string(),
+            name: "my link".to_string(),
+        };
+        assert_eq!(
+            "/analytics/link/travel%2Finventory/my%20link",
+            request.path()
+        );
+
+        let request = AnalyticsQueryRequest::CreateLink {
+            scope: "Default".to_string(),
+            name: "s3_link".to_string(),
+            payload: String::new(),
+        };
+        assert_eq!("/analytics/link/Default/s3_link", request.path());
+    }
+}
@@ -1,23 +1,41 @@
 use crate::cli::{client_error_to_shell_error, CtrlcFuture};
 use crate::client::error::ClientError;
+use crate::client::proxy::ProxyConfig;
+use crate::client::timings::OperationTimings;
 use crate::client::Endpoint;
 use crate::RustTlsConfig;
 use bytes::Bytes;
 use futures_core::Stream;
 use futures_util::stream::StreamExt;
+use lazy_static::lazy_static;
 use log::debug;
 use nu_protocol::{ShellError, Signals};
-use reqwest::ClientBuilder;
+use reqwest::{Client, ClientBuilder};
 use std::collections::HashMap;
 use std::ops::Sub;
 use std::pin::Pin;
 use std::str::from_utf8;
-use std::sync::Arc;
-use tokio::runtime::Runtime;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
 use tokio::{select, time::Instant};
 
 pub type ResultStream = Pin<Box<dyn Stream<Item = Result<Bytes, reqwest::Error>> + Send>>;
 
+lazy_static! {
+    // Pooled HTTP connections are driven by tasks on the runtime which opened them, so requests
+    // are sent on this runtime rather than one per command to keep the connections usable.
+    pub(crate) static ref HTTP_RUNTIME: Arc<Runtime> = Arc::new(
+        Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("cbsh-http")
+            .enable_all()
+            .build()
+            .unwrap()
+    );
+}
+
+#[derive(Clone, Copy)]
 pub enum HttpVerb {
     Delete,
     Get,
@@ -65,23 +83,53 @@ pub struct HttpStreamResponse {
     status: u16,
     endpoint: Endpoint,
     rt: Arc<Runtime>,
+    timings: OperationTimings,
+    started: Instant,
+    slow_operation_threshold: Option<Duration>,
 }
 
 impl HttpStreamResponse {
-    pub fn new(stream: ResultStream, status: u16, endpoint: Endpoint, rt: Arc<Runtime>) -> Self {
+    pub fn new(
+        stream: ResultStream,
+        status: u16,
+        endpoint: Endpoint,
+        rt: Arc<Runtime>,
+        timings: OperationTimings,
+        started: Instant,
+        slow_operation_threshold: Option<Duration>,
+    ) -> Self {
         Self {
             stream,
             status,
             endpoint,
             rt,
+            timings,
+            started,
+            slow_operation_threshold,
         }
     }
 
     pub fn content(self) -> Result<String, ShellError> {
-        self.rt
-            .clone()
-            .block_on(async { read_stream(self.stream).await })
-            .map_err(|e| client_error_to_shell_error(e, None))
+        self.content_with_timings().map(|(content, _)| content)
+    }
+
+    // The total time of the operation includes reading the body, so is only known once it has
+    // been read.
+    pub fn content_with_timings(self) -> Result<(String, OperationTimings), ShellError> {
+        let Self {
+            stream,
+            rt,
+            mut timings,
+            started,
+            slow_operation_threshold,
+            ..
+        } = self;
+        let content = rt.block_on(async { read_stream(stream).await });
+
+        timings.set_total(started.elapsed());
+        timings.record(slow_operation_threshold);
+        let content = content.map_err(|e| client_error_to_shell_error(e, None))?;
+        Ok((content, timings))
     }
 
     pub fn status(&self) -> u16 {
@@ -92,7 +140,9 @@ impl HttpStreamResponse {
         self.endpoint.clone()
     }
 
+    // Streamed responses are read elsewhere, so their timings end when the headers arrived.
     pub fn stream(self) -> ResultStream {
+        self.timings.record(self.slow_operation_threshold);
         self.stream
     }
 }
@@ -101,6 +151,10 @@ pub(crate) struct HTTPHandler {
     username: String,
     password: String,
     tls_config: Option<RustTlsConfig>,
+    // Without one the proxy environment variables are used.
+    proxy: Option<ProxyConfig>,
+    // Built on first use and then kept, so that connections are reused across requests.
+    client: Mutex<Option<Client>>,
 }
 
 impl HTTPHandler {
@@ -108,14 +162,38 @@ impl HTTPHandler {
         username: String,
         password: String,
         tls_config: Option<RustTlsConfig>,
+        proxy: Option<ProxyConfig>,
     ) -> Self {
         Self {
             username,
             password,
             tls_config,
+            proxy,
+            client: Mutex::new(None),
         }
     }
 
+    fn client(&self) -> Result<Client, ClientError> {
+        let mut client = self.client.lock().unwrap();
+        if let Some(c) = client.as_ref() {
+            return Ok(c.clone());
+        }
+
+        let mut client_builder = ClientBuilder::new();
+
+        if let Some(tls_config) = &self.tls_config {
+            client_builder = client_builder.use_preconfigured_tls(tls_config.config());
+        }
+
+        if let Some(proxy) = &self.proxy {
+            client_builder = client_builder.proxy(proxy.reqwest_proxy());
+        }
+
+        let c = client_builder.build().map_err(ClientError::from)?;
+        *client = Some(c.clone());
+        Ok(c)
+    }
+
     fn http_prefix(&self) -> &'static str {
         match self.tls_config.is_some() {
             true => "https",
@@ -141,23 +219,19 @@ impl HTTPHandler {
         let timeout = deadline.sub(now);
         let signals_fut = CtrlcFuture::new(signals);
 
-        let mut client_builder = ClientBuilder::new();
-
-        if let Some(tls_config) = &self.tls_config {
-            client_builder = client_builder.use_preconfigured_tls(tls_config.config());
-        }
-
-        let client = client_builder.build().map_err(ClientError::from)?;
+        let client = self.client()?;
         let mut res_builder = match method {
-            HttpVerb::Delete => client.delete(uri),
-            HttpVerb::Get => client.get(uri),
-            HttpVerb::Post => client.post(uri),
-            HttpVerb::Put => client.put(uri),
+            HttpVerb::Delete => client.delete(&uri),
+            HttpVerb::Get => client.get(&uri),
+            HttpVerb::Post => client.post(&uri),
+            HttpVerb::Put => client.put(&uri),
         };
 
-        res_builder = res_builder
-            .basic_auth(&self.username, Some(&self.password))
-            .timeout(timeout);
+        // Users authenticating with a client certificate have no password to send.
+        if !self.password.is_empty() {
+            res_builder = res_builder.basic_auth(&self.username, Some(&self.password));
+        }
+        res_builder = res_builder.timeout(timeout);
 
         for (key, value) in headers {
             res_builder = res_builder.header(key, value);
@@ -180,6 +254,11 @@ impl HTTPHandler {
                             Err(ClientError::Timeout {
                                 key: None,
                             })
+                        } else if e.is_connect() {
+                            Err(ClientError::HttpCouldNotConnect {
+                                reason: format!("{}", e),
+                                address: uri,
+                            })
                         } else {
                             Err(ClientError::RequestFailed {
                                 reason: Some(format!("{}", e)),
@@ -1,24 +1,38 @@
 use crate::client::codec::KeyValueCodec;
+use crate::client::kv_client::{LookupInSpec, MutateInSpec};
 use crate::client::protocol::{request, KvRequest, KvResponse, Status};
+use crate::client::proxy::ProxyConfig;
 use crate::client::{protocol, ClientError};
 use crate::RustTlsConfig;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine as _;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use futures::lock::Mutex as AsyncMutex;
 use futures::{SinkExt, StreamExt};
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
 use log::{debug, trace, warn};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde_derive::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use tokio::io::{AsyncRead, AsyncWrite};
-use tokio::net::TcpStream;
 use tokio::sync::oneshot::Receiver;
 use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
 use tokio_rustls::rustls::pki_types::ServerName;
 use tokio_rustls::TlsConnector;
 use tokio_util::codec::{FramedRead, FramedWrite};
 use uuid::Uuid;
 
+const SCRAM_NONCE_LEN: usize = 24;
+
 pub struct KvEndpoint {
     tx: mpsc::Sender<Bytes>,
     opaque: AtomicU32,
@@ -27,10 +41,12 @@ pub struct KvEndpoint {
     local_addr: String,
     remote_addr: String,
     uuid: String,
+    reader: JoinHandle<()>,
     // error_map: Option<ErrorMap>,
 }
 
 impl KvEndpoint {
+    #[allow(clippy::too_many_arguments)]
     pub async fn connect(
         hostname: String,
         port: u32,
@@ -38,8 +54,15 @@ impl KvEndpoint {
         password: String,
         bucket: String,
         tls_config: Option<RustTlsConfig>,
+        sasl_mechanism: Option<SaslMechanism>,
+        proxy: Option<ProxyConfig>,
     ) -> Result<KvEndpoint, ClientError> {
         let remote_addr = format!("{}:{}", hostname, port);
+        let could_not_connect = |reason: String| ClientError::KVCouldNotConnect {
+            reason,
+            address: remote_addr.clone(),
+        };
+        let tcp_port = u16::try_from(port).map_err(|e| could_not_connect(e.to_string()))?;
 
         debug!(
             "Connecting to {}, TLS enabled: {}",
@@ -48,12 +71,9 @@ impl KvEndpoint {
         );
 
         if let Some(tls_config) = tls_config {
-            let tcp_socket = TcpStream::connect(&remote_addr).await.map_err(|e| {
-                ClientError::KVCouldNotConnect {
-                    reason: e.to_string(),
-                    address: remote_addr.clone(),
-                }
-            })?;
+            let tcp_socket = ProxyConfig::connect(proxy.as_ref(), &hostname, tcp_port)
+                .await
+                .map_err(could_not_connect)?;
             let local_addr = tcp_socket.local_addr()?;
 
             let connector = TlsConnector::from(Arc::new(tls_config.config()));
@@ -64,28 +84,42 @@ impl KvEndpoint {
                     reason: e.to_string(),
                     address: remote_addr.clone(),
                 })?;
+            // Passwords are already encrypted on the wire, so PLAIN is used unless overridden. With
+            // a client certificate the user is known from the handshake and no password is needed.
+            let default_mechanism = if tls_config.has_client_cert() {
+                SaslMechanism::External
+            } else {
+                SaslMechanism::Plain
+            };
             KvEndpoint::setup(
                 username,
                 password,
                 bucket,
+                Some(sasl_mechanism.unwrap_or(default_mechanism)),
                 socket,
                 local_addr.to_string(),
                 remote_addr,
             )
             .await
         } else {
-            let socket = TcpStream::connect(&remote_addr).await.map_err(|e| {
-                ClientError::KVCouldNotConnect {
-                    reason: e.to_string(),
-                    address: remote_addr.clone(),
-                }
-            })?;
+            if sasl_mechanism == Some(SaslMechanism::External) {
+                return Err(ClientError::AuthError {
+                    reason: Some(
+                        "EXTERNAL authentication requires a TLS client certificate".into(),
+                    ),
+                });
+            }
+
+            let socket = ProxyConfig::connect(proxy.as_ref(), &hostname, tcp_port)
+                .await
+                .map_err(could_not_connect)?;
             let local_addr = socket.local_addr()?;
 
             KvEndpoint::setup(
                 username,
                 password,
                 bucket,
+                sasl_mechanism,
                 socket,
                 local_addr.to_string(),
                 remote_addr,
@@ -98,6 +132,7 @@ impl KvEndpoint {
         username: String,
         password: String,
         bucket: String,
+        sasl_mechanism: Option<SaslMechanism>,
         stream: C,
         local_addr: String,
         remote_addr: String,
@@ -107,16 +142,7 @@ impl KvEndpoint {
         let in_flight = Arc::new(AsyncMutex::new(
             HashMap::<u32, oneshot::Sender<KvResponse>>::new(),
         ));
-        let mut ep = KvEndpoint {
-            opaque: AtomicU32::new(0),
-            in_flight: Arc::clone(&in_flight),
-            tx,
-            collections_enabled: false,
-            local_addr,
-            remote_addr,
-            uuid: uuid.clone(),
-            // error_map: None,
-        };
+        let ep_in_flight = Arc::clone(&in_flight);
 
         let (r, w) = tokio::io::split(stream);
         let mut output = FramedWrite::new(w, KeyValueCodec::new());
@@ -124,7 +150,7 @@ impl KvEndpoint {
 
         // Read thread.
         let recv_uuid = uuid.clone();
-        tokio::spawn(async move {
+        let reader = tokio::spawn(async move {
             loop {
                 if let Some(frame) = input.next().await {
                     match frame {
@@ -162,6 +188,12 @@ impl KvEndpoint {
                             warn!("{} failed to read frame {}", recv_uuid, e.to_string());
                         }
                     };
+                } else {
+                    // The connection has been closed, dropping the senders of any requests still
+                    // in flight fails them rather than leaving them to time out.
+                    debug!("{} connection closed", recv_uuid);
+                    in_flight.lock().await.clear();
+                    return;
                 }
             }
         });
@@ -183,9 +215,22 @@ impl KvEndpoint {
             }
         });
 
+        let mut ep = KvEndpoint {
+            opaque: AtomicU32::new(0),
+            in_flight: ep_in_flight,
+            tx,
+            collections_enabled: false,
+            local_addr,
+            remote_addr,
+            uuid: uuid.clone(),
+            reader,
+            // error_map: None,
+        };
+
         let hello_rcvr = ep.send_hello().await?;
         // let err_map_rcvr = ep.send_error_map().await.map(|r| Some(r))?;
-        let auth_rcvr = ep.send_auth(username, password).await?;
+        ep.authenticate(username, password, sasl_mechanism).await?;
+        debug!("{} authenticated successfully", ep.uuid);
         let bucket_rcvr = ep.send_select_bucket(bucket).await?;
 
         let features = match hello_rcvr.await {
@@ -213,21 +258,6 @@ impl KvEndpoint {
         //     };
         //     ep.error_map = error_map;
         // }
-        match auth_rcvr.await {
-            Ok(r) => match r {
-                Ok(result) => result,
-                Err(e) => {
-                    return Err(e);
-                }
-            },
-            Err(e) => {
-                return Err(ClientError::RequestFailed {
-                    reason: Some(e.to_string()),
-                    key: None,
-                });
-            }
-        };
-        debug!("{} authenticated successfully", ep.uuid);
         match bucket_rcvr.await {
             Ok(r) => match r {
                 Ok(result) => result,
@@ -258,6 +288,10 @@ impl KvEndpoint {
         key: impl Into<Option<String>>,
     ) -> Result<KvResponse, ClientError> {
         match rx.await {
+            Ok(mut r) if r.status() == Status::NotMyVbucket => Err(ClientError::NotMyVbucket {
+                key: key.into(),
+                config: r.body(),
+            }),
             Ok(r) => Ok(r),
             Err(e) => Err(ClientError::RequestFailed {
                 reason: Some(e.to_string()),
@@ -432,6 +466,142 @@ impl KvEndpoint {
             .await
     }
 
+    // lookup_in is a multi lookup that supports xattrs and deleted documents, unlike
+    // sub_doc_multi_lookup individual paths are allowed to fail.
+    pub async fn lookup_in(
+        &self,
+        key: String,
+        partition: u16,
+        collection_id: u32,
+        specs: Vec<LookupInSpec>,
+        access_deleted: bool,
+    ) -> Result<KvResponse, ClientError> {
+        let mut value_buf = BytesMut::new();
+        for spec in specs {
+            let (opcode, flags, path) = spec.encoded();
+            value_buf.put_u8(opcode.encoded());
+            value_buf.put_u8(flags);
+            value_buf.put_u16(path.len() as u16);
+            value_buf.put(path.as_bytes());
+        }
+
+        let mut extras = BytesMut::with_capacity(1);
+        if access_deleted {
+            extras.put_u8(protocol::SUBDOC_DOC_FLAG_ACCESS_DELETED);
+        } else {
+            extras.put_u8(0);
+        }
+
+        let req = KvRequest::new(
+            protocol::Opcode::SubdocMultiLookup,
+            0,
+            partition,
+            0,
+            Some(Bytes::from(key.clone())),
+            Some(extras.freeze()),
+            Some(value_buf.freeze()),
+            collection_id,
+        );
+
+        let (tx, rx) = oneshot::channel::<KvResponse>();
+        self.send(req, tx).await?;
+
+        let mut response = self.await_response(rx, key.clone()).await?;
+        match response.status() {
+            Status::Success
+            | Status::SubdocMultiPathFailure
+            | Status::SubdocSuccessDeleted
+            | Status::SubdocMultiPathFailureDeleted => Ok(response),
+            status => {
+                let reason = ClientError::try_parse_kv_fail_body(&mut response);
+                Err(ClientError::make_kv_doc_op_error(
+                    status,
+                    reason,
+                    key,
+                    collection_id,
+                    None,
+                ))
+            }
+        }
+    }
+
+    pub async fn mutate_in(
+        &self,
+        key: String,
+        partition: u16,
+        collection_id: u32,
+        specs: Vec<MutateInSpec>,
+        cas: u64,
+        doc_flags: u8,
+    ) -> Result<KvResponse, ClientError> {
+        let mut value_buf = BytesMut::new();
+        let mut paths = vec![];
+        for spec in specs {
+            let (opcode, flags, path, value) = spec.encoded();
+            value_buf.put_u8(opcode.encoded());
+            value_buf.put_u8(flags);
+            value_buf.put_u16(path.len() as u16);
+            value_buf.put_u32(value.len() as u32);
+            value_buf.put(path.as_bytes());
+            value_buf.put(value.as_slice());
+            paths.push(path);
+        }
+
+        let extras = if doc_flags != 0 {
+            let mut extras = BytesMut::with_capacity(1);
+            extras.put_u8(doc_flags);
+            Some(extras.freeze())
+        } else {
+            None
+        };
+
+        let req = KvRequest::new(
+            protocol::Opcode::SubdocMultiMutation,
+            0,
+            partition,
+            cas,
+            Some(Bytes::from(key.clone())),
+            extras,
+            Some(value_buf.freeze()),
+            collection_id,
+        );
+
+        let (tx, rx) = oneshot::channel::<KvResponse>();
+        self.send(req, tx).await?;
+
+        let mut response = self.await_response(rx, key.clone()).await?;
+        match response.status() {
+            Status::Success => Ok(response),
+            Status::SubdocMultiPathFailure => {
+                // The body holds the index and status of the first spec which failed.
+                let (status, path) = match response.body() {
+                    Some(mut body) if body.len() >= 3 => {
+                        let index = body.get_u8() as usize;
+                        (Status::from(body.get_u16()), paths.get(index).cloned())
+                    }
+                    _ => (Status::SubdocMultiPathFailure, None),
+                };
+                Err(ClientError::make_kv_doc_op_error(
+                    status,
+                    None,
+                    key,
+                    collection_id,
+                    path,
+                ))
+            }
+            status => {
+                let reason = ClientError::try_parse_kv_fail_body(&mut response);
+                Err(ClientError::make_kv_doc_op_error(
+                    status,
+                    reason,
+                    key,
+                    collection_id,
+                    None,
+                ))
+            }
+        }
+    }
+
     pub async fn set(
         &self,
         key: String,
@@ -569,6 +739,10 @@ impl KvEndpoint {
         self.remote_addr.clone()
     }
 
+    pub fn is_closed(&self) -> bool {
+        self.reader.is_finished()
+    }
+
     async fn send(
         &self,
         mut req: KvRequest,
@@ -620,6 +794,8 @@ impl KvEndpoint {
             ServerFeature::Collections,
             ServerFeature::Tracing,
             ServerFeature::UnorderedExecution,
+            ServerFeature::Vattr,
+            ServerFeature::CreateAsDeleted,
         ];
         let mut body = BytesMut::with_capacity(features.len() * 2);
         for feature in &features {
@@ -675,36 +851,131 @@ impl KvEndpoint {
     //     Ok(completerx)
     // }
 
-    async fn send_auth(
-        &mut self,
+    // Authenticates with the given mechanism or, when there is none, with the strongest SCRAM
+    // mechanism the server supports so that passwords are never sent in the clear.
+    async fn authenticate(
+        &self,
         username: String,
         password: String,
-    ) -> Result<oneshot::Receiver<Result<(), ClientError>>, ClientError> {
-        let mut body = BytesMut::with_capacity(username.len() + password.len() + 2);
-        body.put_u8(0);
-        body.put(username.as_bytes());
-        body.put_u8(0);
-        body.put(password.as_bytes());
+        mechanism: Option<SaslMechanism>,
+    ) -> Result<(), ClientError> {
+        let mechanism = match mechanism {
+            Some(m) => m,
+            None => {
+                let supported = self.list_sasl_mechanisms().await?;
+                match SaslMechanism::preferred(&supported) {
+                    Some(m) => m,
+                    // The list of mechanisms is not protected without TLS, so falling back to PLAIN
+                    // would hand the password to anyone able to rewrite it.
+                    None => {
+                        return Err(ClientError::AuthError {
+                            reason: Some(format!(
+                                "{} supports no SCRAM mechanism ({}), set sasl-mechanism = \"PLAIN\" for the cluster to send the password in plaintext",
+                                self.remote_addr,
+                                supported.join(" ")
+                            )),
+                        })
+                    }
+                }
+            }
+        };
+        debug!("{} authenticating using {}", self.uuid, mechanism);
+
+        match (mechanism, mechanism.scram_functions()) {
+            // The server has already authenticated the connection from the client certificate
+            // presented during the TLS handshake, there is nothing left to exchange.
+            (SaslMechanism::External, _) => Ok(()),
+            (_, None) => self.auth_plain(username, password).await,
+            (_, Some((hmac, hash))) => {
+                let nonce: String = rand::thread_rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(SCRAM_NONCE_LEN)
+                    .map(char::from)
+                    .collect();
+                let scram = ScramClient::new(hmac, hash, &username, password, nonce);
+                self.auth_scram(mechanism, scram).await
+            }
+        }
+    }
 
+    async fn list_sasl_mechanisms(&self) -> Result<Vec<String>, ClientError> {
         let req = KvRequest::new(
-            protocol::Opcode::Auth,
+            protocol::Opcode::SaslListMechs,
             0,
             0,
             0,
-            Some(Bytes::from("PLAIN")),
             None,
-            Some(body.freeze()),
+            None,
+            None,
             0,
         );
+        let mut response = self.sasl_request(req).await?;
+        let mechanisms = response
+            .body()
+            .map(|b| String::from_utf8_lossy(&b).to_string())
+            .unwrap_or_default();
+
+        Ok(mechanisms.split_whitespace().map(String::from).collect())
+    }
+
+    async fn auth_plain(&self, username: String, password: String) -> Result<(), ClientError> {
+        let mut body = BytesMut::with_capacity(username.len() + password.len() + 2);
+        body.put_u8(0);
+        body.put(username.as_bytes());
+        body.put_u8(0);
+        body.put(password.as_bytes());
+
+        self.sasl_request(sasl_auth_request(
+            protocol::Opcode::Auth,
+            SaslMechanism::Plain,
+            body.freeze(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    async fn auth_scram(
+        &self,
+        mechanism: SaslMechanism,
+        mut scram: ScramClient,
+    ) -> Result<(), ClientError> {
+        let client_first = scram.client_first();
+        let mut response = self
+            .sasl_request(sasl_auth_request(
+                protocol::Opcode::Auth,
+                mechanism,
+                Bytes::from(client_first),
+            ))
+            .await?;
+        let server_first = sasl_body(&mut response);
+
+        let client_final = scram.client_final(&server_first)?;
+        let mut response = self
+            .sasl_request(sasl_auth_request(
+                protocol::Opcode::SaslStep,
+                mechanism,
+                Bytes::from(client_final),
+            ))
+            .await?;
+
+        scram.verify_server_final(&sasl_body(&mut response))
+    }
+
+    // Sends one step of the SASL handshake, the server answers every step but the last with an
+    // auth continue status.
+    async fn sasl_request(&self, req: KvRequest) -> Result<KvResponse, ClientError> {
         let (tx, rx) = oneshot::channel::<KvResponse>();
         self.send(req, tx).await?;
 
-        let (completetx, completerx) = oneshot::channel::<Result<(), ClientError>>();
-        tokio::spawn(async move {
-            receive_auth(rx, completetx).await;
-        });
-
-        Ok(completerx)
+        let response = self.await_response(rx, None).await?;
+        match response.status() {
+            Status::Success | Status::AuthContinue => Ok(response),
+            status => Err(ClientError::RequestFailed {
+                reason: Some(status.as_string()),
+                key: None,
+            }),
+        }
     }
 
     async fn send_select_bucket(
@@ -736,6 +1007,14 @@ impl KvEndpoint {
     }
 }
 
+// The read task would otherwise outlive the endpoint for as long as the server keeps the
+// connection open. The send task stops by itself once the request channel is dropped.
+impl Drop for KvEndpoint {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
 async fn receive_hello(
     rx: oneshot::Receiver<KvResponse>,
     completetx: oneshot::Sender<Result<Vec<ServerFeature>, ClientError>>,
@@ -828,7 +1107,7 @@ async fn receive_hello(
 //     };
 // }
 
-async fn receive_auth(
+async fn receive_select_bucket(
     rx: oneshot::Receiver<KvResponse>,
     completetx: oneshot::Sender<Result<(), ClientError>>,
 ) {
@@ -855,41 +1134,207 @@ async fn receive_auth(
     match completetx.send(result) {
         Ok(()) => {}
         Err(_e) => {
-            warn!("auth receive failed");
+            warn!("select bucket receive failed");
         }
     };
 }
 
-async fn receive_select_bucket(
-    rx: oneshot::Receiver<KvResponse>,
-    completetx: oneshot::Sender<Result<(), ClientError>>,
-) {
-    let r = match rx.await {
-        Ok(r) => Some(r),
-        Err(_e) => None,
-    };
-    let result = if let Some(response) = r {
-        let status = response.status();
-        match status {
-            Status::Success => Ok(()),
-            _ => Err(ClientError::RequestFailed {
-                reason: Some(status.as_string()),
-                key: None,
-            }),
+fn sasl_auth_request(opcode: protocol::Opcode, mechanism: SaslMechanism, body: Bytes) -> KvRequest {
+    KvRequest::new(
+        opcode,
+        0,
+        0,
+        0,
+        Some(Bytes::from(mechanism.name())),
+        None,
+        Some(body),
+        0,
+    )
+}
+
+fn sasl_body(response: &mut KvResponse) -> String {
+    response
+        .body()
+        .map(|b| String::from_utf8_lossy(&b).to_string())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub enum SaslMechanism {
+    #[serde(rename = "PLAIN", alias = "plain")]
+    Plain,
+    #[serde(rename = "SCRAM-SHA1", alias = "scram-sha1")]
+    ScramSha1,
+    #[serde(rename = "SCRAM-SHA256", alias = "scram-sha256")]
+    ScramSha256,
+    #[serde(rename = "SCRAM-SHA512", alias = "scram-sha512")]
+    ScramSha512,
+    #[serde(rename = "EXTERNAL", alias = "external")]
+    External,
+}
+
+type HmacFn = fn(&[u8], &[u8]) -> Vec<u8>;
+type HashFn = fn(&[u8]) -> Vec<u8>;
+
+impl SaslMechanism {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Plain => "PLAIN",
+            Self::ScramSha1 => "SCRAM-SHA1",
+            Self::ScramSha256 => "SCRAM-SHA256",
+            Self::ScramSha512 => "SCRAM-SHA512",
+            Self::External => "EXTERNAL",
         }
-    } else {
-        Err(ClientError::RequestFailed {
-            reason: None,
-            key: None,
-        })
-    };
+    }
 
-    match completetx.send(result) {
-        Ok(()) => {}
-        Err(_e) => {
-            warn!("select bucket receive failed");
+    // The strongest SCRAM mechanism out of those listed by the server.
+    fn preferred(supported: &[String]) -> Option<SaslMechanism> {
+        [Self::ScramSha512, Self::ScramSha256, Self::ScramSha1]
+            .iter()
+            .copied()
+            .find(|m| supported.iter().any(|s| s == m.name()))
+    }
+
+    fn scram_functions(&self) -> Option<(HmacFn, HashFn)> {
+        match self {
+            Self::Plain | Self::External => None,
+            Self::ScramSha1 => Some((compute_hmac::<Hmac<Sha1>>, compute_hash::<Sha1>)),
+            Self::ScramSha256 => Some((compute_hmac::<Hmac<Sha256>>, compute_hash::<Sha256>)),
+            Self::ScramSha512 => Some((compute_hmac::<Hmac<Sha512>>, compute_hash::<Sha512>)),
         }
-    };
+    }
+}
+
+impl Display for SaslMechanism {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+fn compute_hmac<M: Mac + KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn compute_hash<D: Digest>(data: &[u8]) -> Vec<u8> {
+    D::digest(data).to_vec()
+}
+
+// The client side of a SCRAM exchange as described in RFC 5802, without channel binding.
+struct ScramClient {
+    hmac: HmacFn,
+    hash: HashFn,
+    password: String,
+    nonce: String,
+    client_first_bare: String,
+    server_signature: Option<Vec<u8>>,
+}
+
+impl ScramClient {
+    fn new(hmac: HmacFn, hash: HashFn, username: &str, password: String, nonce: String) -> Self {
+        let username = username.replace('=', "=3D").replace(',', "=2C");
+        Self {
+            hmac,
+            hash,
+            password,
+            client_first_bare: format!("n={},r={}", username, nonce),
+            nonce,
+            server_signature: None,
+        }
+    }
+
+    fn client_first(&self) -> String {
+        format!("n,,{}", self.client_first_bare)
+    }
+
+    fn client_final(&mut self, server_first: &str) -> Result<String, ClientError> {
+        let mut nonce = None;
+        let mut salt = None;
+        let mut iterations = None;
+        for attr in server_first.split(',') {
+            match attr.split_once('=') {
+                Some(("r", v)) => nonce = Some(v),
+                Some(("s", v)) => salt = BASE64_STANDARD.decode(v).ok(),
+                Some(("i", v)) => iterations = v.parse::<u32>().ok().filter(|i| *i > 0),
+                _ => {}
+            }
+        }
+        let (nonce, salt, iterations) = match (nonce, salt, iterations) {
+            (Some(n), Some(s), Some(i)) if n.starts_with(&self.nonce) => (n, s, i),
+            _ => {
+                return Err(scram_error(format!(
+                    "invalid server first message {}",
+                    server_first
+                )))
+            }
+        };
+
+        let salted_password = pbkdf2(self.hmac, self.password.as_bytes(), &salt, iterations);
+        let client_key = (self.hmac)(&salted_password, b"Client Key");
+        let stored_key = (self.hash)(&client_key);
+        let server_key = (self.hmac)(&salted_password, b"Server Key");
+
+        let client_final_without_proof = format!("c=biws,r={}", nonce);
+        let auth_message = format!(
+            "{},{},{}",
+            self.client_first_bare, server_first, client_final_without_proof
+        );
+        let client_signature = (self.hmac)(&stored_key, auth_message.as_bytes());
+        let proof: Vec<u8> = client_key
+            .iter()
+            .zip(client_signature.iter())
+            .map(|(k, s)| k ^ s)
+            .collect();
+        self.server_signature = Some((self.hmac)(&server_key, auth_message.as_bytes()));
+
+        Ok(format!(
+            "{},p={}",
+            client_final_without_proof,
+            BASE64_STANDARD.encode(proof)
+        ))
+    }
+
+    // Checks that the server knows the password too, otherwise we could be talking to anyone.
+    fn verify_server_final(&self, server_final: &str) -> Result<(), ClientError> {
+        if let Some(e) = server_final.strip_prefix("e=") {
+            return Err(scram_error(format!(
+                "server rejected authentication: {}",
+                e
+            )));
+        }
+
+        let signature = server_final
+            .strip_prefix("v=")
+            .and_then(|v| BASE64_STANDARD.decode(v).ok());
+        match (signature, &self.server_signature) {
+            (Some(signature), Some(expected)) if &signature == expected => Ok(()),
+            _ => Err(scram_error("server signature did not match".to_string())),
+        }
+    }
+}
+
+fn scram_error(reason: String) -> ClientError {
+    ClientError::AuthError {
+        reason: Some(format!("SCRAM authentication failed, {}", reason)),
+    }
+}
+
+// PBKDF2 with a derived key the length of the HMAC output, which only ever needs the first block.
+fn pbkdf2(hmac: HmacFn, password: &[u8], salt: &[u8], iterations: u32) -> Vec<u8> {
+    let mut block = salt.to_vec();
+    block.extend_from_slice(&1u32.to_be_bytes());
+
+    let mut u = hmac(password, &block);
+    let mut result = u.clone();
+    for _ in 1..iterations {
+        u = hmac(password, &u);
+        for (r, b) in result.iter_mut().zip(u.iter()) {
+            *r ^= b;
+        }
+    }
+
+    result
 }
 
 #[derive(Debug)]
@@ -1027,3 +1472,90 @@ impl TryFrom<u16> for ServerFeature {
 //     #[serde(rename = "constant")]
 //     Constant,
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scram_exchange(
+        mechanism: SaslMechanism,
+        nonce: &str,
+        server_first: &str,
+        server_final: &str,
+    ) -> Result<String, ClientError> {
+        let (hmac, hash) = mechanism.scram_functions().unwrap();
+        let mut scram = ScramClient::new(hmac, hash, "user", "pencil".into(), nonce.into());
+        assert_eq!(format!("n,,n=user,r={}", nonce), scram.client_first());
+
+        let client_final = scram.client_final(server_first)?;
+        scram.verify_server_final(server_final)?;
+        Ok(client_final)
+    }
+
+    #[test]
+    fn scram_sha1_rfc5802() {
+        let client_final = scram_exchange(
+            SaslMechanism::ScramSha1,
+            "fyko+d2lbbFgONRv9qkxdawL",
+            "r=fyko+d2lbbFgONRv9qkxdawL3rfcNHYJY1ZVvWVs7j,s=QSXCR+Q6sek8bf92,i=4096",
+            "v=rmF9pqV8S7suAoZWja4dJRkFsKQ=",
+        )
+        .unwrap();
+
+        assert_eq!(
+            "c=biws,r=fyko+d2lbbFgONRv9qkxdawL3rfcNHYJY1ZVvWVs7j,p=v0X8v3Bz2T0CJGbJQyF0X+HI4Ts=",
+            client_final
+        );
+    }
+
+    #[test]
+    fn scram_sha256_rfc7677() {
+        let client_final = scram_exchange(
+            SaslMechanism::ScramSha256,
+            "rOprNGfwEbeRWgbNEkqO",
+            "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096",
+            "v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=",
+        )
+        .unwrap();
+
+        assert_eq!(
+            "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ=",
+            client_final
+        );
+    }
+
+    #[test]
+    fn scram_rejects_bad_server_messages() {
+        // The server nonce must extend the one we sent.
+        assert!(scram_exchange(
+            SaslMechanism::ScramSha256,
+            "rOprNGfwEbeRWgbNEkqO",
+            "r=somebodyelse,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096",
+            "v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=",
+        )
+        .is_err());
+
+        assert!(scram_exchange(
+            SaslMechanism::ScramSha256,
+            "rOprNGfwEbeRWgbNEkqO",
+            "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096",
+            "v=rmF9pqV8S7suAoZWja4dJRkFsKQ=",
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn sasl_prefers_strongest_scram() {
+        let supported = |s: &str| s.split(' ').map(String::from).collect::<Vec<String>>();
+
+        assert_eq!(
+            Some(SaslMechanism::ScramSha512),
+            SaslMechanism::preferred(&supported("SCRAM-SHA1 SCRAM-SHA512 SCRAM-SHA256 PLAIN"))
+        );
+        assert_eq!(
+            Some(SaslMechanism::ScramSha1),
+            SaslMechanism::preferred(&supported("PLAIN SCRAM-SHA1"))
+        );
+        assert_eq!(None, SaslMechanism::preferred(&supported("PLAIN")));
+    }
+}
@@ -3,21 +3,32 @@ use crate::client::crc::cb_vb_map;
 use crate::client::error::ClientError;
 use crate::client::http_client::{Config, PingResponse, ServiceType};
 use crate::client::http_handler::HTTPHandler;
-use crate::client::kv::KvEndpoint;
+use crate::client::kv::{KvEndpoint, SaslMechanism};
+use crate::client::proxy::ProxyConfig;
+use crate::client::timings::{OperationTimings, SlowOperationThreshold};
 use crate::client::{protocol, HTTPClient};
+use crate::connection_string::Network;
 use crate::RustTlsConfig;
 use bytes::{Buf, Bytes};
+use futures::future::join_all;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use log::{debug, trace};
 use nu_protocol::Signals;
 use serde::Deserialize;
 use serde_json::json;
+use std::convert::TryFrom;
 use std::future::Future;
+use std::ops::Mul;
 use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use std::{collections::HashMap, ops::Sub};
+use tokio::runtime::Handle;
 use tokio::select;
-use tokio::time::{sleep, Instant, Sleep};
+use tokio::time::{sleep, timeout_at, Instant, Sleep};
+
+const RETRY_BACKOFF: Duration = Duration::from_millis(50);
 
 #[derive(Debug)]
 pub struct KvResponse {
@@ -25,6 +36,7 @@ pub struct KvResponse {
     cas: u64,
     key: String,
     extras: Option<Bytes>,
+    timings: OperationTimings,
 }
 
 impl KvResponse {
@@ -43,21 +55,41 @@ impl KvResponse {
     pub fn extras(&mut self) -> Option<Bytes> {
         self.extras.take()
     }
+
+    pub fn timings(&self) -> &OperationTimings {
+        &self.timings
+    }
 }
 
 pub struct KvClient {
-    endpoints: HashMap<String, KvEndpoint>,
-    config: BucketConfig,
-    tls_enabled: bool,
+    endpoints: RwLock<HashMap<String, Arc<KvEndpoint>>>,
+    config: RwLock<BucketConfig>,
+    seeds: Vec<String>,
+    username: String,
+    password: String,
+    tls_config: Option<RustTlsConfig>,
+    sasl_mechanism: Option<SaslMechanism>,
+    network: Network,
+    proxy: Option<ProxyConfig>,
+    bucket: String,
+    slow_operation_threshold: SlowOperationThreshold,
+    // The runtime the client was connected on, endpoints for nodes which join later are connected
+    // on it too so that they live as long as the rest.
+    runtime: Handle,
 }
 
 impl KvClient {
+    #[allow(clippy::too_many_arguments)]
     pub async fn connect(
         seeds: Vec<String>,
         username: String,
         password: String,
         tls_config: Option<RustTlsConfig>,
+        sasl_mechanism: Option<SaslMechanism>,
+        network: Network,
+        proxy: Option<ProxyConfig>,
         bucket: String,
+        slow_operation_threshold: SlowOperationThreshold,
         deadline: Instant,
         signals: Signals,
     ) -> Result<Self, ClientError> {
@@ -71,7 +103,12 @@ impl KvClient {
         let ctrlc_fut = CtrlcFuture::new(signals.clone());
         tokio::pin!(ctrlc_fut);
 
-        let http_agent = HTTPHandler::new(username.clone(), password.clone(), tls_config.clone());
+        let http_agent = HTTPHandler::new(
+            username.clone(),
+            password.clone(),
+            tls_config.clone(),
+            proxy.clone(),
+        );
         let config: BucketConfig = HTTPClient::get_config(
             &seeds,
             tls_config.is_some(),
@@ -83,20 +120,21 @@ impl KvClient {
         .await?;
 
         let mut workers = FuturesUnordered::new();
-        for addr in config.key_value_seeds(tls_config.is_some()) {
+        for addr in config.key_value_seeds(tls_config.is_some(), network) {
             let hostname = addr.0.clone();
             let port = addr.1;
             let u = username.clone();
             let p = password.clone();
             let b = bucket.clone();
             let tls = tls_config.clone();
+            let proxy = proxy.clone();
 
             workers.push(tokio::spawn(async move {
-                KvEndpoint::connect(hostname, port, u, p, b, tls).await
+                KvEndpoint::connect(hostname, port, u, p, b, tls, sasl_mechanism, proxy).await
             }));
         }
 
-        let mut endpoints = HashMap::new();
+        let mut endpoints: HashMap<String, Arc<KvEndpoint>> = HashMap::new();
         loop {
             let endpoint = select! {
                 res = workers.next() => {
@@ -116,35 +154,177 @@ impl KvClient {
                 () = &mut ctrlc_fut => Err(ClientError::Cancelled{key: None}),
                 else => {break}
             }?;
-            endpoints.insert(endpoint.remote(), endpoint);
+            endpoints.insert(endpoint.remote(), Arc::new(endpoint));
         }
 
+        debug!(
+            "Connected to bucket {} at config revision {}",
+            &bucket,
+            config.revision()
+        );
+
         Ok(Self {
-            config,
-            endpoints,
-            tls_enabled: tls_config.is_some(),
+            config: RwLock::new(config),
+            endpoints: RwLock::new(endpoints),
+            seeds,
+            username,
+            password,
+            tls_config,
+            sasl_mechanism,
+            network,
+            proxy,
+            bucket,
+            slow_operation_threshold,
+            runtime: Handle::current(),
         })
     }
 
-    fn partition_for_key(&self, key: String) -> u32 {
-        let num_partitions = self.config.vbucket_server_map.vbucket_map.len() as u32;
+    fn tls_enabled(&self) -> bool {
+        self.tls_config.is_some()
+    }
 
-        cb_vb_map(key.as_bytes().to_vec(), num_partitions)
+    pub fn num_partitions(&self) -> u32 {
+        self.config
+            .read()
+            .unwrap()
+            .vbucket_server_map
+            .vbucket_map
+            .len() as u32
     }
 
-    fn node_for_partition(&self, partition: u32) -> (String, u32) {
-        let seeds = self.config.key_value_seeds(self.tls_enabled);
-        let node = self.config.vbucket_server_map.vbucket_map[partition as usize][0];
+    pub fn partition_for_key(&self, key: String) -> u32 {
+        cb_vb_map(key.as_bytes().to_vec(), self.num_partitions())
+    }
 
-        let seed = &seeds[node as usize];
-        let addr = seed.0.clone();
-        let port = seed.1;
+    // Finds the endpoint of the node owning the partition, there may not be one while a node is
+    // being failed over or has not been connected to yet.
+    fn endpoint_for_partition(&self, partition: u32) -> Option<Arc<KvEndpoint>> {
+        // The config guard is dropped before locking the endpoints, apply_config takes the two
+        // locks the other way round.
+        let address = {
+            let config = self.config.read().unwrap();
+            let seeds = config.key_value_seeds(self.tls_enabled(), self.network);
+            let node = *config
+                .vbucket_server_map
+                .vbucket_map
+                .get(partition as usize)?
+                .first()?;
+            let (addr, port) = seeds.get(usize::try_from(node).ok()?)?;
+            format!("{}:{}", addr, port)
+        };
+
+        self.endpoints.read().unwrap().get(&address).cloned()
+    }
+
+    // Applies a config pushed by the server in a NOT_MY_VBUCKET response, falling back to fetching
+    // the config over HTTP when the response did not carry one.
+    async fn refresh_config(
+        &self,
+        pushed: Option<Bytes>,
+        pushed_by: &str,
+        deadline: Instant,
+        signals: Signals,
+    ) -> Result<(), ClientError> {
+        let loaded_from = self.config.read().unwrap().loaded_from.clone();
+        let pushed = pushed.filter(|b| !b.is_empty()).and_then(|body| {
+            match BucketConfig::from_pushed(&body, pushed_by, loaded_from) {
+                Ok(config) => Some(config),
+                Err(e) => {
+                    debug!("Could not parse config from not my vbucket response: {}", e);
+                    None
+                }
+            }
+        });
+
+        let config = match pushed {
+            Some(config) => config,
+            None => {
+                let http_agent = HTTPHandler::new(
+                    self.username.clone(),
+                    self.password.clone(),
+                    self.tls_config.clone(),
+                    self.proxy.clone(),
+                );
+                HTTPClient::get_config(
+                    &self.seeds,
+                    self.tls_enabled(),
+                    &http_agent,
+                    self.bucket.clone(),
+                    deadline,
+                    signals,
+                )
+                .await?
+            }
+        };
 
-        (addr, port)
+        self.apply_config(config).await
+    }
+
+    async fn apply_config(&self, config: BucketConfig) -> Result<(), ClientError> {
+        if !config.is_newer_than(&self.config.read().unwrap()) {
+            return Ok(());
+        }
+
+        let seeds: Vec<String> = config
+            .key_value_seeds(self.tls_enabled(), self.network)
+            .iter()
+            .map(|(addr, port)| format!("{}:{}", addr, port))
+            .collect();
+
+        let missing: Vec<(String, u32)> = {
+            let endpoints = self.endpoints.read().unwrap();
+            config
+                .key_value_seeds(self.tls_enabled(), self.network)
+                .into_iter()
+                .filter(|(addr, port)| !endpoints.contains_key(&format!("{}:{}", addr, port)))
+                .collect()
+        };
+
+        let mut connected = vec![];
+        for (hostname, port) in missing {
+            let u = self.username.clone();
+            let p = self.password.clone();
+            let b = self.bucket.clone();
+            let tls = self.tls_config.clone();
+            let sasl_mechanism = self.sasl_mechanism;
+            let proxy = self.proxy.clone();
+            let endpoint = self
+                .runtime
+                .spawn(async move {
+                    KvEndpoint::connect(hostname, port, u, p, b, tls, sasl_mechanism, proxy).await
+                })
+                .await
+                .map_err(|e| ClientError::RequestFailed {
+                    reason: Some(e.to_string()),
+                    key: None,
+                })??;
+            connected.push(endpoint);
+        }
+
+        let mut endpoints = self.endpoints.write().unwrap();
+        let mut current = self.config.write().unwrap();
+        if !config.is_newer_than(&current) {
+            return Ok(());
+        }
+
+        for endpoint in connected {
+            endpoints.insert(endpoint.remote(), Arc::new(endpoint));
+        }
+        endpoints.retain(|addr, _| seeds.contains(addr));
+
+        debug!(
+            "Bucket {} config updated from revision {} to {}",
+            &self.bucket,
+            current.revision(),
+            config.revision()
+        );
+        *current = config;
+
+        Ok(())
     }
 
     pub async fn ping_all(
-        &mut self,
+        &self,
         deadline: Instant,
         signals: Signals,
     ) -> Result<Vec<PingResponse>, ClientError> {
@@ -159,14 +339,25 @@ impl KvClient {
         let ctrlc_fut = CtrlcFuture::new(signals.clone());
         tokio::pin!(ctrlc_fut);
 
+        let seeds = self
+            .config
+            .read()
+            .unwrap()
+            .key_value_seeds(self.tls_enabled(), self.network);
+
         let mut results: Vec<PingResponse> = Vec::new();
-        for seed in self.config.key_value_seeds(self.tls_enabled) {
+        for seed in seeds {
             let addr = seed.0.clone();
             let port = seed.1;
-            let ep = self
+            let ep = match self
                 .endpoints
+                .read()
+                .unwrap()
                 .get(format!("{}:{}", addr.clone(), port).as_str())
-                .unwrap();
+            {
+                Some(ep) => ep.clone(),
+                None => continue,
+            };
 
             let op = ep.noop();
 
@@ -200,11 +391,30 @@ impl KvClient {
         Ok(results)
     }
 
+    // Checks that every endpoint is still connected and answering, so that a client kept around
+    // between commands is not handed out after its connections have gone away.
+    pub async fn check_health(&self, deadline: Instant) -> bool {
+        let endpoints: Vec<Arc<KvEndpoint>> =
+            self.endpoints.read().unwrap().values().cloned().collect();
+        if endpoints.iter().any(|ep| ep.is_closed()) {
+            return false;
+        }
+
+        let noops = join_all(endpoints.iter().map(|ep| ep.noop()));
+        match timeout_at(deadline, noops).await {
+            Ok(results) => results.iter().all(|r| r.is_ok()),
+            Err(_) => false,
+        }
+    }
+
     pub fn is_non_default_scope_collection(scope: String, collection: String) -> bool {
         (!scope.is_empty() && scope != "_default")
             || (!collection.is_empty() && collection != "_default")
     }
 
+    // Sends the request to the node owning the partition of its key. Should the node no longer own
+    // it, as happens during a rebalance, the config is refreshed and the request retried on the new
+    // owner until it succeeds or the deadline passes.
     pub async fn request(
         &self,
         request: KeyValueRequest,
@@ -218,32 +428,87 @@ impl KvClient {
                 key: Some(request.key()),
             });
         }
-        let deadline = deadline.sub(now);
-        let deadline_sleep = sleep(deadline);
+        let deadline_sleep = sleep(deadline.sub(now));
         tokio::pin!(deadline_sleep);
 
         let ctrlc_fut = CtrlcFuture::new(signals.clone());
         tokio::pin!(ctrlc_fut);
 
-        let key = match request {
-            KeyValueRequest::Get { ref key } => key.clone(),
-            KeyValueRequest::Set { ref key, .. } => key.clone(),
-            KeyValueRequest::Insert { ref key, .. } => key.clone(),
-            KeyValueRequest::Replace { ref key, .. } => key.clone(),
-            KeyValueRequest::Remove { ref key, .. } => key.clone(),
-            KeyValueRequest::SubDocGet { ref key, .. } => key.clone(),
-            KeyValueRequest::SubdocMultiLookup { ref key, .. } => key.clone(),
-        };
-
+        let key = request.key();
         let partition = self.partition_for_key(key.clone());
-        let (addr, port) = self.node_for_partition(partition);
 
-        let ep = self
-            .endpoints
-            .get(format!("{}:{}", addr.clone(), port).as_str())
-            .unwrap();
+        let mut attempt: u32 = 0;
+        loop {
+            let (pushed, pushed_by) = match self.endpoint_for_partition(partition) {
+                Some(ep) => {
+                    let dispatched = Instant::now();
+                    let result = self
+                        .send_request(
+                            &ep,
+                            request.clone(),
+                            partition,
+                            cid,
+                            deadline_sleep.as_mut(),
+                            ctrlc_fut.as_mut(),
+                        )
+                        .await;
+
+                    match result {
+                        Err(ClientError::NotMyVbucket { config, .. }) => (config, ep.remote()),
+                        result => {
+                            let timings = OperationTimings::new(
+                                request.name(),
+                                ep.remote(),
+                                dispatched.elapsed(),
+                                now.elapsed(),
+                            );
+                            let threshold = self.slow_operation_threshold.get();
+                            let result = self.handle_op_result(result, timings.clone());
+                            match &result {
+                                Ok(response) => response.timings.record(threshold),
+                                Err(_) => timings.record(threshold),
+                            }
+                            return result;
+                        }
+                    }
+                }
+                None => (None, String::new()),
+            };
 
-        let result = match request {
+            attempt += 1;
+            debug!(
+                "Partition {} for key {} has moved, refreshing config (attempt {})",
+                partition, &key, attempt
+            );
+
+            let refresh = self.refresh_config(pushed, &pushed_by, deadline, signals.clone());
+            select! {
+                res = refresh => res?,
+                () = &mut deadline_sleep => return Err(ClientError::Timeout{key: Some(key)}),
+                () = &mut ctrlc_fut => return Err(ClientError::Cancelled{key: Some(key)}),
+            };
+
+            // The new owner may not have taken over the partition yet, so back off a little more
+            // with each attempt rather than hammering it.
+            let backoff = sleep(RETRY_BACKOFF.mul(attempt.min(10)));
+            select! {
+                () = backoff => {},
+                () = &mut deadline_sleep => return Err(ClientError::Timeout{key: Some(key)}),
+                () = &mut ctrlc_fut => return Err(ClientError::Cancelled{key: Some(key)}),
+            };
+        }
+    }
+
+    async fn send_request(
+        &self,
+        ep: &KvEndpoint,
+        request: KeyValueRequest,
+        partition: u32,
+        cid: u32,
+        deadline_sleep: Pin<&mut Sleep>,
+        ctrlc_fut: Pin<&mut CtrlcFuture>,
+    ) -> Result<(protocol::KvResponse, Option<String>), ClientError> {
+        match request {
             KeyValueRequest::Get { key } => {
                 let op = ep.get(key.clone(), partition as u16, cid);
 
@@ -286,14 +551,34 @@ impl KvClient {
                 self.handle_op_future(key, op, deadline_sleep, ctrlc_fut)
                     .await
             }
-        };
+            KeyValueRequest::LookupIn {
+                key,
+                specs,
+                access_deleted,
+            } => {
+                let op = ep.lookup_in(key.clone(), partition as u16, cid, specs, access_deleted);
+
+                self.handle_op_future(key, op, deadline_sleep, ctrlc_fut)
+                    .await
+            }
+            KeyValueRequest::MutateIn {
+                key,
+                specs,
+                cas,
+                doc_flags,
+            } => {
+                let op = ep.mutate_in(key.clone(), partition as u16, cid, specs, cas, doc_flags);
 
-        self.handle_op_result(result)
+                self.handle_op_future(key, op, deadline_sleep, ctrlc_fut)
+                    .await
+            }
+        }
     }
 
     fn handle_op_result(
         &self,
         result: Result<(protocol::KvResponse, Option<String>), ClientError>,
+        mut timings: OperationTimings,
     ) -> Result<KvResponse, ClientError> {
         match result {
             Ok(mut r) => {
@@ -304,18 +589,23 @@ impl KvClient {
                             let mut bytes = body.clone();
 
                             while !bytes.is_empty() {
-                                //Drop leading empty bytes
-                                bytes.get_u32();
-                                let len: usize = bytes.get_u16().into();
+                                let status = protocol::Status::from(bytes.get_u16());
+                                let len = bytes.get_u32() as usize;
                                 let temp = bytes.split_off(len);
 
-                                let value = match serde_json::from_slice(bytes.as_ref()) {
-                                    Ok(v) => v,
-                                    Err(e) => {
-                                        return Err(ClientError::RequestFailed {
-                                            reason: Some(e.to_string()),
-                                            key: r.1,
-                                        });
+                                // Paths which could not be fetched, e.g. a missing xattr, and the
+                                // empty body of a tombstone have no value.
+                                let value = if status != protocol::Status::Success || len == 0 {
+                                    serde_json::Value::Null
+                                } else {
+                                    match serde_json::from_slice(bytes.as_ref()) {
+                                        Ok(v) => v,
+                                        Err(e) => {
+                                            return Err(ClientError::RequestFailed {
+                                                reason: Some(e.to_string()),
+                                                key: r.1,
+                                            });
+                                        }
                                     }
                                 };
 
@@ -324,6 +614,7 @@ impl KvClient {
                             }
                             Some(json!(results))
                         }
+                        protocol::Opcode::SubdocMultiMutation => None,
                         _ => match serde_json::from_slice(body.as_ref()) {
                             Ok(v) => Some(v),
                             Err(e) => {
@@ -337,11 +628,13 @@ impl KvClient {
                 } else {
                     None
                 };
+                timings.set_server(r.0.server_duration());
                 Ok(KvResponse {
                     content,
                     cas: r.0.cas(),
                     key: r.1.unwrap_or_default(),
                     extras: r.0.extras(),
+                    timings,
                 })
             }
             Err(e) => Err(e),
@@ -381,19 +674,27 @@ impl KvClient {
         let ctrlc_fut = CtrlcFuture::new(signals.clone());
         tokio::pin!(ctrlc_fut);
 
-        let (addr, port) = self.node_for_partition(0);
         let ep = self
-            .endpoints
-            .get(format!("{}:{}", addr.clone(), port).as_str())
-            .unwrap();
+            .endpoint_for_partition(0)
+            .ok_or_else(|| ClientError::RequestFailed {
+                reason: Some("No node owns the first partition of the bucket".to_string()),
+                key: None,
+            })?;
 
         let op = ep.get_cid(scope_name, collection_name);
 
+        let dispatched = Instant::now();
         let resp = self
             .handle_op_future(None, op, deadline_sleep, ctrlc_fut)
             .await;
 
-        let mut result = self.handle_op_result(resp)?;
+        let timings = OperationTimings::new(
+            "get_collection_id",
+            ep.remote(),
+            dispatched.elapsed(),
+            dispatched.elapsed(),
+        );
+        let mut result = self.handle_op_result(resp, timings)?;
         match result.extras() {
             Some(mut e) => {
                 if e.len() < 12 {
@@ -436,7 +737,10 @@ impl KvClient {
 
 #[derive(Deserialize, Debug)]
 struct BucketConfig {
-    // rev: u64,
+    #[serde(default)]
+    rev: u64,
+    #[serde(alias = "revEpoch", default)]
+    rev_epoch: u64,
     #[serde(alias = "nodesExt")]
     nodes_ext: Vec<NodeExtConfig>,
     nodes: Vec<NodeConfig>,
@@ -452,83 +756,89 @@ impl Config for BucketConfig {
 }
 
 impl BucketConfig {
-    pub fn key_value_seeds(&self, tls: bool) -> Vec<(String, u32)> {
+    // Configs sent over KV refer to the node which sent them as $HOST. The config keeps the host
+    // it was originally loaded from so that the same network is used for the nodes.
+    fn from_pushed(
+        body: &[u8],
+        pushed_by: &str,
+        loaded_from: Option<String>,
+    ) -> Result<Self, serde_json::Error> {
+        let host = pushed_by
+            .rsplit_once(':')
+            .map_or(pushed_by, |(host, _)| host);
+        let content = String::from_utf8_lossy(body).replace("$HOST", host);
+
+        let mut config: BucketConfig = serde_json::from_str(&content)?;
+        config.loaded_from = loaded_from;
+        Ok(config)
+    }
+
+    // Configs from before epochs were introduced have none, which compares as epoch 0.
+    fn is_newer_than(&self, other: &BucketConfig) -> bool {
+        (self.rev_epoch, self.rev) > (other.rev_epoch, other.rev)
+    }
+
+    fn revision(&self) -> String {
+        format!("{}:{}", self.rev_epoch, self.rev)
+    }
+
+    pub fn key_value_seeds(&self, tls: bool, network: Network) -> Vec<(String, u32)> {
         let key = if tls { "kvSSL" } else { "kv" };
 
-        self.seeds(key)
+        self.seeds(key, network)
     }
 
-    fn seeds(&self, key: &str) -> Vec<(String, u32)> {
-        let len_nodes = self.nodes.len();
-        let default: Vec<(String, u32)> = self
-            .nodes_ext
+    fn seeds(&self, key: &str, network: Network) -> Vec<(String, u32)> {
+        let loaded_from = self.loaded_from.as_deref().unwrap_or_default();
+        let external = use_external_network(&self.nodes_ext, loaded_from, network);
+
+        self.nodes_ext
             .iter()
             .enumerate()
-            .filter(|&(i, node)| {
-                if i >= len_nodes {
-                    let hostname = if node.hostname.is_some() {
-                        node.hostname.as_ref().unwrap().clone()
-                    } else {
-                        self.loaded_from.as_ref().unwrap().clone()
-                    };
+            .filter_map(|(i, node)| {
+                if i >= self.nodes.len() {
                     debug!(
                         "Node {} present in nodes ext but not in nodes, skipping",
-                        hostname
+                        node.hostname.as_deref().unwrap_or(loaded_from)
                     );
-                    return false;
+                    return None;
                 }
-                node.services.contains_key(key)
-            })
-            .map(|node| {
-                let hostname = if node.1.hostname.is_some() {
-                    node.1.hostname.as_ref().unwrap().clone()
-                } else {
-                    self.loaded_from.as_ref().unwrap().clone()
-                };
-                (hostname, *node.1.services.get(key).unwrap())
+                node.address(key, external, loaded_from)
             })
-            .collect();
-
-        for seed in &default {
-            if seed.0 == self.loaded_from.as_ref().unwrap().clone() {
-                return default;
-            }
-        }
-
-        let external: Vec<(String, u32)> = self
-            .nodes_ext
-            .iter()
-            .filter(|node| {
-                if let Some(external_addresses) = node.alternate_addresses.get("external") {
-                    return external_addresses.ports.contains_key(key);
-                }
-
-                false
-            })
-            .map(|node| {
-                let address = node.alternate_addresses.get("external").unwrap();
-                let hostname = if address.hostname.is_some() {
-                    address.hostname.as_ref().unwrap().clone()
-                } else {
-                    self.loaded_from.as_ref().unwrap().clone()
-                };
-                (hostname, *address.ports.get(key).unwrap())
-            })
-            .collect();
+            .collect()
+    }
+}
 
-        for seed in &external {
-            if seed.0 == self.loaded_from.as_ref().unwrap().clone() {
-                return external;
-            }
+// Whether to connect to the external alternate addresses of the nodes rather than their default
+// ones. When left to auto detection they are used if the config was loaded from one of them, since
+// that is the network the shell can reach the cluster on.
+pub(crate) fn use_external_network(
+    nodes: &[NodeExtConfig],
+    loaded_from: &str,
+    network: Network,
+) -> bool {
+    match network {
+        Network::Default => false,
+        Network::External => true,
+        Network::Auto => {
+            let is_default = nodes
+                .iter()
+                .any(|node| node.hostname.as_deref().unwrap_or(loaded_from) == loaded_from);
+            !is_default
+                && nodes.iter().any(|node| {
+                    node.alternate_addresses
+                        .get("external")
+                        .and_then(|address| address.hostname.as_deref())
+                        == Some(loaded_from)
+                })
         }
-
-        default
     }
 }
 
 #[derive(Deserialize, Debug)]
 pub(crate) struct AlternateAddress {
     pub(crate) hostname: Option<String>,
+    #[serde(default)]
     pub(crate) ports: HashMap<String, u32>,
 }
 
@@ -542,6 +852,38 @@ pub(crate) struct NodeExtConfig {
     pub(crate) alternate_addresses: HashMap<String, AlternateAddress>,
 }
 
+impl NodeExtConfig {
+    // The address of a service on the node, or none if the node does not run it. An external
+    // address may leave out the hostname or ports where they are the same as the default ones, and
+    // nodes without one fall back to their default address so that node indexes stay the same.
+    pub(crate) fn address(
+        &self,
+        key: &str,
+        external: bool,
+        loaded_from: &str,
+    ) -> Option<(String, u32)> {
+        let port = *self.services.get(key)?;
+        let hostname = self.hostname.as_deref().unwrap_or(loaded_from).to_string();
+        if !external {
+            return Some((hostname, port));
+        }
+
+        match self.alternate_addresses.get("external") {
+            Some(address) => Some((
+                address.hostname.clone().unwrap_or(hostname),
+                address.ports.get(key).copied().unwrap_or(port),
+            )),
+            None => {
+                debug!(
+                    "Node {} has no external address, using its default address",
+                    hostname
+                );
+                Some((hostname, port))
+            }
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub(crate) struct NodeConfig {
     // pub(crate) hostname: Option<String>,
@@ -559,6 +901,7 @@ struct VBucketServerMap {
     vbucket_map: Vec<Vec<i32>>,
 }
 
+#[derive(Clone)]
 pub enum KeyValueRequest {
     Get {
         key: String,
@@ -589,9 +932,34 @@ pub enum KeyValueRequest {
         key: String,
         paths: Vec<String>,
     },
+    LookupIn {
+        key: String,
+        specs: Vec<LookupInSpec>,
+        access_deleted: bool,
+    },
+    MutateIn {
+        key: String,
+        specs: Vec<MutateInSpec>,
+        cas: u64,
+        doc_flags: u8,
+    },
 }
 
 impl KeyValueRequest {
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyValueRequest::Get { .. } => "get",
+            KeyValueRequest::Set { .. } => "upsert",
+            KeyValueRequest::Insert { .. } => "insert",
+            KeyValueRequest::Replace { .. } => "replace",
+            KeyValueRequest::Remove { .. } => "remove",
+            KeyValueRequest::SubDocGet { .. } => "lookup_in",
+            KeyValueRequest::SubdocMultiLookup { .. } => "lookup_in",
+            KeyValueRequest::LookupIn { .. } => "lookup_in",
+            KeyValueRequest::MutateIn { .. } => "mutate_in",
+        }
+    }
+
     pub fn key(&self) -> String {
         match self {
             KeyValueRequest::Get { key } => key.clone(),
@@ -601,6 +969,192 @@ impl KeyValueRequest {
             KeyValueRequest::Remove { key } => key.clone(),
             KeyValueRequest::SubDocGet { key, .. } => key.clone(),
             KeyValueRequest::SubdocMultiLookup { key, .. } => key.clone(),
+            KeyValueRequest::LookupIn { key, .. } => key.clone(),
+            KeyValueRequest::MutateIn { key, .. } => key.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum LookupInSpec {
+    Get { path: String, xattr: bool },
+    GetDoc,
+}
+
+impl LookupInSpec {
+    pub(crate) fn encoded(&self) -> (protocol::Opcode, u8, String) {
+        match self {
+            Self::Get { path, xattr } => {
+                let flags = if *xattr {
+                    protocol::SUBDOC_PATH_FLAG_XATTR
+                } else {
+                    0
+                };
+                (protocol::Opcode::SubdocGet, flags, path.clone())
+            }
+            Self::GetDoc => (protocol::Opcode::Get, 0, "".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MutateInSpec {
+    Upsert {
+        path: String,
+        value: Vec<u8>,
+        xattr: bool,
+        expand_macros: bool,
+    },
+    Remove {
+        path: String,
+        xattr: bool,
+    },
+    ArrayAppend {
+        path: String,
+        value: Vec<u8>,
+        xattr: bool,
+    },
+    SetDoc {
+        value: Vec<u8>,
+    },
+    DeleteDoc,
+}
+
+impl MutateInSpec {
+    pub(crate) fn encoded(&self) -> (protocol::Opcode, u8, String, Vec<u8>) {
+        match self {
+            Self::Upsert {
+                path,
+                value,
+                xattr,
+                expand_macros,
+            } => {
+                let mut flags = protocol::SUBDOC_PATH_FLAG_MKDIR_P;
+                if *xattr {
+                    flags |= protocol::SUBDOC_PATH_FLAG_XATTR;
+                }
+                if *expand_macros {
+                    flags |= protocol::SUBDOC_PATH_FLAG_EXPAND_MACROS;
+                }
+                (
+                    protocol::Opcode::SubdocDictUpsert,
+                    flags,
+                    path.clone(),
+                    value.clone(),
+                )
+            }
+            Self::Remove { path, xattr } => {
+                let flags = if *xattr {
+                    protocol::SUBDOC_PATH_FLAG_XATTR
+                } else {
+                    0
+                };
+                (protocol::Opcode::SubdocDelete, flags, path.clone(), vec![])
+            }
+            Self::ArrayAppend { path, value, xattr } => {
+                let mut flags = protocol::SUBDOC_PATH_FLAG_MKDIR_P;
+                if *xattr {
+                    flags |= protocol::SUBDOC_PATH_FLAG_XATTR;
+                }
+                (
+                    protocol::Opcode::SubdocArrayPushLast,
+                    flags,
+                    path.clone(),
+                    value.clone(),
+                )
+            }
+            Self::SetDoc { value } => (protocol::Opcode::Set, 0, "".to_string(), value.clone()),
+            Self::DeleteDoc => (protocol::Opcode::Remove, 0, "".to_string(), vec![]),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pushed_config(rev_epoch: u64, rev: u64) -> String {
+        format!(
+            r#"{{"rev":{},"revEpoch":{},"nodes":[{{}},{{}}],
+            "nodesExt":[{{"services":{{"kv":11210}},"hostname":"$HOST"}},{{"services":{{"kv":11210}},"hostname":"10.0.0.2"}}],
+            "vBucketServerMap":{{"vBucketMap":[[1,0],[0,1]]}}}}"#,
+            rev, rev_epoch
+        )
+    }
+
+    #[test]
+    fn from_pushed_replaces_host() {
+        let config = BucketConfig::from_pushed(
+            pushed_config(1, 10).as_bytes(),
+            "10.0.0.1:11210",
+            Some("10.0.0.1".to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                ("10.0.0.1".to_string(), 11210),
+                ("10.0.0.2".to_string(), 11210)
+            ],
+            config.key_value_seeds(false, Network::Auto)
+        );
+        assert_eq!("1:10", config.revision());
+    }
+
+    #[test]
+    fn key_value_seeds_use_external_addresses() {
+        let content = r#"{"nodes":[{},{}],
+            "nodesExt":[
+                {"services":{"kv":11210,"kvSSL":11207},"hostname":"10.0.0.1",
+                 "alternateAddresses":{"external":{"hostname":"node1.example.com","ports":{"kvSSL":31207}}}},
+                {"services":{"kv":11210,"kvSSL":11207},"hostname":"10.0.0.2",
+                 "alternateAddresses":{"external":{"hostname":"node2.example.com"}}}],
+            "vBucketServerMap":{"vBucketMap":[[0],[1]]}}"#;
+        let mut config: BucketConfig = serde_json::from_str(content).unwrap();
+
+        config.set_loaded_from("node2.example.com".to_string());
+        assert_eq!(
+            vec![
+                ("node1.example.com".to_string(), 31207),
+                ("node2.example.com".to_string(), 11207)
+            ],
+            config.key_value_seeds(true, Network::Auto)
+        );
+        assert_eq!(
+            vec![
+                ("10.0.0.1".to_string(), 11207),
+                ("10.0.0.2".to_string(), 11207)
+            ],
+            config.key_value_seeds(true, Network::Default)
+        );
+
+        config.set_loaded_from("10.0.0.1".to_string());
+        assert_eq!(
+            vec![
+                ("10.0.0.1".to_string(), 11210),
+                ("10.0.0.2".to_string(), 11210)
+            ],
+            config.key_value_seeds(false, Network::Auto)
+        );
+        assert_eq!(
+            vec![
+                ("node1.example.com".to_string(), 11210),
+                ("node2.example.com".to_string(), 11210)
+            ],
+            config.key_value_seeds(false, Network::External)
+        );
+    }
+
+    #[test]
+    fn is_newer_than_compares_epoch_first() {
+        let parse = |epoch, rev| {
+            BucketConfig::from_pushed(pushed_config(epoch, rev).as_bytes(), "10.0.0.1:11210", None)
+                .unwrap()
+        };
+
+        assert!(parse(1, 11).is_newer_than(&parse(1, 10)));
+        assert!(parse(2, 1).is_newer_than(&parse(1, 10)));
+        assert!(!parse(1, 10).is_newer_than(&parse(1, 10)));
+        assert!(!parse(1, 99).is_newer_than(&parse(2, 1)));
+    }
+}
@@ -4,8 +4,39 @@ use crate::client::gemini_client::GeminiClient;
 use crate::client::openai_client::OpenAIClient;
 use crate::state::{Provider, State};
 use nu_protocol::ShellError;
+use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    User,
+    Assistant,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ChatMessage {
+    role: ChatRole,
+    content: String,
+}
+
+impl ChatMessage {
+    pub fn new(role: ChatRole, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+        }
+    }
+
+    pub fn role(&self) -> ChatRole {
+        self.role.clone()
+    }
+
+    pub fn content(&self) -> String {
+        self.content.clone()
+    }
+}
+
 pub enum LLMClients {
     OpenAI(OpenAIClient),
     Gemini(GeminiClient),
@@ -38,12 +69,39 @@ impl LLMClients {
         &self,
         question: String,
         context: Vec<String>,
+        history: &[ChatMessage],
         model: String,
     ) -> Result<String, ShellError> {
         match self {
-            Self::OpenAI(c) => c.ask(question, context, model).await,
-            Self::Gemini(c) => c.ask(question, context, model).await,
-            Self::Bedrock(c) => c.ask(question, context, model).await,
+            Self::OpenAI(c) => c.ask(question, context, history, model).await,
+            Self::Gemini(c) => c.ask(question, context, history, model).await,
+            Self::Bedrock(c) => c.ask(question, context, history, model).await,
+        }
+    }
+
+    // Passes each chunk of the answer to on_chunk as it is generated, returning the full answer
+    // once the response is complete.
+    pub async fn ask_stream(
+        &self,
+        question: String,
+        context: Vec<String>,
+        history: &[ChatMessage],
+        model: String,
+        on_chunk: impl FnMut(String),
+    ) -> Result<String, ShellError> {
+        match self {
+            Self::OpenAI(c) => {
+                c.ask_stream(question, context, history, model, on_chunk)
+                    .await
+            }
+            Self::Gemini(c) => {
+                c.ask_stream(question, context, history, model, on_chunk)
+                    .await
+            }
+            Self::Bedrock(c) => {
+                c.ask_stream(question, context, history, model, on_chunk)
+                    .await
+            }
         }
     }
 
@@ -1,17 +1,28 @@
 pub use crate::client::cloud::CapellaClient;
 pub use crate::client::cloud::CAPELLA_SRV_SUFFIX;
-pub use crate::client::cloud::CLOUD_URL;
+pub use crate::client::cloud::{CLOUD_URL, DEFAULT_IP_LOOKUP_ENDPOINT};
 pub use crate::client::error::ClientError;
 pub use crate::client::http_client::{
-    AnalyticsQueryRequest, Endpoint, HTTPClient, ManagementRequest, QueryRequest,
-    QueryTransactionRequest, TextSearchQueryRequest, VectorSearchQueryRequest,
+    AnalyticsQueryOptions, AnalyticsQueryRequest, Endpoint, HTTPClient, ManagementRequest,
+    QueryRequest, QueryTransactionRequest, TextSearchQueryRequest, VectorSearchQueryRequest,
 };
-pub use crate::client::kv_client::{KeyValueRequest, KvClient, KvResponse};
+pub use crate::client::kv::SaslMechanism;
+pub use crate::client::kv_client::{
+    KeyValueRequest, KvClient, KvResponse, LookupInSpec, MutateInSpec,
+};
+pub use crate::client::protocol::{
+    SUBDOC_DOC_FLAG_ACCESS_DELETED, SUBDOC_DOC_FLAG_ADD, SUBDOC_DOC_FLAG_CREATE_AS_DELETED,
+    SUBDOC_DOC_FLAG_MKDOC, SUBDOC_DOC_FLAG_REVIVE_DOCUMENT,
+};
+pub use crate::client::proxy::ProxyConfig;
+pub use crate::client::timings::{OperationTimings, SlowOperationThreshold};
 pub use crate::client::tls::RustTlsConfig;
+use crate::connection_string::Network;
 use log::debug;
 
 use nu_protocol::Signals;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
 use tokio::time::Instant;
 
 extern crate utilities;
@@ -31,23 +42,36 @@ mod kv_client;
 mod llm_client;
 mod openai_client;
 mod protocol;
+mod proxy;
+mod timings;
 mod tls;
 
-pub use llm_client::LLMClients;
+pub use llm_client::{ChatMessage, ChatRole, LLMClients};
 
 pub struct Client {
     seeds: Vec<String>,
     username: String,
     password: String,
     tls_config: Option<RustTlsConfig>,
+    sasl_mechanism: Option<SaslMechanism>,
+    network: Network,
+    proxy: Option<ProxyConfig>,
+    slow_operation_threshold: SlowOperationThreshold,
+    // Kept for the life of the client so that HTTP connections to the cluster are reused.
+    http_client: Arc<HTTPClient>,
 }
 
 impl Client {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         seeds: Vec<String>,
         username: String,
         password: String,
         tls_config: Option<RustTlsConfig>,
+        sasl_mechanism: Option<SaslMechanism>,
+        network: Network,
+        proxy: Option<ProxyConfig>,
+        slow_operation_threshold: SlowOperationThreshold,
     ) -> Self {
         let seeds = if Client::might_be_srv(&seeds) {
             match utilities::try_lookup_srv(seeds[0].clone()) {
@@ -61,21 +85,31 @@ impl Client {
             seeds
         };
 
+        let http_client = Arc::new(HTTPClient::new(
+            seeds.clone(),
+            username.clone(),
+            password.clone(),
+            tls_config.clone(),
+            network,
+            proxy.clone(),
+            slow_operation_threshold.clone(),
+        ));
+
         Self {
             seeds,
             username,
             password,
             tls_config,
+            sasl_mechanism,
+            network,
+            proxy,
+            slow_operation_threshold,
+            http_client,
         }
     }
 
-    pub fn http_client(&self) -> HTTPClient {
-        HTTPClient::new(
-            self.seeds.clone(),
-            self.username.clone(),
-            self.password.clone(),
-            self.tls_config.clone(),
-        )
+    pub fn http_client(&self) -> Arc<HTTPClient> {
+        self.http_client.clone()
     }
 
     pub async fn key_value_client(
@@ -89,7 +123,11 @@ impl Client {
             self.username.clone(),
             self.password.clone(),
             self.tls_config.clone(),
+            self.sasl_mechanism,
+            self.network,
+            self.proxy.clone().or_else(ProxyConfig::socks_from_env),
             bucket.clone(),
+            self.slow_operation_threshold.clone(),
             deadline,
             signals,
         )
@@ -1,10 +1,13 @@
 use crate::cli::{generic_error, llm_api_key_missing};
-use async_openai::config::OPENAI_API_BASE;
+use crate::client::{ChatMessage, ChatRole};
+use async_openai::config::{OpenAIConfig, OPENAI_API_BASE};
 use async_openai::types::{
-    ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
-    ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequestArgs,
+    ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs,
 };
 use async_openai::{types::CreateEmbeddingRequestArgs, Client};
+use futures::StreamExt;
 use log::debug;
 use nu_protocol::ShellError;
 use tiktoken_rs::p50k_base;
@@ -85,11 +88,7 @@ impl OpenAIClient {
         dim: Option<usize>,
         model: String,
     ) -> Result<Vec<Vec<f32>>, ShellError> {
-        let client = Client::with_config(
-            async_openai::config::OpenAIConfig::default()
-                .with_api_key(self.api_key.clone())
-                .with_api_base(self.api_base.clone()),
-        );
+        let client = self.client();
 
         if log::log_enabled!(log::Level::Debug) {
             let bpe = p50k_base().unwrap();
@@ -138,47 +137,14 @@ impl OpenAIClient {
         &self,
         question: String,
         context: Vec<String>,
+        history: &[ChatMessage],
         model: String,
     ) -> Result<String, ShellError> {
-        let mut messages: Vec<ChatCompletionRequestMessage> = vec![];
-
-        // Primes the model to respond appropriately
-        messages.push(
-            ChatCompletionRequestSystemMessageArgs::default()
-                .content("You are a helpful assistant.")
-                .build()
-                .unwrap()
-                .into(),
-        );
-
-        for ctx in context {
-            messages.push(
-                ChatCompletionRequestSystemMessageArgs::default()
-                    .content(ctx)
-                    .build()
-                    .unwrap()
-                    .into(),
-            )
-        }
-
-        messages.push(
-            ChatCompletionRequestUserMessageArgs::default()
-                .content(question)
-                .build()
-                .unwrap()
-                .into(),
-        );
-
-        let client = Client::with_config(
-            async_openai::config::OpenAIConfig::default()
-                .with_api_key(self.api_key.clone())
-                .with_api_base(self.api_base.clone()),
-        );
-
+        let client = self.client();
         let request = CreateChatCompletionRequestArgs::default()
             .max_tokens(512u16)
             .model(model)
-            .messages(messages)
+            .messages(chat_messages(question, context, history))
             .build()
             .unwrap();
 
@@ -197,4 +163,115 @@ impl OpenAIClient {
 
         Ok(answer)
     }
+
+    pub async fn ask_stream(
+        &self,
+        question: String,
+        context: Vec<String>,
+        history: &[ChatMessage],
+        model: String,
+        mut on_chunk: impl FnMut(String),
+    ) -> Result<String, ShellError> {
+        let client = self.client();
+        let request = CreateChatCompletionRequestArgs::default()
+            .max_tokens(512u16)
+            .model(model)
+            .messages(chat_messages(question, context, history))
+            .build()
+            .unwrap();
+
+        let mut stream = match client.chat().create_stream(request).await {
+            Ok(s) => s,
+            Err(e) => {
+                return Err(generic_error(
+                    format!("failed to execute request: {}", e),
+                    None,
+                    None,
+                ));
+            }
+        };
+
+        let mut answer = String::new();
+        while let Some(response) = stream.next().await {
+            let response = match response {
+                Ok(r) => r,
+                Err(e) => {
+                    return Err(generic_error(
+                        format!("failed to read streamed response: {}", e),
+                        None,
+                        None,
+                    ));
+                }
+            };
+
+            for choice in response.choices {
+                if let Some(content) = choice.delta.content {
+                    answer.push_str(&content);
+                    on_chunk(content);
+                }
+            }
+        }
+
+        Ok(answer)
+    }
+
+    fn client(&self) -> Client<OpenAIConfig> {
+        Client::with_config(
+            OpenAIConfig::default()
+                .with_api_key(self.api_key.clone())
+                .with_api_base(self.api_base.clone()),
+        )
+    }
+}
+
+fn chat_messages(
+    question: String,
+    context: Vec<String>,
+    history: &[ChatMessage],
+) -> Vec<ChatCompletionRequestMessage> {
+    let mut messages: Vec<ChatCompletionRequestMessage> = vec![];
+
+    // Primes the model to respond appropriately
+    messages.push(
+        ChatCompletionRequestSystemMessageArgs::default()
+            .content("You are a helpful assistant.")
+            .build()
+            .unwrap()
+            .into(),
+    );
+
+    for ctx in context {
+        messages.push(
+            ChatCompletionRequestSystemMessageArgs::default()
+                .content(ctx)
+                .build()
+                .unwrap()
+                .into(),
+        )
+    }
+
+    for message in history {
+        messages.push(match message.role() {
+            ChatRole::User => ChatCompletionRequestUserMessageArgs::default()
+                .content(message.content())
+                .build()
+                .unwrap()
+                .into(),
+            ChatRole::Assistant => ChatCompletionRequestAssistantMessageArgs::default()
+                .content(message.content())
+                .build()
+                .unwrap()
+                .into(),
+        });
+    }
+
+    messages.push(
+        ChatCompletionRequestUserMessageArgs::default()
+            .content(question)
+            .build()
+            .unwrap()
+            .into(),
+    );
+
+    messages
 }
@@ -4,8 +4,21 @@ use bytes::{Buf, BufMut, Bytes, BytesMut};
 use std::convert::TryFrom;
 use std::fmt::Write as _;
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 pub static HEADER_SIZE: usize = 24;
+
+// Sub-document path flags, set per path on lookups and mutations.
+pub static SUBDOC_PATH_FLAG_MKDIR_P: u8 = 0x01;
+pub static SUBDOC_PATH_FLAG_XATTR: u8 = 0x04;
+pub static SUBDOC_PATH_FLAG_EXPAND_MACROS: u8 = 0x10;
+
+// Sub-document doc flags, set once per request in the extras.
+pub static SUBDOC_DOC_FLAG_MKDOC: u8 = 0x01;
+pub static SUBDOC_DOC_FLAG_ADD: u8 = 0x02;
+pub static SUBDOC_DOC_FLAG_ACCESS_DELETED: u8 = 0x04;
+pub static SUBDOC_DOC_FLAG_CREATE_AS_DELETED: u8 = 0x08;
+pub static SUBDOC_DOC_FLAG_REVIVE_DOCUMENT: u8 = 0x10;
 // pub static ERROR_MAP_VERSION: u16 = 1;
 
 #[derive(Debug)]
@@ -69,6 +82,7 @@ pub struct KvResponse {
     // key: Option<Bytes>,
     extras: Option<Bytes>,
     body: Option<Bytes>,
+    server_duration: Option<Duration>,
 }
 
 impl From<&Bytes> for KvResponse {
@@ -136,10 +150,17 @@ impl From<&Bytes> for KvResponse {
             None
         };
 
+        let server_duration = if flexible_extras_len > 0 {
+            decode_server_duration(input.slice(HEADER_SIZE..(HEADER_SIZE + flexible_extras_len)))
+        } else {
+            None
+        };
+
         KvResponse {
             opaque,
             body,
             extras,
+            server_duration,
             // key,
             status: Status::from(status),
             // datatype,
@@ -175,6 +196,40 @@ impl KvResponse {
     pub fn extras(&mut self) -> Option<Bytes> {
         self.extras.take()
     }
+
+    // How long the server spent on the request, sent when tracing has been negotiated.
+    pub fn server_duration(&self) -> Option<Duration> {
+        self.server_duration
+    }
+}
+
+// Framing extras are a sequence of frames, each starting with a byte holding the frame id in its
+// upper and the length in its lower nibble, either of which is followed by another byte when it
+// does not fit. The server duration frame holds the time spent in microseconds, encoded as
+// (2 * micros) ^ (1 / 1.74).
+fn decode_server_duration(mut frames: Bytes) -> Option<Duration> {
+    while frames.has_remaining() {
+        let header = frames.get_u8();
+        let mut id = (header >> 4) as usize;
+        let mut len = (header & 0x0F) as usize;
+        if id == 15 {
+            id += frames.try_get_u8().ok()? as usize;
+        }
+        if len == 15 {
+            len += frames.try_get_u8().ok()? as usize;
+        }
+        if frames.remaining() < len {
+            return None;
+        }
+
+        let mut frame = frames.split_to(len);
+        if id == 0 && len == 2 {
+            let encoded = frame.get_u16() as f64;
+            return Some(Duration::from_micros((encoded.powf(1.74) / 2.0) as u64));
+        }
+    }
+
+    None
 }
 
 /// Creates a regular, non-flex request with all fields necessary.
@@ -402,11 +457,17 @@ pub enum Opcode {
     Hello,
     Noop,
     ErrorMap,
+    SaslListMechs,
     Auth,
+    SaslStep,
     SelectBucket,
     GetCollectionID,
     SubdocGet,
+    SubdocDictUpsert,
+    SubdocDelete,
+    SubdocArrayPushLast,
     SubdocMultiLookup,
+    SubdocMultiMutation,
 }
 
 impl Opcode {
@@ -419,12 +480,18 @@ impl Opcode {
             Self::Remove => 0x04,
             Self::Noop => 0x0A,
             Self::Hello => 0x1F,
+            Self::SaslListMechs => 0x20,
             Self::Auth => 0x21,
+            Self::SaslStep => 0x22,
             Self::SelectBucket => 0x89,
             Self::ErrorMap => 0xFE,
             Self::GetCollectionID => 0xBB,
             Self::SubdocGet => 0xc5,
+            Self::SubdocDictUpsert => 0xc8,
+            Self::SubdocDelete => 0xc9,
+            Self::SubdocArrayPushLast => 0xcc,
             Self::SubdocMultiLookup => 0xd0,
+            Self::SubdocMultiMutation => 0xd1,
         }
     }
 }
@@ -447,12 +514,18 @@ impl TryFrom<u8> for Opcode {
             0x04 => Opcode::Remove,
             0x0A => Opcode::Noop,
             0x1F => Opcode::Hello,
+            0x20 => Opcode::SaslListMechs,
             0x21 => Opcode::Auth,
+            0x22 => Opcode::SaslStep,
             0x89 => Opcode::SelectBucket,
             0xFE => Opcode::ErrorMap,
             0xBB => Opcode::GetCollectionID,
             0xc5 => Opcode::SubdocGet,
+            0xc8 => Opcode::SubdocDictUpsert,
+            0xc9 => Opcode::SubdocDelete,
+            0xcc => Opcode::SubdocArrayPushLast,
             0xd0 => Opcode::SubdocMultiLookup,
+            0xd1 => Opcode::SubdocMultiMutation,
             _ => return Err(input),
         })
     }
@@ -499,12 +572,18 @@ impl From<u8> for Magic {
 pub enum Status {
     Success,
     AuthError,
+    AuthContinue,
     AccessError,
     KeyNotFound,
     KeyExists,
+    NotMyVbucket,
     CollectionUnknown,
     ScopeUnknown,
     PathNotFound,
+    PathExists,
+    SubdocMultiPathFailure,
+    SubdocSuccessDeleted,
+    SubdocMultiPathFailureDeleted,
     Unknown(u16),
 }
 
@@ -519,12 +598,20 @@ impl Status {
         match self {
             Status::Success => "success".into(),
             Status::AuthError => "authentication error".into(),
+            Status::AuthContinue => "authentication continue".into(),
             Status::AccessError => "access error".into(),
             Status::KeyNotFound => "key not found".into(),
             Status::KeyExists => "key already exists".into(),
+            Status::NotMyVbucket => "not my vbucket".into(),
             Status::CollectionUnknown => "collection unknown".into(),
             Status::ScopeUnknown => "scope unknown".into(),
             Status::PathNotFound => "field not found".into(),
+            Status::PathExists => "field already exists".into(),
+            Status::SubdocMultiPathFailure => "one or more sub-document paths failed".into(),
+            Status::SubdocSuccessDeleted => "success on a deleted document".into(),
+            Status::SubdocMultiPathFailureDeleted => {
+                "one or more sub-document paths failed on a deleted document".into()
+            }
             Status::Unknown(status) => format!("{:#04x}", status),
         }
     }
@@ -536,11 +623,17 @@ impl From<u16> for Status {
             0x00 => Status::Success,
             0x01 => Status::KeyNotFound,
             0x02 => Status::KeyExists,
+            0x07 => Status::NotMyVbucket,
             0x88 => Status::CollectionUnknown,
             0x8c => Status::ScopeUnknown,
             0x20 => Status::AuthError,
+            0x21 => Status::AuthContinue,
             0x24 => Status::AccessError,
             0xc0 => Status::PathNotFound,
+            0xc9 => Status::PathExists,
+            0xcc => Status::SubdocMultiPathFailure,
+            0xcd => Status::SubdocSuccessDeleted,
+            0xd3 => Status::SubdocMultiPathFailureDeleted,
             _ => Status::Unknown(input),
         }
     }
@@ -567,3 +660,30 @@ fn make_uleb128_32(key: Bytes, collection_id: u32) -> Bytes {
 
     builder.freeze()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_server_duration() {
+        let frames = Bytes::from_static(&[0x02, 0x00, 0x64]);
+        assert_eq!(
+            Some(Duration::from_micros(1509)),
+            decode_server_duration(frames)
+        );
+
+        // Other frames before it are skipped.
+        let frames = Bytes::from_static(&[0x11, 0xAA, 0x02, 0x00, 0x64]);
+        assert_eq!(
+            Some(Duration::from_micros(1509)),
+            decode_server_duration(frames)
+        );
+
+        assert_eq!(None, decode_server_duration(Bytes::new()));
+        assert_eq!(
+            None,
+            decode_server_duration(Bytes::from_static(&[0x02, 0x00]))
+        );
+    }
+}
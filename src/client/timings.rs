@@ -0,0 +1,103 @@
+use log::warn;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// The threshold set through `cb-env trace`, operations taking at least this long are logged.
+///
+/// It is owned by the state and shared with the clients of every cluster, so that changing it
+/// also applies to clients which are already connected.
+#[derive(Debug, Clone, Default)]
+pub struct SlowOperationThreshold {
+    threshold: Arc<RwLock<Option<Duration>>>,
+}
+
+impl SlowOperationThreshold {
+    pub fn get(&self) -> Option<Duration> {
+        *self.threshold.read().unwrap()
+    }
+
+    pub fn set(&self, threshold: Option<Duration>) {
+        *self.threshold.write().unwrap() = threshold;
+    }
+}
+
+/// How long an operation took against a node.
+///
+/// The dispatch time runs from the request being handed to the connection until the response
+/// arrived, and the server time is the part of that the server reports spending on it, which only
+/// the data service does. The total also covers everything around it, such as fetching configs
+/// and retrying on other nodes.
+#[derive(Debug, Clone)]
+pub struct OperationTimings {
+    operation: String,
+    address: String,
+    dispatch: Duration,
+    server: Option<Duration>,
+    total: Duration,
+}
+
+impl OperationTimings {
+    pub(crate) fn new(
+        operation: impl Into<String>,
+        address: impl Into<String>,
+        dispatch: Duration,
+        total: Duration,
+    ) -> Self {
+        Self {
+            operation: operation.into(),
+            address: address.into(),
+            dispatch,
+            server: None,
+            total,
+        }
+    }
+
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn dispatch(&self) -> Duration {
+        self.dispatch
+    }
+
+    pub fn server(&self) -> Option<Duration> {
+        self.server
+    }
+
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    pub(crate) fn set_server(&mut self, server: Option<Duration>) {
+        self.server = server;
+    }
+
+    pub(crate) fn set_total(&mut self, total: Duration) {
+        self.total = total;
+    }
+
+    // Logs the operation once it has completed, successfully or not, if it was slower than the
+    // threshold.
+    pub(crate) fn record(&self, threshold: Option<Duration>) {
+        let threshold = match threshold {
+            Some(t) => t,
+            None => return,
+        };
+        if self.total < threshold {
+            return;
+        }
+
+        let server = match self.server {
+            Some(s) => format!("{:?}", s),
+            None => "unknown".to_string(),
+        };
+        warn!(
+            "Slow {} operation on {}: total {:?}, dispatch {:?}, server {}",
+            self.operation, self.address, self.total, self.dispatch, server
+        );
+    }
+}
@@ -2,13 +2,15 @@ use crate::client::capella_ca::CAPELLA_CERT;
 use crate::client::ClientError;
 use crate::ClusterTlsConfig;
 use log::{debug, error};
-use rustls_pemfile::{read_all, Item};
+use rustls_pemfile::{certs, private_key, read_all, Item};
 use std::convert::TryFrom;
 use std::fs;
 use std::io::BufReader;
 use std::sync::Arc;
+use tokio_rustls::rustls::client::WantsClientCert;
 use tokio_rustls::rustls::crypto::{aws_lc_rs::default_provider, CryptoProvider};
-use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{ClientConfig, ConfigBuilder, RootCertStore};
 
 #[derive(Clone)]
 pub struct RustTlsConfig {
@@ -16,25 +18,47 @@ pub struct RustTlsConfig {
     // We need to hold onto these so that we can later rewrite them into the config file if needed.
     accept_all_certs: bool,
     cert_path: Option<String>,
+    client_cert_path: Option<String>,
+    client_key_path: Option<String>,
 }
 
 impl RustTlsConfig {
     pub fn new(
         accept_all_certs: bool,
         cert_path: Option<String>,
+        client_cert_path: Option<String>,
+        client_key_path: Option<String>,
     ) -> Result<RustTlsConfig, ClientError> {
         let _ = CryptoProvider::install_default(default_provider());
+        let client_cert = match (&client_cert_path, &client_key_path) {
+            (Some(cert), Some(key)) => Some(load_client_cert(cert, key)?),
+            (None, None) => None,
+            _ => {
+                return Err(ClientError::RequestFailed {
+                    reason: Some(
+                        "A client certificate needs both client-cert-path and client-key-path"
+                            .to_string(),
+                    ),
+                    key: None,
+                })
+            }
+        };
+
         let builder = ClientConfig::builder();
         if accept_all_certs {
-            let config = builder
-                .dangerous()
-                .with_custom_certificate_verifier(Arc::new(utilities::InsecureCertVerifier {}))
-                .with_no_client_auth();
+            let config = with_client_cert(
+                builder
+                    .dangerous()
+                    .with_custom_certificate_verifier(Arc::new(utilities::InsecureCertVerifier {})),
+                client_cert,
+            )?;
 
             return Ok(RustTlsConfig {
                 config,
                 accept_all_certs,
                 cert_path,
+                client_cert_path,
+                client_key_path,
             });
         }
 
@@ -98,14 +122,15 @@ impl RustTlsConfig {
                 }
             };
         };
-        let config = builder
-            .with_root_certificates(root_cert_store)
-            .with_no_client_auth();
+        let config =
+            with_client_cert(builder.with_root_certificates(root_cert_store), client_cert)?;
 
         Ok(RustTlsConfig {
             config,
             accept_all_certs,
             cert_path,
+            client_cert_path,
+            client_key_path,
         })
     }
 
@@ -120,6 +145,67 @@ impl RustTlsConfig {
     pub fn cert_path(&self) -> Option<String> {
         self.cert_path.clone()
     }
+
+    pub fn client_cert_path(&self) -> Option<String> {
+        self.client_cert_path.clone()
+    }
+
+    pub fn client_key_path(&self) -> Option<String> {
+        self.client_key_path.clone()
+    }
+
+    // Whether connections authenticate with a client certificate rather than a password.
+    pub fn has_client_cert(&self) -> bool {
+        self.client_cert_path.is_some()
+    }
+}
+
+type ClientCert = (Vec<CertificateDer<'static>>, PrivateKeyDer<'static>);
+
+fn load_client_cert(cert_path: &str, key_path: &str) -> Result<ClientCert, ClientError> {
+    let cert = fs::read(cert_path).map_err(ClientError::from)?;
+    let chain = certs(&mut BufReader::new(&cert[..]))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ClientError::RequestFailed {
+            reason: Some(format!("Failed to read client cert file {}", e)),
+            key: None,
+        })?;
+    if chain.is_empty() {
+        return Err(ClientError::RequestFailed {
+            reason: Some(format!("No certificates found in {}", cert_path)),
+            key: None,
+        });
+    }
+
+    let key = fs::read(key_path).map_err(ClientError::from)?;
+    let key = private_key(&mut BufReader::new(&key[..]))
+        .map_err(|e| ClientError::RequestFailed {
+            reason: Some(format!("Failed to read client key file {}", e)),
+            key: None,
+        })?
+        .ok_or_else(|| ClientError::RequestFailed {
+            reason: Some(format!("No private key found in {}", key_path)),
+            key: None,
+        })?;
+
+    Ok((chain, key))
+}
+
+fn with_client_cert(
+    builder: ConfigBuilder<ClientConfig, WantsClientCert>,
+    client_cert: Option<ClientCert>,
+) -> Result<ClientConfig, ClientError> {
+    match client_cert {
+        Some((chain, key)) => {
+            builder
+                .with_client_auth_cert(chain, key)
+                .map_err(|e| ClientError::RequestFailed {
+                    reason: Some(format!("Invalid client certificate {}", e)),
+                    key: None,
+                })
+        }
+        None => Ok(builder.with_no_client_auth()),
+    }
 }
 
 impl TryFrom<ClusterTlsConfig> for RustTlsConfig {
@@ -127,8 +213,10 @@ impl TryFrom<ClusterTlsConfig> for RustTlsConfig {
 
     fn try_from(tls_config: ClusterTlsConfig) -> Result<Self, Self::Error> {
         RustTlsConfig::new(
-            tls_config.accept_all_certs(),
+            tls_config.accept_all_certs().unwrap_or(false),
             tls_config.cert_path().clone(),
+            tls_config.client_cert_path().clone(),
+            tls_config.client_key_path().clone(),
         )
     }
 }
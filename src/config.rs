@@ -1,3 +1,4 @@
+use crate::client::{ClientError, SaslMechanism};
 use crate::remote_cluster::{ClusterTimeouts, RemoteCluster, RemoteClusterType};
 use crate::state::Provider;
 use log::debug;
@@ -5,6 +6,7 @@ use log::error;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::path::PathBuf;
+use std::process::{Command, Stdio};
 use std::time::Duration;
 use std::{fmt, fs};
 use toml::ser::Error;
@@ -78,8 +80,17 @@ impl ShellConfig {
                         if config_credentials.username.is_none() && cred.username.is_some() {
                             config_credentials.username.clone_from(&cred.username)
                         }
-                        if config_credentials.password.is_none() && cred.password.is_some() {
-                            config_credentials.password.clone_from(&cred.password)
+                        if !config_credentials.has_password() {
+                            config_credentials.password.clone_from(&cred.password);
+                            config_credentials
+                                .password_env
+                                .clone_from(&cred.password_env);
+                            config_credentials
+                                .password_command
+                                .clone_from(&cred.password_command);
+                            config_credentials
+                                .password_file
+                                .clone_from(&cred.password_file);
                         }
                     }
                 }
@@ -91,11 +102,33 @@ impl ShellConfig {
 
                 for cred in &standalone.capella_orgs {
                     if cred.identifier() == identifier {
-                        if config_credentials.secret_key.is_empty() && !cred.secret_key.is_empty() {
-                            config_credentials.secret_key.clone_from(&cred.secret_key)
+                        if config_credentials.secret_key.is_empty()
+                            && config_credentials.secret_key_source().is_none()
+                        {
+                            config_credentials.secret_key.clone_from(&cred.secret_key);
+                            config_credentials
+                                .secret_key_env
+                                .clone_from(&cred.secret_key_env);
+                            config_credentials
+                                .secret_key_command
+                                .clone_from(&cred.secret_key_command);
+                            config_credentials
+                                .secret_key_file
+                                .clone_from(&cred.secret_key_file);
                         }
-                        if config_credentials.access_key.is_empty() && !cred.access_key.is_empty() {
-                            config_credentials.access_key.clone_from(&cred.access_key)
+                        if config_credentials.access_key.is_empty()
+                            && config_credentials.access_key_source().is_none()
+                        {
+                            config_credentials.access_key.clone_from(&cred.access_key);
+                            config_credentials
+                                .access_key_env
+                                .clone_from(&cred.access_key_env);
+                            config_credentials
+                                .access_key_command
+                                .clone_from(&cred.access_key_command);
+                            config_credentials
+                                .access_key_file
+                                .clone_from(&cred.access_key_file);
                         }
                     }
                 }
@@ -244,17 +277,28 @@ pub struct CapellaOrganizationConfig {
 
     #[serde(rename(deserialize = "api-endpoint", serialize = "api-endpoint"))]
     api_endpoint: Option<String>,
+
+    #[serde(rename(deserialize = "ip-lookup-endpoint", serialize = "ip-lookup-endpoint"))]
+    ip_lookup_endpoint: Option<String>,
+
+    proxy: Option<String>,
 }
 
 impl CapellaOrganizationConfig {
     pub fn identifier(&self) -> String {
         self.identifier.clone()
     }
-    pub fn secret_key(&self) -> String {
-        self.credentials.secret_key.clone()
+    pub fn secret_key(&self) -> Secret {
+        match self.credentials.secret_key_source() {
+            Some(source) if self.credentials.secret_key.is_empty() => Secret::Source(source),
+            _ => Secret::Value(self.credentials.secret_key.clone()),
+        }
     }
-    pub fn access_key(&self) -> String {
-        self.credentials.access_key.clone()
+    pub fn access_key(&self) -> Secret {
+        match self.credentials.access_key_source() {
+            Some(source) if self.credentials.access_key.is_empty() => Secret::Source(source),
+            _ => Secret::Value(self.credentials.access_key.clone()),
+        }
     }
     pub fn management_timeout(&self) -> Option<&Duration> {
         self.management_timeout.as_ref()
@@ -265,6 +309,12 @@ impl CapellaOrganizationConfig {
     pub fn api_endpoint(&self) -> Option<String> {
         self.api_endpoint.clone()
     }
+    pub fn ip_lookup_endpoint(&self) -> Option<String> {
+        self.ip_lookup_endpoint.clone()
+    }
+    pub fn proxy(&self) -> Option<String> {
+        self.proxy.clone()
+    }
 
     pub fn credentials_mut(&mut self) -> &mut OrganizationCredentials {
         &mut self.credentials
@@ -388,6 +438,8 @@ impl ClusterConfigBuilder {
             timeouts: ClusterConfigTimeouts::default(),
             tls: self.tls.unwrap_or_default(),
             kv_batch_size: None,
+            sasl_mechanism: None,
+            proxy: None,
             capella_org: None,
             project: None,
             cluster_type: None,
@@ -419,6 +471,11 @@ pub struct ClusterConfig {
     #[serde(rename(deserialize = "kv-batch-size", serialize = "kv-batch-size"))]
     kv_batch_size: Option<u32>,
 
+    #[serde(rename(deserialize = "sasl-mechanism", serialize = "sasl-mechanism"))]
+    sasl_mechanism: Option<SaslMechanism>,
+
+    proxy: Option<String>,
+
     #[serde(rename(
         deserialize = "capella-organization",
         serialize = "capella-organization"
@@ -443,15 +500,24 @@ impl ClusterConfig {
         if let Some(u) = &self.credentials.username {
             return u.clone();
         }
+        if self.tls.has_client_cert() {
+            return String::new();
+        }
         error!(
             "No username found in config or credentials file for identifier \"{}\"!",
             self.identifier
         );
         std::process::exit(-1);
     }
-    pub fn password(&self) -> String {
+    pub fn password(&self) -> Secret {
         if let Some(p) = &self.credentials.password {
-            return p.clone();
+            return Secret::Value(p.clone());
+        }
+        if let Some(source) = self.credentials.password_source() {
+            return Secret::Source(source);
+        }
+        if self.tls.has_client_cert() {
+            return Secret::Value(String::new());
         }
         error!(
             "No password found in config or credentials file for identifier \"{}\"!",
@@ -486,6 +552,12 @@ impl ClusterConfig {
     pub fn kv_batch_size(&self) -> Option<u32> {
         self.kv_batch_size
     }
+    pub fn sasl_mechanism(&self) -> Option<SaslMechanism> {
+        self.sasl_mechanism
+    }
+    pub fn proxy(&self) -> Option<String> {
+        self.proxy.clone()
+    }
     pub fn display_name(&self) -> Option<String> {
         self.display_name.clone()
     }
@@ -549,15 +621,16 @@ impl From<(String, &RemoteCluster)> for ClusterConfig {
 
         let tls_config = if let Some(tls_config) = cluster.1.tls_config() {
             ClusterTlsConfig {
-                enabled: true,
+                enabled: Some(true),
                 cert_path: tls_config.cert_path(),
-                accept_all_certs: tls_config.accept_all_certs(),
+                accept_all_certs: Some(tls_config.accept_all_certs()),
+                client_cert_path: tls_config.client_cert_path(),
+                client_key_path: tls_config.client_key_path(),
             }
         } else {
             ClusterTlsConfig {
-                enabled: false,
-                cert_path: None,
-                accept_all_certs: false,
+                enabled: Some(false),
+                ..ClusterTlsConfig::default()
             }
         };
 
@@ -569,19 +642,21 @@ impl From<(String, &RemoteCluster)> for ClusterConfig {
 
         Self {
             identifier: cluster.0,
-            conn_string: cluster.1.hostnames().join(","),
+            conn_string: cluster.1.conn_string().to_string(),
             default_collection: cluster.1.active_collection(),
             default_scope: cluster.1.active_scope(),
             default_bucket: cluster.1.active_bucket(),
             timeouts: ClusterConfigTimeouts::from(cluster.1.timeouts()),
             tls: tls_config,
-            credentials: ClusterCredentials {
-                username: Some(cluster.1.username().to_string()),
-                password: Some(cluster.1.password().to_string()),
-            },
+            credentials: ClusterCredentials::from_secret(
+                Some(cluster.1.username().to_string()).filter(|u| !u.is_empty()),
+                cluster.1.password_secret(),
+            ),
             capella_org: cloud,
             project: cluster.1.project(),
             kv_batch_size,
+            sasl_mechanism: cluster.1.sasl_mechanism(),
+            proxy: cluster.1.proxy().map(|p| p.url().to_string()),
             display_name: cluster.1.display_name(),
             // This is a config option for dev ony so we won't want to write to file
             cluster_type: None,
@@ -599,6 +674,18 @@ pub struct OrganizationCredentials {
     #[serde(rename(deserialize = "secret-key", serialize = "secret-key"))]
     #[serde(skip_serializing_if = "String::is_empty")]
     secret_key: String,
+    #[serde(rename(deserialize = "access-key-env", serialize = "access-key-env"))]
+    access_key_env: Option<String>,
+    #[serde(rename(deserialize = "access-key-command", serialize = "access-key-command"))]
+    access_key_command: Option<String>,
+    #[serde(rename(deserialize = "access-key-file", serialize = "access-key-file"))]
+    access_key_file: Option<String>,
+    #[serde(rename(deserialize = "secret-key-env", serialize = "secret-key-env"))]
+    secret_key_env: Option<String>,
+    #[serde(rename(deserialize = "secret-key-command", serialize = "secret-key-command"))]
+    secret_key_command: Option<String>,
+    #[serde(rename(deserialize = "secret-key-file", serialize = "secret-key-file"))]
+    secret_key_file: Option<String>,
 }
 
 impl Debug for OrganizationCredentials {
@@ -610,12 +697,34 @@ impl Debug for OrganizationCredentials {
     }
 }
 
-impl OrganizationCredentials {}
+impl OrganizationCredentials {
+    fn access_key_source(&self) -> Option<SecretSource> {
+        SecretSource::from_options(
+            &self.access_key_env,
+            &self.access_key_command,
+            &self.access_key_file,
+        )
+    }
+
+    fn secret_key_source(&self) -> Option<SecretSource> {
+        SecretSource::from_options(
+            &self.secret_key_env,
+            &self.secret_key_command,
+            &self.secret_key_file,
+        )
+    }
+}
 
 #[derive(Deserialize, Serialize)]
 pub struct ClusterCredentials {
     username: Option<String>,
     password: Option<String>,
+    #[serde(rename(deserialize = "password-env", serialize = "password-env"))]
+    password_env: Option<String>,
+    #[serde(rename(deserialize = "password-command", serialize = "password-command"))]
+    password_command: Option<String>,
+    #[serde(rename(deserialize = "password-file", serialize = "password-file"))]
+    password_file: Option<String>,
 }
 
 impl Debug for ClusterCredentials {
@@ -626,17 +735,167 @@ impl Debug for ClusterCredentials {
                 "password",
                 &self.password.clone().map(|_| "****".to_string()),
             )
+            .field("password_env", &self.password_env)
+            .field("password_command", &self.password_command)
+            .field("password_file", &self.password_file)
             .finish()
     }
 }
 
 impl ClusterCredentials {
     pub fn new(username: Option<String>, password: Option<String>) -> Self {
-        Self { username, password }
+        Self {
+            username,
+            password,
+            password_env: None,
+            password_command: None,
+            password_file: None,
+        }
+    }
+
+    fn from_secret(username: Option<String>, password: &Secret) -> Self {
+        let mut credentials = Self::new(username, None);
+        match password {
+            Secret::Value(v) => {
+                credentials.password = Some(v.clone()).filter(|p| !p.is_empty());
+            }
+            Secret::Source(SecretSource::Env(var)) => credentials.password_env = Some(var.clone()),
+            Secret::Source(SecretSource::Command(cmd)) => {
+                credentials.password_command = Some(cmd.clone())
+            }
+            Secret::Source(SecretSource::File(path)) => {
+                credentials.password_file = Some(path.clone())
+            }
+        }
+        credentials
+    }
+
+    fn password_source(&self) -> Option<SecretSource> {
+        SecretSource::from_options(
+            &self.password_env,
+            &self.password_command,
+            &self.password_file,
+        )
+    }
+
+    fn has_password(&self) -> bool {
+        self.password.is_some() || self.password_source().is_some()
+    }
+}
+
+/// Where a secret is read from when it is not written into the config or credentials file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SecretSource {
+    /// The name of an environment variable.
+    Env(String),
+    /// A command run through the shell, the secret is what it prints.
+    Command(String),
+    /// The path of a file holding the secret.
+    File(String),
+}
+
+impl SecretSource {
+    fn from_options(
+        env: &Option<String>,
+        command: &Option<String>,
+        file: &Option<String>,
+    ) -> Option<Self> {
+        env.clone()
+            .map(SecretSource::Env)
+            .or_else(|| command.clone().map(SecretSource::Command))
+            .or_else(|| file.clone().map(SecretSource::File))
+    }
+
+    pub fn resolve(&self) -> Result<String, ClientError> {
+        let value = match self {
+            SecretSource::Env(var) => std::env::var(var).map_err(|e| self.error(e.to_string()))?,
+            SecretSource::Command(command) => {
+                let output = shell_command(command)
+                    .stderr(Stdio::piped())
+                    .output()
+                    .map_err(|e| self.error(e.to_string()))?;
+                if !output.status.success() {
+                    return Err(self.error(format!(
+                        "{}: {}",
+                        output.status,
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    )));
+                }
+                String::from_utf8(output.stdout).map_err(|e| self.error(e.to_string()))?
+            }
+            SecretSource::File(path) => fs::read_to_string(shellexpand::tilde(path).as_ref())
+                .map_err(|e| self.error(e.to_string()))?,
+        };
+
+        // Commands and files almost always end with a newline which is not part of the secret.
+        Ok(value.trim_end_matches(&['\r', '\n'][..]).to_string())
+    }
+
+    fn error(&self, reason: String) -> ClientError {
+        ClientError::SecretUnavailable {
+            from: self.to_string(),
+            reason,
+        }
+    }
+}
+
+impl fmt::Display for SecretSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretSource::Env(var) => write!(f, "environment variable {}", var),
+            SecretSource::Command(command) => write!(f, "command `{}`", command),
+            SecretSource::File(path) => write!(f, "file {}", path),
+        }
+    }
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+/// A secret from the config, either written out or read from its source when it is first needed.
+#[derive(Clone)]
+pub enum Secret {
+    Value(String),
+    Source(SecretSource),
+}
+
+impl Secret {
+    pub fn resolve(&self) -> Result<String, ClientError> {
+        match self {
+            Secret::Value(v) => Ok(v.clone()),
+            Secret::Source(source) => source.resolve(),
+        }
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret::Value(value)
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Secret::Value(_) => write!(f, "****"),
+            Secret::Source(source) => write!(f, "{:?}", source),
+        }
     }
 }
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
+// Timeouts which are not set are taken from the connection string, or else the defaults.
+#[derive(Debug, Default, Deserialize, Clone, Serialize)]
 pub struct ClusterConfigTimeouts {
     #[serde(default)]
     #[serde(
@@ -676,19 +935,6 @@ pub struct ClusterConfigTimeouts {
     transaction_timeout: Option<Duration>,
 }
 
-impl Default for ClusterConfigTimeouts {
-    fn default() -> Self {
-        ClusterConfigTimeouts {
-            data_timeout: Some(DEFAULT_DATA_TIMEOUT),
-            query_timeout: Some(DEFAULT_QUERY_TIMEOUT),
-            analytics_timeout: Some(DEFAULT_ANALYTICS_TIMEOUT),
-            search_timeout: Some(DEFAULT_SEARCH_TIMEOUT),
-            management_timeout: Some(DEFAULT_MANAGEMENT_TIMEOUT),
-            transaction_timeout: Some(DEFAULT_MANAGEMENT_TIMEOUT),
-        }
-    }
-}
-
 impl ClusterConfigTimeouts {
     pub fn data_timeout(&self) -> Option<&Duration> {
         self.data_timeout.as_ref()
@@ -715,30 +961,41 @@ impl ClusterConfigTimeouts {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
+#[derive(Debug, Default, Deserialize, Clone, Serialize)]
 pub struct ClusterTlsConfig {
+    // Both flags are kept unset unless configured, so that the connection string can decide them.
     #[serde(rename(deserialize = "tls-enabled", serialize = "tls-enabled"))]
-    #[serde(default = "default_as_true")]
-    enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
     #[serde(rename(deserialize = "tls-cert-path", serialize = "tls-cert-path"))]
     cert_path: Option<String>,
     #[serde(rename(
         deserialize = "tls-accept-all-certs",
         serialize = "tls-accept-all-certs"
     ))]
-    #[serde(default)]
-    accept_all_certs: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    accept_all_certs: Option<bool>,
+    #[serde(rename(deserialize = "client-cert-path", serialize = "client-cert-path"))]
+    client_cert_path: Option<String>,
+    #[serde(rename(deserialize = "client-key-path", serialize = "client-key-path"))]
+    client_key_path: Option<String>,
 }
 
 impl ClusterTlsConfig {
-    pub fn new(enabled: bool, cert_path: Option<String>, accept_all_certs: bool) -> Self {
+    pub fn new(
+        enabled: Option<bool>,
+        cert_path: Option<String>,
+        accept_all_certs: Option<bool>,
+    ) -> Self {
         Self {
             enabled,
             cert_path,
             accept_all_certs,
+            client_cert_path: None,
+            client_key_path: None,
         }
     }
-    pub fn enabled(&self) -> bool {
+    pub fn enabled(&self) -> Option<bool> {
         self.enabled
     }
 
@@ -746,18 +1003,21 @@ impl ClusterTlsConfig {
         &self.cert_path
     }
 
-    pub fn accept_all_certs(&self) -> bool {
+    pub fn accept_all_certs(&self) -> Option<bool> {
         self.accept_all_certs
     }
-}
 
-impl Default for ClusterTlsConfig {
-    fn default() -> Self {
-        Self {
-            enabled: true,
-            cert_path: None,
-            accept_all_certs: false,
-        }
+    pub fn client_cert_path(&self) -> &Option<String> {
+        &self.client_cert_path
+    }
+
+    pub fn client_key_path(&self) -> &Option<String> {
+        &self.client_key_path
+    }
+
+    // A client certificate identifies the user by itself, so no password is needed with one.
+    pub fn has_client_cert(&self) -> bool {
+        self.enabled.unwrap_or(true) && self.client_cert_path.is_some()
     }
 }
 
@@ -812,6 +1072,18 @@ pub struct OrganizationCredentialsFromFile {
     #[serde(default)]
     #[serde(rename(deserialize = "secret-key", serialize = "secret-key"))]
     secret_key: String,
+    #[serde(rename(deserialize = "access-key-env", serialize = "access-key-env"))]
+    access_key_env: Option<String>,
+    #[serde(rename(deserialize = "access-key-command", serialize = "access-key-command"))]
+    access_key_command: Option<String>,
+    #[serde(rename(deserialize = "access-key-file", serialize = "access-key-file"))]
+    access_key_file: Option<String>,
+    #[serde(rename(deserialize = "secret-key-env", serialize = "secret-key-env"))]
+    secret_key_env: Option<String>,
+    #[serde(rename(deserialize = "secret-key-command", serialize = "secret-key-command"))]
+    secret_key_command: Option<String>,
+    #[serde(rename(deserialize = "secret-key-file", serialize = "secret-key-file"))]
+    secret_key_file: Option<String>,
 }
 
 impl OrganizationCredentialsFromFile {
@@ -825,6 +1097,12 @@ pub struct ClusterCredentialsFromFile {
     identifier: String,
     username: Option<String>,
     password: Option<String>,
+    #[serde(rename(deserialize = "password-env"))]
+    password_env: Option<String>,
+    #[serde(rename(deserialize = "password-command"))]
+    password_command: Option<String>,
+    #[serde(rename(deserialize = "password-file"))]
+    password_file: Option<String>,
 }
 
 impl ClusterCredentialsFromFile {
@@ -833,12 +1111,100 @@ impl ClusterCredentialsFromFile {
     }
 }
 
-fn default_as_true() -> bool {
-    true
-}
-
 #[derive(Debug, Deserialize)]
 pub struct LLMCredentials {
     identifier: String,
     api_key: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_sources_resolve_without_trailing_newline() {
+        std::env::set_var("CBSH_TEST_SECRET_SOURCE", "from-env");
+        assert_eq!(
+            "from-env",
+            SecretSource::Env("CBSH_TEST_SECRET_SOURCE".into())
+                .resolve()
+                .unwrap()
+        );
+
+        let path = std::env::temp_dir().join("cbsh-test-secret-source");
+        fs::write(&path, "from-file\n").unwrap();
+        assert_eq!(
+            "from-file",
+            SecretSource::File(path.to_string_lossy().to_string())
+                .resolve()
+                .unwrap()
+        );
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(
+            "from-command",
+            SecretSource::Command("echo from-command".into())
+                .resolve()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn secret_sources_report_failures() {
+        assert!(SecretSource::Env("CBSH_TEST_SECRET_SOURCE_UNSET".into())
+            .resolve()
+            .is_err());
+        assert!(SecretSource::Command("exit 3".into()).resolve().is_err());
+    }
+
+    #[test]
+    fn built_cluster_leaves_unset_options_to_connection_string() {
+        let cluster = ClusterConfigBuilder::new(
+            "local",
+            "couchbase://localhost?kv_timeout=10s",
+            ClusterCredentials::new(Some("Administrator".into()), Some("password".into())),
+        )
+        .tls_config(ClusterTlsConfig::new(None, None, None))
+        .build();
+        assert_eq!(None, cluster.timeouts().data_timeout());
+        assert_eq!(None, cluster.timeouts().query_timeout());
+        assert_eq!(None, cluster.tls().enabled());
+
+        let written = ShellConfig::new_from_clusters(vec![cluster], vec![])
+            .to_str()
+            .unwrap();
+        assert!(!written.contains("-timeout"));
+        assert!(!written.contains("tls-enabled"));
+    }
+
+    #[test]
+    fn cluster_password_prefers_written_value() {
+        let config = ShellConfig::from_str(
+            r#"
+            version = 1
+
+            [[cluster]]
+            identifier = "written"
+            connstr = "localhost"
+            username = "Administrator"
+            password = "password"
+            password-env = "CBSH_PASSWORD"
+
+            [[cluster]]
+            identifier = "command"
+            connstr = "localhost"
+            username = "Administrator"
+            password-command = "pass show cb/prod"
+            "#,
+        );
+
+        match config.clusters()[0].password() {
+            Secret::Value(v) => assert_eq!("password", v),
+            s => panic!("unexpected secret {:?}", s),
+        }
+        match config.clusters()[1].password() {
+            Secret::Source(s) => assert_eq!(SecretSource::Command("pass show cb/prod".into()), s),
+            s => panic!("unexpected secret {:?}", s),
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use crate::remote_cluster::{ClusterTimeouts, RemoteClusterType};
+use humantime_serde::re::humantime;
+use log::warn;
+use std::fmt;
+use std::time::Duration;
+
+/// A connection string in the form used by the SDKs, e.g.
+/// `couchbases://node1,node2?network=external&kv_timeout=10s`.
+///
+/// The hostnames are what the shell bootstraps from over HTTP, so the only ports allowed with the
+/// couchbase schemes are the default KV ports. A custom management port needs the http scheme.
+#[derive(Debug, Clone)]
+pub struct ConnectionString {
+    raw: String,
+    hostnames: Vec<String>,
+    tls_enabled: Option<bool>,
+    accept_all_certs: Option<bool>,
+    network: Network,
+    kv_timeout: Option<Duration>,
+    query_timeout: Option<Duration>,
+    analytics_timeout: Option<Duration>,
+    search_timeout: Option<Duration>,
+    management_timeout: Option<Duration>,
+}
+
+/// Which of the addresses advertised by the cluster to connect to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Network {
+    /// Use the external addresses when the seed hostname is one of them.
+    Auto,
+    Default,
+    External,
+}
+
+impl ConnectionString {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let (hosts, options) = match input.split_once('?') {
+            Some((hosts, options)) => (hosts, Some(options)),
+            None => (input, None),
+        };
+
+        let mut conn_string = Self {
+            raw: input.to_string(),
+            hostnames: vec![],
+            tls_enabled: None,
+            accept_all_certs: None,
+            network: Network::Auto,
+            kv_timeout: None,
+            query_timeout: None,
+            analytics_timeout: None,
+            search_timeout: None,
+            management_timeout: None,
+        };
+
+        // The scheme is given once for all of the hosts, as in couchbases://node1,node2.
+        let (scheme, hosts) = split_scheme(hosts.trim());
+        conn_string.tls_enabled = scheme.map(|s| matches!(s, "couchbases" | "https"));
+        for host in hosts.split(',').map(str::trim).filter(|h| !h.is_empty()) {
+            let host = match split_scheme(host) {
+                (None, host) => host,
+                // Connection strings written before the scheme applied to every host repeat it.
+                (repeated, host) if repeated == scheme => host,
+                (Some(other), _) => {
+                    return Err(format!(
+                        "Host {} uses the {} scheme, but all hosts must use the same scheme",
+                        host, other
+                    ))
+                }
+            };
+            conn_string.hostnames.push(parse_host(scheme, host)?);
+        }
+        if conn_string.hostnames.is_empty() {
+            return Err(format!("No hosts found in connection string {}", input));
+        }
+
+        for option in options
+            .unwrap_or_default()
+            .split('&')
+            .filter(|o| !o.is_empty())
+        {
+            let (key, value) = option
+                .split_once('=')
+                .ok_or_else(|| format!("Connection string option {} has no value", option))?;
+            match key {
+                "kv_timeout" => conn_string.kv_timeout = Some(parse_timeout(key, value)?),
+                "query_timeout" => conn_string.query_timeout = Some(parse_timeout(key, value)?),
+                "analytics_timeout" => {
+                    conn_string.analytics_timeout = Some(parse_timeout(key, value)?)
+                }
+                "search_timeout" => conn_string.search_timeout = Some(parse_timeout(key, value)?),
+                "management_timeout" => {
+                    conn_string.management_timeout = Some(parse_timeout(key, value)?)
+                }
+                "tls_verify" => {
+                    conn_string.accept_all_certs = match value {
+                        "none" => Some(true),
+                        "peer" => Some(false),
+                        _ => {
+                            return Err(format!(
+                                "Invalid tls_verify {}, expected none or peer",
+                                value
+                            ))
+                        }
+                    }
+                }
+                "network" => {
+                    conn_string.network = match value {
+                        "auto" => Network::Auto,
+                        "default" => Network::Default,
+                        "external" => Network::External,
+                        _ => {
+                            return Err(format!(
+                                "Invalid network {}, expected auto, default or external",
+                                value
+                            ))
+                        }
+                    }
+                }
+                _ => warn!("Ignoring unsupported connection string option {}", key),
+            }
+        }
+
+        Ok(conn_string)
+    }
+
+    pub fn hostnames(&self) -> &Vec<String> {
+        &self.hostnames
+    }
+
+    pub fn cluster_type(&self) -> RemoteClusterType {
+        RemoteClusterType::from(self.hostnames.clone())
+    }
+
+    /// Whether the scheme asks for TLS, `None` when there is no scheme.
+    pub fn tls_enabled(&self) -> Option<bool> {
+        self.tls_enabled
+    }
+
+    /// Whether `tls_verify=none` was given, `None` when `tls_verify` was not given at all.
+    pub fn accept_all_certs(&self) -> Option<bool> {
+        self.accept_all_certs
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    pub fn kv_timeout(&self) -> Option<Duration> {
+        self.kv_timeout
+    }
+
+    pub fn query_timeout(&self) -> Option<Duration> {
+        self.query_timeout
+    }
+
+    pub fn analytics_timeout(&self) -> Option<Duration> {
+        self.analytics_timeout
+    }
+
+    pub fn search_timeout(&self) -> Option<Duration> {
+        self.search_timeout
+    }
+
+    pub fn management_timeout(&self) -> Option<Duration> {
+        self.management_timeout
+    }
+
+    /// The default timeouts with any given in the options applied.
+    pub fn timeouts(&self) -> ClusterTimeouts {
+        let mut timeouts = ClusterTimeouts::default();
+        if let Some(t) = self.kv_timeout {
+            timeouts.set_data_timeout(t);
+        }
+        if let Some(t) = self.query_timeout {
+            timeouts.set_query_timeout(t);
+        }
+        if let Some(t) = self.analytics_timeout {
+            timeouts.set_analytics_timeout(t);
+        }
+        if let Some(t) = self.search_timeout {
+            timeouts.set_search_timeout(t);
+        }
+        if let Some(t) = self.management_timeout {
+            timeouts.set_management_timeout(t);
+        }
+        timeouts
+    }
+}
+
+impl fmt::Display for ConnectionString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+// Splits off the scheme, which is returned without the :// separator.
+fn split_scheme(input: &str) -> (Option<&str>, &str) {
+    for scheme in ["couchbase", "couchbases", "http", "https"] {
+        if let Some(rest) = input
+            .strip_prefix(scheme)
+            .and_then(|r| r.strip_prefix("://"))
+        {
+            return (Some(scheme), rest);
+        }
+    }
+    (None, input)
+}
+
+// Returns the host to bootstrap from, given the scheme of the connection string.
+fn parse_host(scheme: Option<&str>, host: &str) -> Result<String, String> {
+    match scheme {
+        Some("couchbase") => strip_kv_port(host, "couchbase", &[":11210"]),
+        Some("couchbases") => strip_kv_port(host, "couchbases", &[":11207", ":11211"]),
+        _ => {
+            if host.ends_with(":11210") || host.ends_with(":11207") || host.ends_with(":11211") {
+                return Err(
+                    "Memcached port detected, http scheme must be used with custom port (management port)"
+                        .to_string(),
+                );
+            }
+            Ok(host.to_string())
+        }
+    }
+}
+
+fn strip_kv_port(host: &str, scheme: &str, default_ports: &[&str]) -> Result<String, String> {
+    if let Some(stripped) = default_ports.iter().find_map(|p| host.strip_suffix(p)) {
+        return Ok(stripped.to_string());
+    }
+    if host.contains(':') {
+        return Err(format!(
+            "{} scheme and non-default port detected, http scheme must be used with custom port (management port)",
+            scheme
+        ));
+    }
+
+    Ok(host.to_string())
+}
+
+// Timeouts are either durations such as 2500ms or 10s, or a number of milliseconds.
+fn parse_timeout(key: &str, value: &str) -> Result<Duration, String> {
+    if let Ok(millis) = value.parse::<u64>() {
+        return Ok(Duration::from_millis(millis));
+    }
+
+    humantime::parse_duration(value).map_err(|e| format!("Invalid {} {}: {}", key, value, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hosts_and_schemes() {
+        let conn_string = ConnectionString::parse("couchbases://node1:11207,node2").unwrap();
+        assert_eq!(
+            &vec!["node1".to_string(), "node2".to_string()],
+            conn_string.hostnames()
+        );
+        assert_eq!(Some(true), conn_string.tls_enabled());
+
+        let conn_string =
+            ConnectionString::parse("couchbases://node1:11207,node2:11207,node3").unwrap();
+        assert_eq!(
+            &vec![
+                "node1".to_string(),
+                "node2".to_string(),
+                "node3".to_string()
+            ],
+            conn_string.hostnames()
+        );
+        assert_eq!(Some(true), conn_string.tls_enabled());
+
+        let conn_string =
+            ConnectionString::parse("couchbase://node1,couchbase://node2:11210").unwrap();
+        assert_eq!(
+            &vec!["node1".to_string(), "node2".to_string()],
+            conn_string.hostnames()
+        );
+        assert_eq!(Some(false), conn_string.tls_enabled());
+
+        let conn_string = ConnectionString::parse("http://node1:9000").unwrap();
+        assert_eq!(&vec!["node1:9000".to_string()], conn_string.hostnames());
+        assert_eq!(Some(false), conn_string.tls_enabled());
+
+        let conn_string = ConnectionString::parse("node1").unwrap();
+        assert_eq!(None, conn_string.tls_enabled());
+
+        assert!(ConnectionString::parse("couchbase://node1:12000").is_err());
+        assert!(ConnectionString::parse("node1:11210").is_err());
+        assert!(ConnectionString::parse("http://node1:11210").is_err());
+        assert!(ConnectionString::parse("couchbase://node1,couchbases://node2").is_err());
+        assert!(ConnectionString::parse("?kv_timeout=10s").is_err());
+    }
+
+    #[test]
+    fn parses_options() {
+        let conn_string = ConnectionString::parse(
+            "couchbase://node1?kv_timeout=2500&query_timeout=1m&tls_verify=none&network=external&unknown=1",
+        )
+        .unwrap();
+        assert_eq!(&vec!["node1".to_string()], conn_string.hostnames());
+        assert_eq!(Some(Duration::from_millis(2500)), conn_string.kv_timeout());
+        assert_eq!(Some(Duration::from_secs(60)), conn_string.query_timeout());
+        assert_eq!(None, conn_string.search_timeout());
+        assert_eq!(Some(true), conn_string.accept_all_certs());
+        assert_eq!(Network::External, conn_string.network());
+
+        let timeouts = conn_string.timeouts();
+        assert_eq!(Duration::from_millis(2500), timeouts.data_timeout());
+        assert_eq!(
+            ClusterTimeouts::default().search_timeout(),
+            timeouts.search_timeout()
+        );
+
+        assert!(ConnectionString::parse("node1?tls_verify=maybe").is_err());
+        assert!(ConnectionString::parse("node1?network=internal").is_err());
+        assert!(ConnectionString::parse("node1?kv_timeout=soon").is_err());
+    }
+}
@@ -263,10 +263,7 @@ pub fn create_default_context() -> EngineState {
             DateToTimezone,
         };
 
-        // Shells, from nu_command
-        bind_command! {
-            Exit,
-        };
+        // Shells, exit is provided by cbsh so that it can warn about open transactions.
 
         // Formats, from nu_command
         bind_command! {
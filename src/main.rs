@@ -7,6 +7,7 @@ mod cli_options;
 mod client;
 mod config;
 mod config_files;
+mod connection_string;
 mod default_context;
 mod remote_cluster;
 mod state;
@@ -22,10 +23,9 @@ use crate::config::{
     DEFAULT_SEARCH_TIMEOUT, DEFAULT_TRANSACTION_TIMEOUT,
 };
 use crate::config_files::read_nu_config_file;
+use crate::connection_string::ConnectionString;
 use crate::default_context::create_default_context;
-use crate::remote_cluster::{
-    ClusterTimeouts, RemoteCluster, RemoteClusterResources, RemoteClusterType,
-};
+use crate::remote_cluster::{ClusterTimeouts, RemoteCluster, RemoteClusterResources};
 use crate::state::Llm;
 use crate::state::RemoteCapellaOrganization;
 use state::State;
@@ -45,11 +45,10 @@ use nu_protocol::{
     PipelineData, PluginIdentity, RegisteredPlugin, Signals, Span, Value,
 };
 
-use crate::client::{RustTlsConfig, CLOUD_URL};
+use crate::client::{ProxyConfig, RustTlsConfig, CLOUD_URL, DEFAULT_IP_LOOKUP_ENDPOINT};
 use nu_path::canonicalize_with;
 use nu_plugin_engine::{GetPlugin, PluginDeclaration};
 use std::collections::HashMap;
-use std::convert::TryFrom;
 use std::error::Error;
 use std::fs::File;
 use std::io::{BufReader, Write};
@@ -260,46 +259,6 @@ struct Motd {
     msg: String,
 }
 
-fn validate_hostnames(hostnames: Vec<String>) -> (RemoteClusterType, Vec<String>) {
-    let mut validated = vec![];
-    for hostname in &hostnames {
-        let host = if let Some(stripped_couchbase) = hostname.strip_prefix("couchbase://") {
-            if let Some(stripped_port) = stripped_couchbase.strip_suffix(":11210") {
-                stripped_port.to_string()
-            } else if stripped_couchbase.contains(':') {
-                error!("Couchbase scheme and non-default port detected, http scheme must be used with custom port (management port)");
-                std::process::exit(1);
-            } else {
-                stripped_couchbase.to_string()
-            }
-        } else if let Some(stripped_couchbase) = hostname.strip_prefix("couchbases://") {
-            if let Some(stripped_port) = stripped_couchbase.strip_suffix(":11211") {
-                stripped_port.to_string()
-            } else if stripped_couchbase.contains(':') {
-                error!("Couchbases scheme and non-default port detected, http scheme must be used with custom port (management port)");
-                std::process::exit(1);
-            } else {
-                stripped_couchbase.to_string()
-            }
-        } else if hostname.strip_suffix(":11210").is_some()
-            || hostname.strip_suffix(":11211").is_some()
-        {
-            error!("Memcached port detected, http scheme must be used with custom port (management port)");
-            std::process::exit(1);
-        } else if let Some(stripped_http) = hostname.strip_prefix("http://") {
-            stripped_http.to_string()
-        } else if let Some(stripped_http) = hostname.strip_prefix("https://") {
-            stripped_http.to_string()
-        } else {
-            hostname.to_string()
-        };
-
-        validated.push(host);
-    }
-
-    (RemoteClusterType::from(hostnames), validated)
-}
-
 fn create_logger_builder(logger_prefix: Option<String>) {
     let mut logger_builder = env_logger::Builder::from_env(
         Env::default().filter_or("CBSH_LOG", "info,isahc=error,surf=error,nu=error"),
@@ -359,28 +318,51 @@ fn remote_cluster_from_opts(opt: CliOptions, password: Option<String>) -> Remote
         DEFAULT_PASSWORD.to_string()
     };
 
-    let tls_config = if opt.disable_tls {
+    let conn_string = parse_conn_string(&conn_string);
+    let tls_enabled = configured_or_conn_string(
+        "tls-enabled",
+        "default",
+        opt.disable_tls.then_some(false),
+        conn_string.tls_enabled(),
+    );
+    let tls_config = if !tls_enabled.unwrap_or(true) {
         warn!(
-                "Using PLAIN authentication for cluster default, credentials will sent in plaintext - configure tls to disable this warning"
+                "TLS is disabled for cluster default, credentials will be sent in plaintext to HTTP services - configure tls to disable this warning"
             );
         None
     } else {
-        Some(RustTlsConfig::new(opt.tls_accept_all_certs, opt.tls_cert_path).unwrap())
+        Some(
+            RustTlsConfig::new(
+                configured_or_conn_string(
+                    "tls-accept-all-certs",
+                    "default",
+                    opt.tls_accept_all_certs.then_some(true),
+                    conn_string.accept_all_certs(),
+                )
+                .unwrap_or(false),
+                opt.tls_cert_path,
+                None,
+                None,
+            )
+            .unwrap(),
+        )
     };
-    let (cluster_type, hostnames) =
-        validate_hostnames(conn_string.split(',').map(|v| v.to_owned()).collect());
+    let cluster_type = conn_string.cluster_type();
+    let timeouts = conn_string.timeouts();
     RemoteCluster::new(
         RemoteClusterResources {
-            hostnames,
+            conn_string,
             username,
-            password: rpassword,
+            password: rpassword.into(),
+            sasl_mechanism: None,
+            proxy: None,
             active_bucket: opt.bucket,
             active_scope: opt.scope,
             active_collection: opt.collection,
             display_name: opt.display_name,
         },
         tls_config,
-        ClusterTimeouts::default(),
+        timeouts,
         None,
         None,
         DEFAULT_KV_BATCH_SIZE,
@@ -388,6 +370,43 @@ fn remote_cluster_from_opts(opt: CliOptions, password: Option<String>) -> Remote
     )
 }
 
+fn parse_conn_string(conn_string: &str) -> ConnectionString {
+    match ConnectionString::parse(conn_string) {
+        Ok(c) => c,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// Settings given in the config or as flags take precedence over those in the connection string, as
+// timeouts do, with a warning when the two disagree.
+fn configured_or_conn_string(
+    setting: &str,
+    identifier: &str,
+    configured: Option<bool>,
+    conn_string: Option<bool>,
+) -> Option<bool> {
+    if configured.is_some() && conn_string.is_some() && configured != conn_string {
+        warn!(
+            "{} of cluster {} overrides the connection string, which disagrees with it",
+            setting, identifier
+        );
+    }
+    configured.or(conn_string)
+}
+
+fn parse_proxy(proxy: Option<String>) -> Option<ProxyConfig> {
+    match proxy.map(ProxyConfig::new).transpose() {
+        Ok(p) => p,
+        Err(e) => {
+            error!("{}", e.expanded_message());
+            std::process::exit(1);
+        }
+    }
+}
+
 fn maybe_write_config_file(opt: CliOptions, password: Option<String>) -> PathBuf {
     let identifier = if let Some(c) = opt.cluster {
         println!("Using {} as cluster identifier", c);
@@ -411,13 +430,7 @@ fn maybe_write_config_file(opt: CliOptions, password: Option<String>) -> PathBuf
             .expect("Failed to read user input");
         answer.trim().to_string()
     };
-    validate_hostnames(
-        conn_string
-            .clone()
-            .split(',')
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>(),
-    );
+    parse_conn_string(&conn_string);
     let username = if let Some(user) = opt.username {
         println!("Using {} as username", &user);
         Some(user)
@@ -470,7 +483,11 @@ fn maybe_write_config_file(opt: CliOptions, password: Option<String>) -> PathBuf
     .default_bucket(bucket)
     .default_scope(scope)
     .default_collection(collection)
-    .tls_config(ClusterTlsConfig::new(!opt.disable_tls, None, false));
+    .tls_config(ClusterTlsConfig::new(
+        opt.disable_tls.then_some(false),
+        None,
+        None,
+    ));
 
     let config = ShellConfig::new_from_clusters(vec![config_builder.build()], vec![]);
     let mut to_write_to = path.clone();
@@ -574,7 +591,7 @@ fn make_state(
                         username = user;
                     }
                     if let Some(pass) = password.clone() {
-                        cpassword = pass;
+                        cpassword = pass.into();
                     }
                     if let Some(bucket) = opt.bucket.clone() {
                         default_bucket = Some(bucket);
@@ -592,7 +609,7 @@ fn make_state(
                     username = user;
                 }
                 if let Some(pass) = password.clone() {
-                    cpassword = pass;
+                    cpassword = pass.into();
                 }
                 if let Some(bucket) = opt.bucket.clone() {
                     default_bucket = Some(bucket);
@@ -605,25 +622,47 @@ fn make_state(
                 }
             }
 
+            // Timeouts set in the config take precedence over those in the connection string.
+            let conn_string = parse_conn_string(v.conn_string());
             let timeouts = v.timeouts();
-            let data_timeout = match timeouts.data_timeout() {
-                Some(t) => t.to_owned(),
+            let data_timeout = match timeouts
+                .data_timeout()
+                .copied()
+                .or(conn_string.kv_timeout())
+            {
+                Some(t) => t,
                 None => DEFAULT_DATA_TIMEOUT,
             };
-            let query_timeout = match timeouts.query_timeout() {
-                Some(t) => t.to_owned(),
+            let query_timeout = match timeouts
+                .query_timeout()
+                .copied()
+                .or(conn_string.query_timeout())
+            {
+                Some(t) => t,
                 None => DEFAULT_QUERY_TIMEOUT,
             };
-            let analytics_timeout = match timeouts.analytics_timeout() {
-                Some(t) => t.to_owned(),
+            let analytics_timeout = match timeouts
+                .analytics_timeout()
+                .copied()
+                .or(conn_string.analytics_timeout())
+            {
+                Some(t) => t,
                 None => DEFAULT_ANALYTICS_TIMEOUT,
             };
-            let search_timeout = match timeouts.search_timeout() {
-                Some(t) => t.to_owned(),
+            let search_timeout = match timeouts
+                .search_timeout()
+                .copied()
+                .or(conn_string.search_timeout())
+            {
+                Some(t) => t,
                 None => DEFAULT_SEARCH_TIMEOUT,
             };
-            let management_timeout = match timeouts.management_timeout() {
-                Some(t) => t.to_owned(),
+            let management_timeout = match timeouts
+                .management_timeout()
+                .copied()
+                .or(conn_string.management_timeout())
+            {
+                Some(t) => t,
                 None => DEFAULT_MANAGEMENT_TIMEOUT,
             };
             let transaction_timeout = match timeouts.transaction_timeout() {
@@ -635,23 +674,41 @@ fn make_state(
                 None => DEFAULT_KV_BATCH_SIZE,
             };
 
-            let (cluster_type, hostnames) = validate_hostnames(
-                v.conn_string()
-                    .split(',')
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>(),
-            );
+            let cluster_type = conn_string.cluster_type();
             let cluster_tls_config = v.tls().clone();
-            let tls_config = if cluster_tls_config.enabled() {
-                Some(RustTlsConfig::try_from(cluster_tls_config).unwrap())
+            let tls_enabled = configured_or_conn_string(
+                "tls-enabled",
+                v.identifier(),
+                cluster_tls_config.enabled(),
+                conn_string.tls_enabled(),
+            );
+            let tls_config = if tls_enabled.unwrap_or(true) {
+                Some(
+                    RustTlsConfig::new(
+                        configured_or_conn_string(
+                            "tls-accept-all-certs",
+                            v.identifier(),
+                            cluster_tls_config.accept_all_certs(),
+                            conn_string.accept_all_certs(),
+                        )
+                        .unwrap_or(false),
+                        cluster_tls_config.cert_path().clone(),
+                        cluster_tls_config.client_cert_path().clone(),
+                        cluster_tls_config.client_key_path().clone(),
+                    )
+                    .unwrap(),
+                )
             } else {
                 None
             };
+            let tls_disabled = tls_config.is_none();
             let cluster = RemoteCluster::new(
                 RemoteClusterResources {
-                    hostnames,
+                    conn_string,
                     username,
                     password: cpassword,
+                    sasl_mechanism: v.sasl_mechanism(),
+                    proxy: parse_proxy(v.proxy()),
                     active_bucket: default_bucket,
                     active_scope: scope,
                     active_collection: collection,
@@ -671,9 +728,9 @@ fn make_state(
                 kv_batch_size,
                 v.cluster_type().unwrap_or(cluster_type),
             );
-            if !v.tls().clone().enabled() {
+            if tls_disabled {
                 warn!(
-                    "Using PLAIN authentication for cluster {}, credentials will sent in plaintext - configure tls to disable this warning",
+                    "TLS is disabled for cluster {}, credentials will be sent in plaintext to HTTP services - configure tls to disable this warning",
                     name.clone()
                 );
             }
@@ -686,6 +743,9 @@ fn make_state(
             };
             let name = c.identifier();
             let api_endpoint = c.api_endpoint().unwrap_or(CLOUD_URL.to_string());
+            let ip_lookup_endpoint = c
+                .ip_lookup_endpoint()
+                .unwrap_or(DEFAULT_IP_LOOKUP_ENDPOINT.to_string());
 
             let plane = RemoteCapellaOrganization::new(
                 c.secret_key(),
@@ -693,6 +753,8 @@ fn make_state(
                 management_timeout,
                 c.default_project(),
                 api_endpoint,
+                ip_lookup_endpoint,
+                parse_proxy(c.proxy()),
             );
 
             if active_capella_org.is_none() {
@@ -739,14 +801,36 @@ fn merge_couchbase_delta(context: &mut EngineState, state: Arc<Mutex<State>>) {
     let delta = {
         let mut working_set = StateWorkingSet::new(context);
         working_set.add_decl(Box::new(AllowIP::new(state.clone())));
+        working_set.add_decl(Box::new(AllowIPDrop::new(state.clone())));
+        working_set.add_decl(Box::new(AllowIPList::new(state.clone())));
         working_set.add_decl(Box::new(Analytics::new(state.clone())));
         working_set.add_decl(Box::new(AnalyticsBuckets::new(state.clone())));
         working_set.add_decl(Box::new(AnalyticsDatasets::new(state.clone())));
+        working_set.add_decl(Box::new(AnalyticsDatasetsCreateExternal::new(
+            state.clone(),
+        )));
         working_set.add_decl(Box::new(AnalyticsDataverses::new(state.clone())));
         working_set.add_decl(Box::new(AnalyticsIndexes::new(state.clone())));
         working_set.add_decl(Box::new(AnalyticsLinks::new(state.clone())));
+        working_set.add_decl(Box::new(AnalyticsLinksAlter::new(state.clone())));
+        working_set.add_decl(Box::new(AnalyticsLinksCreate::new(state.clone())));
+        working_set.add_decl(Box::new(AnalyticsLinksDrop::new(state.clone())));
         working_set.add_decl(Box::new(AnalyticsPendingMutations::new(state.clone())));
+        working_set.add_decl(Box::new(AppServices::new(state.clone())));
+        working_set.add_decl(Box::new(AppServicesCreate::new(state.clone())));
+        working_set.add_decl(Box::new(AppServicesDrop::new(state.clone())));
+        working_set.add_decl(Box::new(AppServicesOff::new(state.clone())));
+        working_set.add_decl(Box::new(AppServicesOn::new(state.clone())));
         working_set.add_decl(Box::new(Ask::new(state.clone())));
+        working_set.add_decl(Box::new(AskQuery::new(state.clone())));
+        working_set.add_decl(Box::new(AskSession::new(state.clone())));
+        working_set.add_decl(Box::new(AskSessionEnd::new(state.clone())));
+        working_set.add_decl(Box::new(AskSessionStart::new(state.clone())));
+        working_set.add_decl(Box::new(Backups::new(state.clone())));
+        working_set.add_decl(Box::new(BackupsCreate::new(state.clone())));
+        working_set.add_decl(Box::new(BackupsRestore::new(state.clone())));
+        working_set.add_decl(Box::new(BackupsSchedule::new(state.clone())));
+        working_set.add_decl(Box::new(BackupsScheduleUpdate::new(state.clone())));
         working_set.add_decl(Box::new(Buckets::new(state.clone())));
         working_set.add_decl(Box::new(BucketsConfig::new(state.clone())));
         working_set.add_decl(Box::new(BucketsCreate::new(state.clone())));
@@ -764,6 +848,12 @@ fn merge_couchbase_delta(context: &mut EngineState, state: Arc<Mutex<State>>) {
         working_set.add_decl(Box::new(ClustersCreate::new(state.clone())));
         working_set.add_decl(Box::new(ClustersDrop::new(state.clone())));
         working_set.add_decl(Box::new(ClustersGet::new(state.clone())));
+        working_set.add_decl(Box::new(ClustersNetworkPeers::new(state.clone())));
+        working_set.add_decl(Box::new(ClustersOff::new(state.clone())));
+        working_set.add_decl(Box::new(ClustersOn::new(state.clone())));
+        working_set.add_decl(Box::new(ClustersPrivateEndpoints::new(state.clone())));
+        working_set.add_decl(Box::new(ClustersSchedule::new(state.clone())));
+        working_set.add_decl(Box::new(ClustersUpdate::new(state.clone())));
         working_set.add_decl(Box::new(Collections::new(state.clone())));
         working_set.add_decl(Box::new(CollectionsCreate::new(state.clone())));
         working_set.add_decl(Box::new(CollectionsDrop::new(state.clone())));
@@ -771,11 +861,16 @@ fn merge_couchbase_delta(context: &mut EngineState, state: Arc<Mutex<State>>) {
         working_set.add_decl(Box::new(ColumnarClusters::new(state.clone())));
         working_set.add_decl(Box::new(ColumnarClustersCreate::new(state.clone())));
         working_set.add_decl(Box::new(ColumnarClustersDrop::new(state.clone())));
+        working_set.add_decl(Box::new(ColumnarCollectionsCreateExternal::new(
+            state.clone(),
+        )));
         working_set.add_decl(Box::new(ColumnarDatabases::new(state.clone())));
         working_set.add_decl(Box::new(ColumnarQuery::new(state.clone())));
         working_set.add_decl(Box::new(Credentials::new(state.clone())));
         working_set.add_decl(Box::new(CredentialsCreate::new(state.clone())));
         working_set.add_decl(Box::new(CredentialsDrop::new(state.clone())));
+        working_set.add_decl(Box::new(CredentialsRotatePassword::new(state.clone())));
+        working_set.add_decl(Box::new(CredentialsUpdate::new(state.clone())));
         working_set.add_decl(Box::new(Doc));
         working_set.add_decl(Box::new(DocGet::new(state.clone())));
         working_set.add_decl(Box::new(DocImport::new(state.clone())));
@@ -783,6 +878,7 @@ fn merge_couchbase_delta(context: &mut EngineState, state: Arc<Mutex<State>>) {
         working_set.add_decl(Box::new(DocReplace::new(state.clone())));
         working_set.add_decl(Box::new(DocRemove::new(state.clone())));
         working_set.add_decl(Box::new(DocUpsert::new(state.clone())));
+        working_set.add_decl(Box::new(Exit::new(state.clone())));
         working_set.add_decl(Box::new(HealthCheck::new(state.clone())));
         working_set.add_decl(Box::new(Help));
         working_set.add_decl(Box::new(FakeData::new(state.clone())));
@@ -802,7 +898,12 @@ fn merge_couchbase_delta(context: &mut EngineState, state: Arc<Mutex<State>>) {
         working_set.add_decl(Box::new(Search::new(state.clone())));
         working_set.add_decl(Box::new(SubDocGet::new(state.clone())));
         working_set.add_decl(Box::new(Transactions));
+        working_set.add_decl(Box::new(TransactionsBegin::new(state.clone())));
+        working_set.add_decl(Box::new(TransactionsCleanup::new(state.clone())));
+        working_set.add_decl(Box::new(TransactionsCommit::new(state.clone())));
         working_set.add_decl(Box::new(TransactionsListAtrs::new(state.clone())));
+        working_set.add_decl(Box::new(TransactionsRollback::new(state.clone())));
+        working_set.add_decl(Box::new(TransactionsStatus::new(state.clone())));
         working_set.add_decl(Box::new(Tutorial::new(state.clone())));
         working_set.add_decl(Box::new(TutorialNext::new(state.clone())));
         working_set.add_decl(Box::new(TutorialPage::new(state.clone())));
@@ -814,6 +915,7 @@ fn merge_couchbase_delta(context: &mut EngineState, state: Arc<Mutex<State>>) {
         working_set.add_decl(Box::new(UseProject::new(state.clone())));
         working_set.add_decl(Box::new(UseScope::new(state.clone())));
         working_set.add_decl(Box::new(UseTimeouts::new(state.clone())));
+        working_set.add_decl(Box::new(UseTrace::new(state.clone())));
         working_set.add_decl(Box::new(Users::new(state.clone())));
         working_set.add_decl(Box::new(UsersGet::new(state.clone())));
         working_set.add_decl(Box::new(UsersDrop::new(state.clone())));
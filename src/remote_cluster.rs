@@ -1,12 +1,39 @@
-use crate::client::{Client, RustTlsConfig, CAPELLA_SRV_SUFFIX};
+use crate::client::{
+    Client, ClientError, KvClient, ProxyConfig, RustTlsConfig, SaslMechanism,
+    SlowOperationThreshold, CAPELLA_SRV_SUFFIX,
+};
+use crate::config::Secret;
+use crate::connection_string::ConnectionString;
 use crate::remote_cluster::RemoteClusterType::Provisioned;
 use crate::{
     DEFAULT_ANALYTICS_TIMEOUT, DEFAULT_DATA_TIMEOUT, DEFAULT_MANAGEMENT_TIMEOUT,
     DEFAULT_QUERY_TIMEOUT, DEFAULT_SEARCH_TIMEOUT, DEFAULT_TRANSACTION_TIMEOUT,
 };
+use lazy_static::lazy_static;
+use log::debug;
+use nu_protocol::Signals;
 use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Add;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+use tokio::time::Instant;
+
+// Pooled KV clients are closed once they have not been used for this long.
+const KV_CLIENT_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const KV_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+lazy_static! {
+    // Commands each run on a runtime of their own which is dropped when they finish, so pooled KV
+    // connections are made on this runtime instead to keep their read and write tasks alive.
+    static ref KV_RUNTIME: Runtime = Builder::new_multi_thread()
+        .worker_threads(2)
+        .thread_name("cbsh-kv")
+        .enable_all()
+        .build()
+        .unwrap();
+}
 
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq)]
 pub enum RemoteClusterType {
@@ -46,9 +73,11 @@ impl From<RemoteClusterType> for String {
 }
 
 pub struct RemoteClusterResources {
-    pub hostnames: Vec<String>,
+    pub conn_string: ConnectionString,
     pub username: String,
-    pub password: String,
+    pub password: Secret,
+    pub sasl_mechanism: Option<SaslMechanism>,
+    pub proxy: Option<ProxyConfig>,
     pub active_bucket: Option<String>,
     pub active_scope: Option<String>,
     pub active_collection: Option<String>,
@@ -56,9 +85,11 @@ pub struct RemoteClusterResources {
 }
 
 pub struct RemoteCluster {
-    hostnames: Vec<String>,
+    conn_string: ConnectionString,
     username: String,
-    password: String,
+    password: Secret,
+    sasl_mechanism: Option<SaslMechanism>,
+    proxy: Option<ProxyConfig>,
     cluster: Mutex<Option<Arc<Client>>>,
     active_bucket: Mutex<Option<String>>,
     active_scope: Mutex<Option<String>>,
@@ -70,6 +101,13 @@ pub struct RemoteCluster {
     kv_batch_size: u32,
     cluster_type: RemoteClusterType,
     display_name: Option<String>,
+    kv_clients: Mutex<HashMap<String, PooledKvClient>>,
+    slow_operation_threshold: SlowOperationThreshold,
+}
+
+struct PooledKvClient {
+    client: Arc<KvClient>,
+    last_used: Instant,
 }
 
 impl RemoteCluster {
@@ -85,9 +123,11 @@ impl RemoteCluster {
     ) -> Self {
         Self {
             cluster: Mutex::new(None),
-            hostnames: resources.hostnames,
+            conn_string: resources.conn_string,
             username: resources.username,
             password: resources.password,
+            sasl_mechanism: resources.sasl_mechanism,
+            proxy: resources.proxy,
             active_bucket: Mutex::new(resources.active_bucket),
             active_scope: Mutex::new(resources.active_scope),
             active_collection: Mutex::new(resources.active_collection),
@@ -98,20 +138,89 @@ impl RemoteCluster {
             kv_batch_size,
             cluster_type,
             display_name: resources.display_name,
+            kv_clients: Mutex::new(HashMap::new()),
+            slow_operation_threshold: SlowOperationThreshold::default(),
         }
     }
 
-    pub fn cluster(&self) -> Arc<Client> {
+    // The client is only cached once the password could be resolved, so that a secret which is
+    // unavailable now is read again by the next command.
+    pub fn cluster(&self) -> Result<Arc<Client>, ClientError> {
         let mut c = self.cluster.lock().unwrap();
         if c.is_none() {
+            let password = self.password.resolve()?;
             *c = Some(Arc::new(Client::new(
-                self.hostnames.clone(),
+                self.conn_string.hostnames().clone(),
                 self.username.clone(),
-                self.password.clone(),
+                password,
                 self.tls_config.clone(),
+                self.sasl_mechanism,
+                self.conn_string.network(),
+                self.proxy.clone(),
+                self.slow_operation_threshold.clone(),
             )));
         }
-        c.as_ref().unwrap().clone()
+        Ok(c.as_ref().unwrap().clone())
+    }
+
+    // Returns a KV client for the bucket, reusing the one from an earlier command if it is still
+    // healthy rather than fetching the bucket config and connecting to every node again.
+    pub async fn key_value_client(
+        &self,
+        bucket: String,
+        deadline: Instant,
+        signals: Signals,
+    ) -> Result<Arc<KvClient>, ClientError> {
+        let pooled = {
+            let mut clients = self.kv_clients.lock().unwrap();
+            clients.retain(|_, c| c.last_used.elapsed() < KV_CLIENT_IDLE_TIMEOUT);
+            clients.get(&bucket).map(|c| c.client.clone())
+        };
+
+        if let Some(client) = pooled {
+            let check_deadline = deadline.min(Instant::now().add(KV_HEALTH_CHECK_TIMEOUT));
+            if client.check_health(check_deadline).await {
+                self.pool_kv_client(bucket, client.clone());
+                return Ok(client);
+            }
+            debug!(
+                "Pooled kv client for {} is unhealthy, reconnecting",
+                &bucket
+            );
+            self.kv_clients.lock().unwrap().remove(&bucket);
+        }
+
+        let cluster = self.cluster()?;
+        let connect_bucket = bucket.clone();
+        let client = KV_RUNTIME
+            .spawn(async move {
+                cluster
+                    .key_value_client(connect_bucket, deadline, signals)
+                    .await
+            })
+            .await
+            .map_err(|e| ClientError::RequestFailed {
+                reason: Some(e.to_string()),
+                key: None,
+            })??;
+
+        let client = Arc::new(client);
+        self.pool_kv_client(bucket, client.clone());
+        Ok(client)
+    }
+
+    fn pool_kv_client(&self, bucket: String, client: Arc<KvClient>) {
+        self.kv_clients.lock().unwrap().insert(
+            bucket,
+            PooledKvClient {
+                client,
+                last_used: Instant::now(),
+            },
+        );
+    }
+
+    pub fn reset_kv_clients(&self) {
+        self.kv_clients.lock().unwrap().clear();
     }
 
     pub fn active_bucket(&self) -> Option<String> {
@@ -121,6 +230,7 @@ impl RemoteCluster {
     pub fn set_active_bucket(&self, name: String) {
         let mut active = self.active_bucket.lock().unwrap();
         *active = Some(name);
+        self.reset_kv_clients();
     }
 
     pub fn active_scope(&self) -> Option<String> {
@@ -146,18 +256,35 @@ impl RemoteCluster {
         if c.is_some() {
             *c = None;
         }
+        self.reset_kv_clients();
     }
 
     pub fn hostnames(&self) -> &Vec<String> {
-        &self.hostnames
+        self.conn_string.hostnames()
+    }
+
+    pub fn conn_string(&self) -> &ConnectionString {
+        &self.conn_string
     }
 
     pub fn username(&self) -> &str {
         self.username.as_str()
     }
 
-    pub fn password(&self) -> &str {
-        self.password.as_str()
+    pub fn password(&self) -> Result<String, ClientError> {
+        self.password.resolve()
+    }
+
+    pub fn password_secret(&self) -> &Secret {
+        &self.password
+    }
+
+    pub fn sasl_mechanism(&self) -> Option<SaslMechanism> {
+        self.sasl_mechanism
+    }
+
+    pub fn proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_ref()
     }
 
     pub fn tls_config(&self) -> &Option<RustTlsConfig> {
@@ -197,6 +324,11 @@ impl RemoteCluster {
     pub fn display_name(&self) -> Option<String> {
         self.display_name.clone()
     }
+
+    // Called by the state before any clients are created, so that they all use its threshold.
+    pub(crate) fn share_slow_operation_threshold(&mut self, threshold: SlowOperationThreshold) {
+        self.slow_operation_threshold = threshold;
+    }
 }
 
 #[derive(Debug, Clone)]
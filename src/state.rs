@@ -1,9 +1,13 @@
-use crate::client::{CapellaClient, Endpoint};
+use crate::client::{
+    CapellaClient, ChatMessage, ChatRole, ClientError, Endpoint, ProxyConfig,
+    SlowOperationThreshold,
+};
 
 use crate::cli::{
     embed_model_missing, generic_error, no_active_project_error, no_llm_configured,
-    organization_not_registered,
+    organization_not_registered, KvTransaction,
 };
+use crate::config::Secret;
 use crate::tutorial::Tutorial;
 use crate::RemoteCluster;
 use lazy_static::__Deref;
@@ -13,12 +17,16 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Instant;
 use std::{collections::HashMap, time::Duration};
 
 #[derive(Debug, Clone)]
 pub struct TransactionState {
     id: String,
     endpoint: Endpoint,
+    cluster: String,
+    started: Instant,
+    timeout: Duration,
 }
 
 impl TransactionState {
@@ -29,6 +37,87 @@ impl TransactionState {
     pub fn endpoint(&self) -> Endpoint {
         self.endpoint.clone()
     }
+
+    pub fn cluster(&self) -> String {
+        self.cluster.clone()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.timeout.saturating_sub(self.started.elapsed())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatSession {
+    id: String,
+    messages: Vec<ChatMessage>,
+    keyspace: Option<ChatSessionKeyspace>,
+}
+
+impl ChatSession {
+    pub fn new(
+        id: String,
+        messages: Vec<ChatMessage>,
+        keyspace: impl Into<Option<ChatSessionKeyspace>>,
+    ) -> Self {
+        Self {
+            id,
+            messages,
+            keyspace: keyspace.into(),
+        }
+    }
+
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn messages(&self) -> &Vec<ChatMessage> {
+        &self.messages
+    }
+
+    pub fn keyspace(&self) -> Option<ChatSessionKeyspace> {
+        self.keyspace.clone()
+    }
+}
+
+// Where the history of a chat session is persisted, the session id is used as the doc id.
+#[derive(Debug, Clone)]
+pub struct ChatSessionKeyspace {
+    cluster: String,
+    bucket: String,
+    scope: String,
+    collection: String,
+}
+
+impl ChatSessionKeyspace {
+    pub fn new(cluster: String, bucket: String, scope: String, collection: String) -> Self {
+        Self {
+            cluster,
+            bucket,
+            scope,
+            collection,
+        }
+    }
+
+    pub fn cluster(&self) -> String {
+        self.cluster.clone()
+    }
+
+    pub fn bucket(&self) -> String {
+        self.bucket.clone()
+    }
+
+    pub fn scope(&self) -> String {
+        self.scope.clone()
+    }
+
+    pub fn collection(&self) -> String {
+        self.collection.clone()
+    }
 }
 
 #[derive(Debug)]
@@ -96,11 +185,14 @@ pub struct State {
     active_transaction: Mutex<Option<TransactionState>>,
     llms: HashMap<String, Llm>,
     active_llm: Mutex<Option<String>>,
+    chat_session: Mutex<Option<ChatSession>>,
+    kv_transaction: Mutex<Option<KvTransaction>>,
+    slow_operation_threshold: SlowOperationThreshold,
 }
 
 impl State {
     pub fn new(
-        clusters: HashMap<String, RemoteCluster>,
+        mut clusters: HashMap<String, RemoteCluster>,
         active: String,
         config_path: Option<PathBuf>,
         capella_orgs: HashMap<String, RemoteCapellaOrganization>,
@@ -109,6 +201,11 @@ impl State {
         llms: HashMap<String, Llm>,
         active_llm: Option<String>,
     ) -> Self {
+        let slow_operation_threshold = SlowOperationThreshold::default();
+        for cluster in clusters.values_mut() {
+            cluster.share_slow_operation_threshold(slow_operation_threshold.clone());
+        }
+
         let state = Self {
             active: Mutex::new(active.clone()),
             clusters,
@@ -120,6 +217,9 @@ impl State {
             active_transaction: Mutex::new(None),
             llms,
             active_llm: Mutex::new(active_llm),
+            chat_session: Mutex::new(None),
+            kv_transaction: Mutex::new(None),
+            slow_operation_threshold,
         };
         if !active.is_empty() {
             state.set_active(active).unwrap();
@@ -127,7 +227,11 @@ impl State {
         state
     }
 
-    pub fn add_cluster(&mut self, alias: String, cluster: RemoteCluster) -> Result<(), ShellError> {
+    pub fn add_cluster(
+        &mut self,
+        alias: String,
+        mut cluster: RemoteCluster,
+    ) -> Result<(), ShellError> {
         if self.clusters.contains_key(alias.as_str()) {
             return Err(generic_error(
                 format!("Identifier {} is already registered to a cluster", alias),
@@ -135,6 +239,7 @@ impl State {
                 None
             ));
         }
+        cluster.share_slow_operation_threshold(self.slow_operation_threshold.clone());
         self.clusters.insert(alias, cluster);
         Ok(())
     }
@@ -286,15 +391,34 @@ impl State {
         self.active_transaction.lock().unwrap().clone()
     }
 
-    pub fn start_transaction(&mut self, id: String, endpoint: Endpoint) -> Result<(), ShellError> {
+    pub fn start_transaction(
+        &mut self,
+        id: String,
+        endpoint: Endpoint,
+        cluster: String,
+        timeout: Duration,
+    ) -> Result<(), ShellError> {
         {
             let mut guard = self.active_transaction.lock().unwrap();
-            *guard = Some(TransactionState { id, endpoint });
+            *guard = Some(TransactionState {
+                id,
+                endpoint,
+                cluster,
+                started: Instant::now(),
+                timeout,
+            });
         }
 
         Ok(())
     }
 
+    // The id of the query or KV transaction open in the shell, if there is one.
+    pub(crate) fn open_transaction_id(&self) -> Option<String> {
+        self.active_transaction()
+            .map(|t| t.id())
+            .or_else(|| self.kv_transaction().map(|t| t.id))
+    }
+
     pub fn end_transaction(&mut self) {
         {
             let mut guard = self.active_transaction.lock().unwrap();
@@ -302,6 +426,57 @@ impl State {
         }
     }
 
+    pub fn chat_session(&self) -> Option<ChatSession> {
+        self.chat_session.lock().unwrap().clone()
+    }
+
+    pub fn start_chat_session(&self, session: ChatSession) {
+        let mut guard = self.chat_session.lock().unwrap();
+        *guard = Some(session);
+    }
+
+    pub fn end_chat_session(&self) -> Option<ChatSession> {
+        let mut guard = self.chat_session.lock().unwrap();
+        guard.take()
+    }
+
+    // Records a question and its answer in the active chat session, if there is one, returning the
+    // updated session.
+    pub fn add_chat_exchange(&self, question: String, answer: String) -> Option<ChatSession> {
+        let mut guard = self.chat_session.lock().unwrap();
+        if let Some(session) = guard.as_mut() {
+            session
+                .messages
+                .push(ChatMessage::new(ChatRole::User, question));
+            session
+                .messages
+                .push(ChatMessage::new(ChatRole::Assistant, answer));
+        }
+        guard.clone()
+    }
+
+    pub(crate) fn kv_transaction(&self) -> Option<KvTransaction> {
+        self.kv_transaction.lock().unwrap().clone()
+    }
+
+    pub(crate) fn update_kv_transaction(&self, transaction: KvTransaction) {
+        let mut guard = self.kv_transaction.lock().unwrap();
+        *guard = Some(transaction);
+    }
+
+    pub(crate) fn end_kv_transaction(&self) -> Option<KvTransaction> {
+        let mut guard = self.kv_transaction.lock().unwrap();
+        guard.take()
+    }
+
+    pub fn slow_operation_threshold(&self) -> Option<Duration> {
+        self.slow_operation_threshold.get()
+    }
+
+    pub fn set_slow_operation_threshold(&self, threshold: Option<Duration>) {
+        self.slow_operation_threshold.set(threshold);
+    }
+
     pub fn active_llm_id(&self) -> Option<String> {
         self.active_llm.lock().unwrap().clone()
     }
@@ -337,21 +512,25 @@ impl State {
 }
 
 pub struct RemoteCapellaOrganization {
-    secret_key: String,
-    access_key: String,
+    secret_key: Secret,
+    access_key: Secret,
     client: Mutex<Option<Arc<CapellaClient>>>,
     timeout: Duration,
     default_project: Option<String>,
     api_endpoint: String,
+    ip_lookup_endpoint: String,
+    proxy: Option<ProxyConfig>,
 }
 
 impl RemoteCapellaOrganization {
     pub fn new(
-        secret_key: String,
-        access_key: String,
+        secret_key: Secret,
+        access_key: Secret,
         timeout: Duration,
         default_project: Option<String>,
         api_endpoint: String,
+        ip_lookup_endpoint: String,
+        proxy: Option<ProxyConfig>,
     ) -> Self {
         Self {
             secret_key,
@@ -360,20 +539,24 @@ impl RemoteCapellaOrganization {
             timeout,
             default_project,
             api_endpoint,
+            ip_lookup_endpoint,
+            proxy,
         }
     }
 
-    pub fn client(&self) -> Arc<CapellaClient> {
+    // As with cluster passwords the client is only cached once both keys could be resolved.
+    pub fn client(&self) -> Result<Arc<CapellaClient>, ClientError> {
         let mut c = self.client.lock().unwrap();
         if c.is_none() {
             *c = Some(Arc::new(CapellaClient::new(
-                self.secret_key.clone(),
-                self.access_key.clone(),
+                self.secret_key.resolve()?,
+                self.access_key.resolve()?,
                 self.api_endpoint.clone(),
                 self.timeout,
+                self.proxy.clone(),
             )));
         }
-        c.as_ref().unwrap().clone()
+        Ok(c.as_ref().unwrap().clone())
     }
 
     pub fn timeout(&self) -> Duration {
@@ -383,4 +566,12 @@ impl RemoteCapellaOrganization {
     pub fn default_project(&self) -> Option<String> {
         self.default_project.clone()
     }
+
+    pub fn ip_lookup_endpoint(&self) -> String {
+        self.ip_lookup_endpoint.clone()
+    }
+
+    pub fn proxy(&self) -> Option<ProxyConfig> {
+        self.proxy.clone()
+    }
 }